@@ -34,7 +34,7 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     let mut h1s: HashMap<String, Vec<String>> = HashMap::new();
     let mut content_hashes: HashMap<u64, Vec<String>> = HashMap::new();
 
-    for page in &index.pages {
+    for page in index.pages.iter().filter(|p| !p.is_error_page()) {
         let html = page.parse_html();
 
         // Title
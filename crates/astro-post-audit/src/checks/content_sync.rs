@@ -1,17 +1,47 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 
+use url::Url;
 use walkdir::WalkDir;
 
 use crate::config::Config;
+use crate::datetime;
 use crate::discovery::SiteIndex;
+use crate::feed;
+use crate::normalize;
 use crate::report::{Confidence, Finding, Level};
 
 const CONTENT_EXTENSIONS: &[&str] = &["md", "mdx", "markdown", "mdoc"];
 
+/// Frontmatter fields the sync checks look at.
+#[derive(Default)]
+struct Frontmatter {
+    slug: Option<String>,
+    draft: bool,
+    /// Raw `pubDate` / `publishDate` (else `date`) value and its Unix time.
+    date: Option<(String, i64)>,
+    /// The date comes from a bare `date:`, which may be an updated or event
+    /// date rather than the publication date.
+    date_is_fallback: bool,
+}
+
+/// An entry that should not be live: a draft or a future publication date.
+struct Unpublished {
+    file: String,
+    collection: Option<String>,
+    slug: Vec<String>,
+    /// The future date, or None for a draft.
+    date: Option<String>,
+    /// The date is a bare `date:` field.
+    date_is_fallback: bool,
+}
+
 /// Cross-check `src/content/` collection items against generated pages. Because
 /// the audit runs post-build, content files that were never rendered (e.g. a
-/// broken `getStaticPaths` filter or slug mapping) can be surfaced.
+/// broken `getStaticPaths` filter or slug mapping) can be surfaced. With
+/// `check_drafts`, the reverse is checked too: items marked `draft: true` or
+/// dated in the future that still produced a page, a sitemap entry or a feed
+/// item.
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     if !config.content_sync.enabled {
         return Vec::new();
@@ -37,6 +67,8 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         .collect();
 
     let mut findings = Vec::new();
+    let mut unpublished = Vec::new();
+    let now = datetime::now();
 
     for entry in WalkDir::new(&content_dir)
         .into_iter()
@@ -70,8 +102,37 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
             continue;
         }
 
+        let frontmatter = read_frontmatter(path);
         // A frontmatter `slug:` overrides the filename-derived slug.
-        let slug = frontmatter_slug(path).unwrap_or(stem);
+        let slug = frontmatter.slug.unwrap_or(stem);
+        let future = frontmatter.date.filter(|(_, ts)| *ts > now);
+        if frontmatter.draft || future.is_some() {
+            // Not rendering these is correct; rendering them is the problem.
+            if config.content_sync.check_drafts {
+                let collection = path
+                    .strip_prefix(&content_dir)
+                    .ok()
+                    .and_then(Path::parent)
+                    .and_then(|p| p.components().next())
+                    .map(|c| c.as_os_str().to_string_lossy().to_lowercase());
+                unpublished.push(Unpublished {
+                    file: rel.to_string_lossy().replace('\\', "/"),
+                    collection,
+                    slug: slug
+                        .split('/')
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_lowercase)
+                        .collect(),
+                    date: if frontmatter.draft {
+                        None
+                    } else {
+                        future.map(|(raw, _)| raw)
+                    },
+                    date_is_fallback: !frontmatter.draft && frontmatter.date_is_fallback,
+                });
+            }
+            continue;
+        }
 
         let matched = if slug.contains('/') {
             // Multi-segment slug: require all segments to be present.
@@ -101,28 +162,186 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         }
     }
 
+    if !unpublished.is_empty() {
+        findings.extend(check_unpublished(index, config, &unpublished));
+    }
+
+    findings
+}
+
+/// Where drafts and future entries ended up: one finding per entry and
+/// route, naming the page, sitemap and feed that expose it.
+fn check_unpublished(index: &SiteIndex, config: &Config, entries: &[Unpublished]) -> Vec<Finding> {
+    let route_of = |url: &str| {
+        let path = Url::parse(url).map_or_else(|_| url.to_string(), |u| u.path().to_string());
+        normalize::normalize_path(&path, &config.url_normalization)
+    };
+
+    // route -> places it was published (page, sitemap file, feed file)
+    let mut published: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for page in &index.pages {
+        published
+            .entry(page.route.clone())
+            .or_default()
+            .push("page".into());
+    }
+    let mut sitemap_urls: Vec<(&String, &String)> = index.sitemaps.url_sources.iter().collect();
+    sitemap_urls.sort();
+    for (url, source) in sitemap_urls {
+        let places = published.entry(route_of(url)).or_default();
+        if !places.contains(source) {
+            places.push(source.clone());
+        }
+    }
+    for feed_file in feed::discover(index) {
+        let Ok(feed) = &feed_file.feed else {
+            continue;
+        };
+        for link in feed.items.iter().filter_map(|item| item.link.as_deref()) {
+            let places = published.entry(route_of(link)).or_default();
+            if !places.contains(&feed_file.rel_path) {
+                places.push(feed_file.rel_path.clone());
+            }
+        }
+    }
+
+    let mut findings = Vec::new();
+    for entry in entries {
+        if entry.slug.is_empty() {
+            continue;
+        }
+        let matches: Vec<(&String, &Vec<String>)> = published
+            .iter()
+            .filter(|(route, _)| route_segments(route).ends_with(&entry.slug))
+            .collect();
+        // Several collections may share a slug; prefer routes under this one.
+        // A route outside the collection only shares the slug, so it may be a
+        // different page: report it at Info.
+        let in_collection: Vec<_> = matches
+            .iter()
+            .filter(|(route, _)| {
+                entry
+                    .collection
+                    .as_ref()
+                    .is_some_and(|c| route_segments(route).contains(c))
+            })
+            .copied()
+            .collect();
+        let slug_only = in_collection.is_empty();
+        let matches = if slug_only { matches } else { in_collection };
+
+        for (route, places) in matches {
+            let places = places
+                .iter()
+                .map(|p| match p.as_str() {
+                    "page" => "a page".to_string(),
+                    other => format!("'{other}'"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let (level, rule_id, what, help) = match &entry.date {
+                None => (
+                    Level::Error,
+                    "content/draft-published",
+                    "is a draft".to_string(),
+                    "The entry is marked `draft: true` but was built. Filter drafts out in `getStaticPaths` and in the sitemap and feed generators, e.g. `getCollection('blog', ({ data }) => !data.draft)`.",
+                ),
+                Some(date) => (
+                    Level::Warning,
+                    "content/future-published",
+                    format!("is dated {date}"),
+                    "The publication date is in the future, but the entry is already live. Filter on the date when building pages, the sitemap and feeds, or rebuild on the publication date.",
+                ),
+            };
+            let (level, confidence, hedge) = if slug_only {
+                (
+                    Level::Info,
+                    Confidence::Low,
+                    " (matched by slug only, outside the collection)",
+                )
+            } else if entry.date_is_fallback {
+                (level, Confidence::Low, "")
+            } else {
+                (level, Confidence::Medium, "")
+            };
+            findings.push(Finding {
+                level,
+                rule_id: rule_id.into(),
+                file: entry.file.clone(),
+                selector: String::new(),
+                message: format!(
+                    "'{}' {what} but is published at '{route}' ({places}){hedge}",
+                    entry.file
+                ),
+                help: help.into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: Some(confidence),
+            });
+        }
+    }
     findings
 }
 
-/// Read a `slug:` value from a file's leading YAML frontmatter, if present.
-fn frontmatter_slug(path: &Path) -> Option<String> {
-    let content = std::fs::read_to_string(path).ok()?;
+fn route_segments(route: &str) -> Vec<String> {
+    route
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Read `slug`, `draft` and the publication date (`pubDate` or `publishDate`,
+/// else `date`) from a file's leading YAML frontmatter, if present.
+fn read_frontmatter(path: &Path) -> Frontmatter {
+    let mut fm = Frontmatter::default();
+    let mut fallback = None;
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return fm;
+    };
     let trimmed = content.trim_start();
     if !trimmed.starts_with("---") {
-        return None;
+        return fm;
     }
     // Frontmatter is between the first two `---` fences.
     let after = &trimmed[3..];
-    let end = after.find("\n---")?;
-    let frontmatter = &after[..end];
-    for line in frontmatter.lines() {
-        let line = line.trim();
-        if let Some(rest) = line.strip_prefix("slug:") {
-            let value = rest.trim().trim_matches('"').trim_matches('\'').trim();
-            if !value.is_empty() {
-                return Some(value.trim_matches('/').to_string());
+    let Some(end) = after.find("\n---") else {
+        return fm;
+    };
+    for line in after[..end].lines() {
+        // Nested keys belong to other fields.
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim_matches('\'').trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim() {
+            "slug" => fm.slug = Some(value.trim_matches('/').to_string()),
+            "draft" => fm.draft = value == "true",
+            "pubDate" | "publishDate" if fm.date.is_none() => {
+                fm.date = parse_date(value).map(|ts| (value.to_string(), ts));
+            }
+            "date" if fallback.is_none() => {
+                fallback = parse_date(value).map(|ts| (value.to_string(), ts));
             }
+            _ => {}
         }
     }
-    None
+    if fm.date.is_none() && fallback.is_some() {
+        fm.date = fallback;
+        fm.date_is_fallback = true;
+    }
+    fm
+}
+
+/// A YAML date or timestamp. Times without an offset are taken as UTC.
+fn parse_date(value: &str) -> Option<i64> {
+    datetime::parse_w3c(value)
+        .or_else(|| datetime::parse_w3c(&format!("{}Z", value.replace(' ', "T"))))
+        .or_else(|| value.get(..10).and_then(datetime::parse_w3c))
 }
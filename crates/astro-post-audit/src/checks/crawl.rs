@@ -0,0 +1,64 @@
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::report::{Finding, Level};
+
+/// Report what the HTTP crawl could not reach: internal URLs answering with
+/// 4xx/5xx or failing outright (often SSR routes that throw at request time,
+/// which a dist-only audit never sees), and a crawl cut short by `max_pages`.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let Some(crawl) = &index.crawl else {
+        return Vec::new();
+    };
+
+    let mut findings: Vec<Finding> = crawl
+        .errors
+        .iter()
+        .map(|error| {
+            let message = match (&error.referrer, error.status) {
+                (Some(_), Some(status)) => {
+                    format!("Linked URL '{}' returned HTTP {status}", error.url)
+                }
+                (Some(_), None) => {
+                    format!("Linked URL '{}' failed: {}", error.url, error.message)
+                }
+                (None, Some(status)) => format!("'{}' returned HTTP {status}", error.url),
+                (None, None) => format!("'{}' failed: {}", error.url, error.message),
+            };
+            Finding {
+                level: Level::Error,
+                rule_id: "crawl/http-error".into(),
+                file: error.referrer.clone().unwrap_or_else(|| error.url.clone()),
+                selector: String::new(),
+                message,
+                help: if error.status.is_some_and(|s| s >= 500) {
+                    "The server failed to render this route. Check the preview server log for the exception."
+                } else {
+                    "Fix the link or make sure the route is served."
+                }
+                .into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            }
+        })
+        .collect();
+
+    if crawl.limit_reached {
+        findings.push(Finding {
+            level: Level::Info,
+            rule_id: "crawl/limit-reached".into(),
+            file: crawl.start_url.clone(),
+            selector: String::new(),
+            message: format!(
+                "Crawl stopped after {} URL(s); pages beyond the limit were not audited",
+                config.crawl.max_pages
+            ),
+            help: "Raise crawl.max_pages to cover the whole site. Links to unvisited pages may be reported as broken.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
+    findings
+}
@@ -132,7 +132,7 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     }
 
     for page in &index.pages {
-        if page.noindex {
+        if page.noindex && !page.is_error_page() {
             let incoming = incoming_links.get(&page.route).copied().unwrap_or(0);
             if incoming > 0 {
                 findings.push(Finding {
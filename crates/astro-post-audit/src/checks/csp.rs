@@ -0,0 +1,566 @@
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use rayon::prelude::*;
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+
+use crate::checks::page_weight::parse_srcset;
+use crate::config::Config;
+use crate::csp::{self, Policy, META_IGNORED_DIRECTIVES};
+use crate::discovery::{PageInfo, SiteIndex};
+use crate::hosting::{self, HostingConfig};
+use crate::report::{Confidence, Finding, Level};
+
+/// Where a page's policy came from.
+struct PagePolicy {
+    origin: &'static str,
+    text: String,
+    policies: Vec<Policy>,
+}
+
+/// Sources a page needs, collected for the suggested policy.
+#[derive(Default)]
+struct Inventory {
+    /// (directive, source) pairs for external resources.
+    sources: BTreeSet<(&'static str, String)>,
+    script_hashes: BTreeSet<String>,
+    style_hashes: BTreeSet<String>,
+    handler_hashes: BTreeSet<String>,
+    style_attributes: bool,
+}
+
+/// Evaluate the Content-Security-Policy each page is served with (from
+/// `_headers` / `netlify.toml` / `vercel.json`, a `<meta http-equiv>`, or
+/// `csp.policy`) against what the page actually contains: inline scripts and
+/// styles (hydration scripts, `define:vars`, `is:inline`), event-handler
+/// attributes, and external scripts, styles, images, fonts, media and iframes.
+/// Optionally suggests a policy with `sha256-` hashes for the inline scripts
+/// Astro emits.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.csp;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let hosting = hosting::load(
+        &index.dist_path,
+        config.project_root.as_deref().map(Path::new),
+    );
+
+    let results: Vec<(Vec<Finding>, Option<PagePolicy>, Inventory)> = index
+        .pages
+        .par_iter()
+        .map(|page| check_page(index, config, &hosting, page))
+        .collect();
+
+    let mut findings = Vec::new();
+    let mut inventory = Inventory::default();
+    let mut reported_policies: HashSet<String> = HashSet::new();
+    let mut unprotected: Vec<&str> = Vec::new();
+
+    for (page, (page_findings, policy, page_inventory)) in index.pages.iter().zip(results) {
+        findings.extend(page_findings);
+        inventory.sources.extend(page_inventory.sources);
+        inventory.script_hashes.extend(page_inventory.script_hashes);
+        inventory.style_hashes.extend(page_inventory.style_hashes);
+        inventory
+            .handler_hashes
+            .extend(page_inventory.handler_hashes);
+        inventory.style_attributes |= page_inventory.style_attributes;
+
+        let Some(policy) = policy else {
+            unprotected.push(&page.rel_path);
+            continue;
+        };
+        // Policy text problems are reported once per distinct policy.
+        if !reported_policies.insert(policy.text.clone()) {
+            continue;
+        }
+        let mut problems: Vec<String> = policy
+            .policies
+            .iter()
+            .flat_map(Policy::syntax_problems)
+            .collect();
+        if policy.origin == "meta" {
+            for p in &policy.policies {
+                for name in META_IGNORED_DIRECTIVES {
+                    if p.directive(name).is_some() {
+                        problems.push(format!(
+                            "'{name}' is ignored in a <meta> policy; send it as an HTTP header"
+                        ));
+                    }
+                }
+            }
+        }
+        for problem in problems {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "csp/invalid-policy".into(),
+                file: if matches!(policy.origin, "meta" | "response header") {
+                    page.rel_path.clone()
+                } else {
+                    policy.origin.to_string()
+                },
+                selector: if policy.origin == "meta" {
+                    "meta[http-equiv='Content-Security-Policy']".into()
+                } else {
+                    String::new()
+                },
+                message: format!("Content-Security-Policy: {problem}"),
+                help: "Browsers skip what they cannot parse, which usually makes the policy stricter or looser than intended.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+    }
+
+    if cfg.require_policy && !unprotected.is_empty() {
+        if unprotected.len() == index.pages.len() {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "csp/missing".into(),
+                file: "_headers".into(),
+                selector: String::new(),
+                message: "No Content-Security-Policy found in _headers, netlify.toml, vercel.json or <meta http-equiv> on any page".into(),
+                help: "Add a policy to public/_headers (or your host's config). Set `csp.suggest_policy` to get a starting point.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        } else {
+            for rel_path in unprotected {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "csp/missing".into(),
+                    file: rel_path.to_string(),
+                    selector: String::new(),
+                    message: "Page is served without a Content-Security-Policy".into(),
+                    help: "Check that the _headers pattern covers this route, or add the <meta http-equiv> to the page's layout.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: Some(Confidence::Medium),
+                });
+            }
+        }
+    }
+
+    if cfg.suggest_policy && !index.pages.is_empty() {
+        let policy = suggest_policy(&inventory);
+        findings.push(Finding {
+            level: Level::Info,
+            rule_id: "csp/suggested-policy".into(),
+            file: "_headers".into(),
+            selector: String::new(),
+            message: format!(
+                "Suggested Content-Security-Policy for {} page(s) ({} inline script hash(es))",
+                index.pages.len(),
+                inventory.script_hashes.len()
+            ),
+            help: "Add under `/*` in public/_headers as `Content-Security-Policy: <policy>`. Hashes change when inline scripts change, so regenerate after upgrading Astro or integrations.".into(),
+            suggestion: Some(policy),
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        });
+    }
+
+    findings
+}
+
+fn check_page(
+    index: &SiteIndex,
+    config: &Config,
+    hosting: &HostingConfig,
+    page: &PageInfo,
+) -> (Vec<Finding>, Option<PagePolicy>, Inventory) {
+    let html = page.parse_html();
+    let policy = page_policy(config, hosting, page, &html);
+    let page_url = index
+        .base_url
+        .as_deref()
+        .and_then(|b| Url::parse(b).ok())
+        .unwrap_or_else(|| Url::parse("https://localhost/").unwrap());
+    let page_url = page_url.join(&page.route).unwrap_or(page_url);
+
+    let mut findings = Vec::new();
+    let mut inventory = Inventory::default();
+    let policies: &[Policy] = policy.as_ref().map_or(&[], |p| &p.policies);
+    // The first policy whose directive blocks, with that directive's name.
+    let blocked_by = |directive: &str, allowed: &dyn Fn(&csp::Directive) -> bool| {
+        policies.iter().find_map(|p| {
+            p.effective(directive)
+                .filter(|d| !allowed(d))
+                .map(|d| d.name.clone())
+        })
+    };
+    let finding = |level, rule_id: &str, selector: String, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: page.rel_path.clone(),
+        selector,
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    // Inline <script> elements
+    let script_sel = Selector::parse("script:not([src])").unwrap();
+    for el in html.select(&script_sel) {
+        if !is_executable_script(el.value().attr("type")) {
+            continue;
+        }
+        let content: String = el.text().collect();
+        let hash = csp::digest_source("sha256", &content);
+        inventory.script_hashes.insert(hash.clone());
+        let nonce = el.value().attr("nonce");
+        if let Some(directive) =
+            blocked_by("script-src-elem", &|d| d.allows_inline(&content, nonce))
+        {
+            findings.push(Finding {
+                suggestion: Some(format!("'{hash}'")),
+                ..finding(
+                    Level::Error,
+                    "csp/inline-script",
+                    "script".into(),
+                    format!("Inline script `{}` is blocked by {directive}", snippet(&content)),
+                    "Add the script's hash to script-src, or move it to a file (Astro bundles `<script>` tags without `is:inline`).",
+                )
+            });
+        }
+    }
+
+    // Inline <style> elements
+    let style_sel = Selector::parse("style").unwrap();
+    for el in html.select(&style_sel) {
+        let content: String = el.text().collect();
+        let hash = csp::digest_source("sha256", &content);
+        inventory.style_hashes.insert(hash.clone());
+        let nonce = el.value().attr("nonce");
+        if let Some(directive) = blocked_by("style-src-elem", &|d| d.allows_inline(&content, nonce))
+        {
+            findings.push(Finding {
+                suggestion: Some(format!("'{hash}'")),
+                ..finding(
+                    Level::Error,
+                    "csp/inline-style",
+                    "style".into(),
+                    format!("Inline <style> `{}` is blocked by {directive}", snippet(&content)),
+                    "Add the style's hash to style-src, or set `build.inlineStylesheets: 'never'` so Astro emits stylesheet files.",
+                )
+            });
+        }
+    }
+
+    // Event-handler and style attributes
+    let all_sel = Selector::parse("*").unwrap();
+    let mut blocked_style_attrs = 0;
+    let mut style_directive = String::new();
+    for el in html.select(&all_sel) {
+        let tag = el.value().name();
+        for (name, value) in el.value().attrs() {
+            if name == "style" {
+                inventory.style_attributes = true;
+                if let Some(directive) =
+                    blocked_by("style-src-attr", &|d| d.allows_attribute(value))
+                {
+                    blocked_style_attrs += 1;
+                    style_directive = directive;
+                }
+                continue;
+            }
+            if !name.starts_with("on") || name.len() < 3 {
+                continue;
+            }
+            let hash = csp::digest_source("sha256", value);
+            inventory.handler_hashes.insert(hash.clone());
+            if let Some(directive) = blocked_by("script-src-attr", &|d| d.allows_attribute(value)) {
+                findings.push(Finding {
+                    suggestion: Some(format!("'unsafe-hashes' '{hash}'")),
+                    ..finding(
+                        Level::Error,
+                        "csp/event-handler",
+                        format!("{tag}[{name}]"),
+                        format!("Event handler {name}=\"{}\" is blocked by {directive}", snippet(value)),
+                        "Attach the listener from a script instead, or allow this handler with 'unsafe-hashes' and its hash.",
+                    )
+                });
+            }
+        }
+    }
+    if blocked_style_attrs > 0 {
+        findings.push(finding(
+            Level::Warning,
+            "csp/inline-style",
+            "[style]".into(),
+            format!("{blocked_style_attrs} style attribute(s) are blocked by {style_directive}"),
+            "`define:vars` and inline `style` attributes need 'unsafe-inline' in style-src (without hashes or nonces next to it), or move the styles into classes.",
+        ));
+    }
+
+    // External resources
+    let mut reported: HashSet<(&str, String)> = HashSet::new();
+    for (directive, el, reference) in resources(&html) {
+        let Ok(url) = page_url.join(reference.trim()) else {
+            continue;
+        };
+        if matches!(url.scheme(), "javascript" | "about" | "mailto" | "tel") {
+            continue;
+        }
+        if let Some(source) = source_expression(&url, &page_url) {
+            inventory.sources.insert((directive, source));
+        }
+        let nonce = el.value().attr("nonce");
+        let Some(blocking) = blocked_by(directive, &|d| d.allows_url(&url, &page_url, nonce))
+        else {
+            continue;
+        };
+        if !reported.insert((directive, url.to_string())) {
+            continue;
+        }
+        let attr = match directive {
+            "form-action" => "action",
+            "object-src" if el.value().name() == "object" => "data",
+            _ if el.value().attr("src").is_some() => "src",
+            _ if el.value().attr("href").is_some() => "href",
+            _ => "srcset",
+        };
+        findings.push(finding(
+            Level::Error,
+            "csp/resource-blocked",
+            format!("{}[{attr}]", el.value().name()),
+            format!("'{reference}' is blocked by {blocking} ({directive})"),
+            "Add the origin to the directive, or self-host the resource.",
+        ));
+    }
+
+    (findings, policy, inventory)
+}
+
+/// The policy (or policies) the page is served with.
+fn page_policy(
+    config: &Config,
+    hosting: &HostingConfig,
+    page: &PageInfo,
+    html: &Html,
+) -> Option<PagePolicy> {
+    if let Some(text) = config.csp.policy.as_deref() {
+        return Some(PagePolicy {
+            origin: "csp.policy",
+            text: text.to_string(),
+            policies: Policy::parse_list(text),
+        });
+    }
+
+    let mut policies = Vec::new();
+    let mut texts = Vec::new();
+    let mut origin = None;
+    // A crawled page carries the headers actually served.
+    if let Some(http) = &page.http {
+        for value in http.header_values("content-security-policy") {
+            policies.extend(Policy::parse_list(value));
+            texts.push(value.to_string());
+            origin = Some("response header");
+        }
+    }
+    let configured = if page.http.is_some() {
+        Vec::new()
+    } else {
+        hosting.headers_for(&page.route)
+    };
+    for (name, value) in configured {
+        if name.eq_ignore_ascii_case("content-security-policy") {
+            policies.extend(Policy::parse_list(&value));
+            texts.push(value);
+            let file = hosting
+                .headers
+                .iter()
+                .find(|h| {
+                    hosting::match_pattern(&h.pattern, &page.route).is_some()
+                        && h.headers
+                            .iter()
+                            .any(|(n, _)| n.eq_ignore_ascii_case("content-security-policy"))
+                })
+                .map_or("_headers", |h| h.file.as_str());
+            origin = Some(match file {
+                "netlify.toml" => "netlify.toml",
+                "vercel.json" => "vercel.json",
+                _ => "_headers",
+            });
+        }
+    }
+    let meta_sel = Selector::parse("meta[http-equiv][content]").unwrap();
+    for el in html.select(&meta_sel) {
+        let equiv = el.value().attr("http-equiv").unwrap_or("");
+        if !equiv.eq_ignore_ascii_case("content-security-policy") {
+            continue;
+        }
+        let content = el.value().attr("content").unwrap_or("");
+        // A meta policy holds a single policy; commas are not separators.
+        policies.push(Policy::parse(content));
+        texts.push(content.to_string());
+        origin.get_or_insert("meta");
+    }
+
+    (!policies.is_empty()).then(|| PagePolicy {
+        origin: origin.unwrap_or("meta"),
+        text: texts.join(", "),
+        policies,
+    })
+}
+
+/// Every external resource reference on the page: (directive, element, URL).
+fn resources(html: &Html) -> Vec<(&'static str, ElementRef<'_>, String)> {
+    let mut out = Vec::new();
+    let mut select = |css: &str, attr: &str, directive: &'static str| {
+        let sel = Selector::parse(css).unwrap();
+        for el in html.select(&sel) {
+            if let Some(value) = el.value().attr(attr).filter(|v| !v.trim().is_empty()) {
+                if attr == "srcset" {
+                    for candidate in parse_srcset(value) {
+                        out.push((directive, el, candidate.url));
+                    }
+                } else {
+                    out.push((directive, el, value.to_string()));
+                }
+            }
+        }
+    };
+    select("script[src]", "src", "script-src-elem");
+    select(
+        "link[rel~='modulepreload'][href]",
+        "href",
+        "script-src-elem",
+    );
+    select(
+        "link[rel~='preload'][as='script'][href]",
+        "href",
+        "script-src-elem",
+    );
+    select("link[rel~='stylesheet'][href]", "href", "style-src-elem");
+    select(
+        "link[rel~='preload'][as='style'][href]",
+        "href",
+        "style-src-elem",
+    );
+    select("link[rel~='preload'][as='font'][href]", "href", "font-src");
+    select("link[rel~='preload'][as='image'][href]", "href", "img-src");
+    select("link[rel~='icon'][href]", "href", "img-src");
+    select("link[rel~='apple-touch-icon'][href]", "href", "img-src");
+    select("link[rel~='manifest'][href]", "href", "manifest-src");
+    select("img[src]", "src", "img-src");
+    select("img[srcset]", "srcset", "img-src");
+    select("picture source[srcset]", "srcset", "img-src");
+    select("video[poster]", "poster", "img-src");
+    select("video[src], audio[src]", "src", "media-src");
+    select(
+        "video source[src], audio source[src], track[src]",
+        "src",
+        "media-src",
+    );
+    select("iframe[src]", "src", "frame-src");
+    select("object[data]", "data", "object-src");
+    select("embed[src]", "src", "object-src");
+    select("form[action]", "action", "form-action");
+    select("base[href]", "href", "base-uri");
+    out
+}
+
+/// The source expression that allows `url`: None for same-origin URLs
+/// (covered by 'self'), a scheme for data:/blob:, else the origin.
+fn source_expression(url: &Url, page: &Url) -> Option<String> {
+    if url.origin() == page.origin() {
+        return None;
+    }
+    match url.scheme() {
+        "data" | "blob" => Some(format!("{}:", url.scheme())),
+        "http" | "https" => Some(url.origin().ascii_serialization()),
+        _ => None,
+    }
+}
+
+/// Classic and module scripts run; JSON-LD and other data blocks do not.
+fn is_executable_script(script_type: Option<&str>) -> bool {
+    match script_type.map(|t| t.trim().to_ascii_lowercase()) {
+        None => true,
+        Some(t) => {
+            t.is_empty()
+                || t == "module"
+                || t == "importmap"
+                || t.contains("javascript")
+                || t.contains("ecmascript")
+        }
+    }
+}
+
+fn snippet(content: &str) -> String {
+    let collapsed = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() > 60 {
+        format!("{}…", collapsed.chars().take(60).collect::<String>())
+    } else {
+        collapsed
+    }
+}
+
+/// Build a policy that allows everything the site uses: 'self', the
+/// external origins per directive, and hashes for inline scripts and styles.
+fn suggest_policy(inventory: &Inventory) -> String {
+    let sources_for = |directives: &[&str]| -> Vec<String> {
+        let mut out: Vec<String> = inventory
+            .sources
+            .iter()
+            .filter(|(d, _)| directives.contains(d))
+            .map(|(_, s)| s.clone())
+            .collect();
+        out.dedup();
+        out
+    };
+    let quote =
+        |hashes: &BTreeSet<String>| hashes.iter().map(|h| format!("'{h}'")).collect::<Vec<_>>();
+
+    let mut parts = vec!["default-src 'self'".to_string()];
+
+    let mut script = vec!["'self'".to_string()];
+    script.extend(sources_for(&["script-src-elem"]));
+    script.extend(quote(&inventory.script_hashes));
+    if !inventory.handler_hashes.is_empty() {
+        script.push("'unsafe-hashes'".into());
+        script.extend(quote(&inventory.handler_hashes));
+    }
+    parts.push(format!("script-src {}", script.join(" ")));
+
+    let mut style = vec!["'self'".to_string()];
+    style.extend(sources_for(&["style-src-elem"]));
+    if inventory.style_attributes {
+        // Hashes would switch 'unsafe-inline' off for style attributes.
+        style.push("'unsafe-inline'".into());
+    } else {
+        style.extend(quote(&inventory.style_hashes));
+    }
+    parts.push(format!("style-src {}", style.join(" ")));
+
+    for directive in [
+        "img-src",
+        "font-src",
+        "media-src",
+        "frame-src",
+        "manifest-src",
+    ] {
+        let extra = sources_for(&[directive]);
+        if !extra.is_empty() {
+            parts.push(format!("{directive} 'self' {}", extra.join(" ")));
+        }
+    }
+    parts.push("object-src 'none'".into());
+    parts.push("base-uri 'self'".into());
+    let forms = sources_for(&["form-action"]);
+    if forms.is_empty() {
+        parts.push("form-action 'self'".into());
+    } else {
+        parts.push(format!("form-action 'self' {}", forms.join(" ")));
+    }
+
+    parts.join("; ")
+}
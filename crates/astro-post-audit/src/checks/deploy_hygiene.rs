@@ -0,0 +1,311 @@
+use base64::Engine;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::report::{Finding, Level};
+
+/// Development leftovers that are never meant to be served. Patterns match a
+/// file or directory name anywhere in dist.
+const DEFAULT_DENY: &[&str] = &[
+    ".env",
+    ".env.*",
+    ".git",
+    ".svn",
+    ".hg",
+    ".npmrc",
+    ".htpasswd",
+    "node_modules",
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+    ".idea",
+    ".vscode",
+    "*.bak",
+    "*.orig",
+    "*.old",
+    "*.swp",
+    "*.swo",
+    "*~",
+];
+
+/// Names that usually carry credentials or full history.
+fn is_sensitive(name: &str) -> bool {
+    name == ".env"
+        || name.starts_with(".env.")
+        || matches!(name, ".git" | ".svn" | ".hg" | ".npmrc" | ".htpasswd")
+}
+
+/// Name patterns (no `/`) and dist-relative path patterns.
+struct Patterns {
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+impl Patterns {
+    fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+        for pattern in patterns {
+            let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+            let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() else {
+                continue;
+            };
+            if pattern.contains('/') {
+                paths.add(glob);
+            } else {
+                names.add(glob);
+            }
+        }
+        Self {
+            names: names.build().unwrap_or_else(|_| GlobSet::empty()),
+            paths: paths.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    fn is_match(&self, name: &str, rel: &str) -> bool {
+        self.names.is_match(name) || self.paths.is_match(rel)
+    }
+}
+
+/// Walk the whole dist tree, not just the pages in the index, for files that
+/// should not be deployed: dotfiles such as `.env`, VCS directories, editor
+/// swap and backup files, a copied `node_modules`, plus `deploy_hygiene.deny`.
+/// A denied directory is reported once. With `check_source_maps`, `.map`
+/// files that embed `sourcesContent` are reported, and so are
+/// `sourceMappingURL` comments in JS and CSS that point at a missing map, a
+/// map deployed next to the bundle, or an inline map with sources.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.deploy_hygiene;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let deny = Patterns::new(
+        DEFAULT_DENY
+            .iter()
+            .copied()
+            .chain(cfg.deny.iter().map(String::as_str)),
+    );
+    let allow = Patterns::new(cfg.allow.iter().map(String::as_str));
+
+    let mut findings = Vec::new();
+    let mut walker = WalkDir::new(&index.dist_path)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy();
+        let rel = index.dist_rel_path(entry.path());
+        let is_dir = entry.file_type().is_dir();
+
+        if deny.is_match(&name, &rel) && !allow.is_match(&name, &rel) {
+            let level = if is_sensitive(&name) {
+                Level::Error
+            } else {
+                Level::Warning
+            };
+            let (file, message) = if is_dir {
+                walker.skip_current_dir();
+                let files = WalkDir::new(entry.path())
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file())
+                    .count();
+                (
+                    format!("{rel}/"),
+                    format!("Directory '{rel}/' ({files} file(s)) is part of the build output"),
+                )
+            } else {
+                (rel.clone(), format!("'{rel}' is part of the build output"))
+            };
+            findings.push(Finding {
+                level,
+                rule_id: "deploy/denied-file".into(),
+                file,
+                selector: String::new(),
+                message,
+                help: "Everything in public/ is copied into dist unchanged. Delete it from public/ and add it to .gitignore, or exclude it in the step that copies files into dist. If it is meant to be public, add it to `deploy_hygiene.allow`.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+            continue;
+        }
+
+        if !cfg.check_source_maps || is_dir {
+            continue;
+        }
+        let ext = entry
+            .path()
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "map" => {
+                let embedded = std::fs::read(entry.path())
+                    .ok()
+                    .and_then(|bytes| embedded_sources(&bytes));
+                if let Some((count, example)) = embedded {
+                    findings.push(sources_finding(&rel, "Source map", count, &example));
+                }
+            }
+            "js" | "mjs" | "cjs" | "css" => {
+                let Ok(bytes) = std::fs::read(entry.path()) else {
+                    continue;
+                };
+                let content = String::from_utf8_lossy(&bytes);
+                if let Some(url) = source_mapping_url(&content) {
+                    findings.extend(check_reference(index, &rel, url));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+/// Findings for a `sourceMappingURL` in `rel`.
+fn check_reference(index: &SiteIndex, rel: &str, url: &str) -> Option<Finding> {
+    let finding = |level, rule_id: &str, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: rel.into(),
+        selector: "sourceMappingURL".into(),
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    if let Some(data) = url.strip_prefix("data:") {
+        let (meta, payload) = data.split_once(',')?;
+        let json = if meta.ends_with(";base64") {
+            base64::engine::general_purpose::STANDARD
+                .decode(payload)
+                .ok()?
+        } else {
+            percent_encoding::percent_decode_str(payload).collect()
+        };
+        let (count, example) = embedded_sources(&json)?;
+        return Some(sources_finding(rel, "Inline source map", count, &example));
+    }
+
+    let target = index.resolve_local_asset(url, rel)?;
+    if target.is_file() {
+        Some(finding(
+            Level::Info,
+            "deploy/sourcemap-public",
+            format!(
+                "sourceMappingURL points to '{}', which is deployed publicly",
+                index.dist_rel_path(&target)
+            ),
+            "Anyone can download the map and read the original module structure. Use `vite: { build: { sourcemap: 'hidden' } }` and upload maps to your error tracker instead of deploying them.",
+        ))
+    } else {
+        Some(finding(
+            Level::Warning,
+            "deploy/sourcemap-missing",
+            format!("sourceMappingURL points to '{url}', which is not in dist"),
+            "Browser devtools request the map and get a 404. Build with `sourcemap: 'hidden'` to drop the comment, or deploy the map.",
+        ))
+    }
+}
+
+fn sources_finding(rel: &str, what: &str, count: usize, example: &str) -> Finding {
+    Finding {
+        level: Level::Warning,
+        rule_id: "deploy/sourcemap-sources".into(),
+        file: rel.into(),
+        selector: String::new(),
+        message: format!(
+            "{what} embeds the original source of {count} file(s), e.g. '{example}'"
+        ),
+        help: "`sourcesContent` ships your unminified code, including comments and server-side imports that were tree-shaken away. Do not deploy source maps, or strip `sourcesContent` before uploading.".into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    }
+}
+
+/// Number of non-empty `sourcesContent` entries and the first such source
+/// path, or None when the map embeds no sources.
+fn embedded_sources(map: &[u8]) -> Option<(usize, String)> {
+    let map: serde_json::Value = serde_json::from_slice(map).ok()?;
+    let contents = map.get("sourcesContent")?.as_array()?;
+    let sources = map.get("sources").and_then(|s| s.as_array());
+    let embedded: Vec<usize> = contents
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.as_str().is_some_and(|c| !c.is_empty()))
+        .map(|(i, _)| i)
+        .collect();
+    let first = *embedded.first()?;
+    let example = sources
+        .and_then(|s| s.get(first))
+        .and_then(|s| s.as_str())
+        .map(|s| s.trim_start_matches("../").to_string())
+        .unwrap_or_else(|| format!("source #{}", first + 1));
+    Some((embedded.len(), example))
+}
+
+/// The URL of the last `//# sourceMappingURL=` (or `/*# ... */`) comment.
+fn source_mapping_url(content: &str) -> Option<&str> {
+    const KEY: &str = "sourceMappingURL=";
+    let mut search = content;
+    while let Some(pos) = search.rfind(KEY) {
+        let before = search[..pos].trim_end_matches([' ', '#', '@']);
+        if before.ends_with("//") || before.ends_with("/*") {
+            let url = search[pos + KEY.len()..]
+                .split(|c: char| c.is_whitespace() || c == '*')
+                .next()
+                .unwrap_or("");
+            return (!url.is_empty()).then_some(url);
+        }
+        search = &search[..pos];
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_source_mapping_url_comments() {
+        assert_eq!(
+            source_mapping_url("a();\n//# sourceMappingURL=app.js.map\n"),
+            Some("app.js.map")
+        );
+        assert_eq!(
+            source_mapping_url("a{}\n/*# sourceMappingURL=app.css.map */"),
+            Some("app.css.map")
+        );
+        // A string literal mentioning the key is not a comment.
+        assert_eq!(source_mapping_url(r#"const k="sourceMappingURL=x";"#), None);
+    }
+
+    #[test]
+    fn counts_embedded_sources() {
+        let map = br#"{"sources":["../src/a.ts","../src/b.ts"],"sourcesContent":[null,"export const b = 1;"]}"#;
+        assert_eq!(embedded_sources(map), Some((1, "src/b.ts".to_string())));
+        assert_eq!(embedded_sources(br#"{"sources":["a.ts"]}"#), None);
+    }
+
+    #[test]
+    fn matches_names_and_paths() {
+        let deny = Patterns::new(["*.bak", "drafts/*.html"]);
+        assert!(deny.is_match("index.html.bak", "blog/index.html.bak"));
+        assert!(deny.is_match("a.html", "drafts/a.html"));
+        assert!(!deny.is_match("a.html", "blog/drafts/a.html"));
+        assert!(is_sensitive(".env.production"));
+        assert!(!is_sensitive(".envrc-example"));
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::config::Config;
+use crate::discovery::{crawl, PageInfo, SiteIndex};
+use crate::normalize;
+use crate::report::{Finding, Level};
+
+/// Path requested in crawl mode to see what the server answers for a URL
+/// that does not exist.
+const PROBE_PATH: &str = "/astro-post-audit-missing-page-check/";
+
+/// Attributes holding asset URLs, as (selector, attribute, is srcset).
+const ASSET_ATTRS: &[(&str, &str, bool)] = &[
+    ("script[src]", "src", false),
+    ("link[href]", "href", false),
+    ("img[src]", "src", false),
+    ("img[srcset]", "srcset", true),
+    ("source[src]", "src", false),
+    ("source[srcset]", "srcset", true),
+    ("video[src]", "src", false),
+    ("video[poster]", "poster", false),
+    ("audio[src]", "src", false),
+];
+
+/// Audit error pages (404.html, 500.html). Hosts serve them at whatever URL
+/// was requested, so they must not be indexed, must not canonicalize to
+/// themselves, need a way back into the site, and must reference assets by
+/// root-relative URLs — `_astro/app.css` resolves to `/blog/x/_astro/app.css`
+/// on a nested 404 and the page renders unstyled.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    if !config.error_pages.enabled {
+        return Vec::new();
+    }
+
+    let norm = &config.url_normalization;
+    let mut findings = Vec::new();
+    let error_pages: Vec<&PageInfo> = index.pages.iter().filter(|p| p.is_error_page()).collect();
+
+    // Hosts only fall back to the root 404 page; localized ones (de/404.html)
+    // need explicit rules.
+    if let Some(crawl) = &index.crawl {
+        findings.extend(probe_missing_url(&crawl.start_url, config));
+    } else if !index.file_exists("404.html") {
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "error-page/missing".into(),
+            file: "404.html".into(),
+            selector: String::new(),
+            message: "No 404 page in the build; visitors get the host's generic error page".into(),
+            help: "Create src/pages/404.astro. Astro builds it to 404.html, which static hosts serve for unknown URLs.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
+    let sitemap_routes: HashSet<String> = index
+        .sitemap_urls
+        .iter()
+        .filter_map(|u| Url::parse(u).ok())
+        .map(|u| normalize::normalize_path(u.path(), norm))
+        .collect();
+    let home = index
+        .base_url
+        .as_deref()
+        .and_then(|b| Url::parse(b).ok())
+        .map_or("/".to_string(), |u| {
+            normalize::normalize_path(u.path(), norm)
+        });
+    let base = index.base_url.as_deref();
+
+    for page in error_pages {
+        let finding =
+            |level, rule_id: &str, selector: String, message: String, help: &str| Finding {
+                level,
+                rule_id: rule_id.into(),
+                file: page.rel_path.clone(),
+                selector,
+                message,
+                help: help.into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            };
+        let html = page.parse_html();
+
+        if sitemap_routes.contains(&page.route) {
+            findings.push(finding(
+                Level::Error,
+                "error-page/in-sitemap",
+                String::new(),
+                format!(
+                    "Error page '{}' is listed in the sitemap{}",
+                    page.route,
+                    if page.noindex { "" } else { " and has no noindex" }
+                ),
+                "Exclude it from the sitemap (e.g. the `filter` option of @astrojs/sitemap) and add <meta name=\"robots\" content=\"noindex\">.",
+            ));
+        }
+
+        for href in &page.canonical_hrefs {
+            let Some(resolved) = normalize::resolve_href(href, &page.route, base) else {
+                continue;
+            };
+            if normalize::normalize_path(&resolved, norm) == page.route {
+                findings.push(Finding {
+                    suggestion: Some("Remove the canonical link from the error page layout".into()),
+                    ..finding(
+                        Level::Warning,
+                        "error-page/self-canonical",
+                        "link[rel='canonical']".into(),
+                        format!("Error page canonicalizes to itself ('{href}')"),
+                        "The page is served at every missing URL, so each broken link would declare the error page canonical.",
+                    )
+                });
+            }
+        }
+
+        if !links_home_or_search(page, &html, &home, norm, base) {
+            findings.push(finding(
+                Level::Warning,
+                "error-page/no-home-link",
+                "body".into(),
+                "Error page has no link to the home page or a search".into(),
+                "Give lost visitors a way back: link to the home page or offer a search form.",
+            ));
+        }
+
+        let has_base = html
+            .select(&Selector::parse("base[href]").unwrap())
+            .next()
+            .and_then(|el| el.value().attr("href"))
+            .is_some_and(|h| h.starts_with('/') || h.contains("://"));
+        if has_base {
+            continue;
+        }
+        for (css, attr, srcset) in ASSET_ATTRS {
+            let sel = Selector::parse(css).unwrap();
+            for el in html.select(&sel) {
+                let value = el.value().attr(attr).unwrap_or("");
+                let urls: Vec<&str> = if *srcset {
+                    value
+                        .split(',')
+                        .filter_map(|c| c.split_whitespace().next())
+                        .collect()
+                } else {
+                    vec![value.trim()]
+                };
+                for url in urls.into_iter().filter(|u| is_document_relative(u)) {
+                    findings.push(Finding {
+                        suggestion: Some(format!("/{}", url.trim_start_matches("./"))),
+                        ..finding(
+                            Level::Error,
+                            "error-page/relative-url",
+                            format!("{}[{attr}='{value}']", el.value().name()),
+                            format!("Error page loads '{url}' by a relative URL, which breaks when served at a nested path"),
+                            "Use a root-relative URL (starting with /). Astro's bundled assets already are; check hand-written paths in the 404 layout.",
+                        )
+                    });
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+/// In crawl mode, request a URL that cannot exist and expect a 404.
+fn probe_missing_url(start_url: &str, config: &Config) -> Option<Finding> {
+    let url = Url::parse(start_url).ok()?.join(PROBE_PATH).ok()?;
+    let agent = crawl::new_agent(config.crawl.timeout_ms);
+    let (response, _) = crawl::get(&agent, url.as_str()).ok()?;
+    let (rule_id, message, help) = match response.status {
+        404 | 410 => return None,
+        200..=299 => (
+            "error-page/soft-404",
+            format!("Unknown URL {url} answers {} instead of 404", response.status),
+            "Search engines index every mistyped URL as a duplicate page. Return 404 for unknown routes (Astro.response.status = 404 in SSR).",
+        ),
+        status => (
+            "error-page/wrong-status",
+            format!("Unknown URL {url} answers {status} instead of 404"),
+            "Serve the 404 page with status 404 for unknown routes.",
+        ),
+    };
+    Some(Finding {
+        level: Level::Error,
+        rule_id: rule_id.into(),
+        file: url.path().trim_start_matches('/').to_string(),
+        selector: String::new(),
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    })
+}
+
+fn links_home_or_search(
+    page: &PageInfo,
+    html: &Html,
+    home: &str,
+    norm: &crate::config::UrlNormalizationConfig,
+    base: Option<&str>,
+) -> bool {
+    let links_home = page.anchor_hrefs.iter().any(|href| {
+        if !normalize::is_internal(href, base) {
+            return false;
+        }
+        let path = normalize::strip_fragment_and_query(href);
+        let path = if path.starts_with('/') || path.contains("://") {
+            normalize::resolve_href(path, "/", base)
+        } else {
+            None
+        };
+        path.is_some_and(|p| {
+            let route = normalize::normalize_path(&p, norm);
+            route == home || route.contains("search") || route.contains("suche")
+        })
+    });
+    let search_sel = Selector::parse("form[role='search'], search, input[type='search']").unwrap();
+    links_home || html.select(&search_sel).next().is_some()
+}
+
+/// A URL resolved against the current document's path: not absolute,
+/// root-relative, protocol-relative, a fragment or a data/blob URL.
+fn is_document_relative(url: &str) -> bool {
+    !(url.is_empty()
+        || url.starts_with('/')
+        || url.starts_with('#')
+        || url.contains("://")
+        || url
+            .split_once(':')
+            .is_some_and(|(scheme, _)| !scheme.contains('/') && !scheme.is_empty()))
+}
@@ -1,21 +1,60 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
 
-use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::config::Config;
+use crate::config::{Config, ExternalLinksConfig};
+use crate::datetime;
 use crate::discovery::SiteIndex;
 use crate::normalize;
 use crate::report::{Finding, Level};
 
-/// Collect all unique external URLs across all pages, then check them via HEAD requests.
+/// Longest `Retry-After` that is waited for; a host asking for more is
+/// treated as failing.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+const CACHE_VERSION: u32 = 1;
+
+/// A failed check: HTTP status (or "timeout/error") and a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Broken {
+    status: String,
+    message: String,
+}
+
+/// Last result for a URL in the on-disk cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Unix seconds of the last request.
+    checked_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    broken: Option<Broken>,
+    /// Outcome of the most recent requests, oldest first; `true` = broken.
+    #[serde(default)]
+    history: Vec<bool>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+/// Collect all unique external URLs across all pages, then check them via
+/// HEAD requests (GET where HEAD is refused). Results are kept in
+/// `cache_path` for `cache_ttl_ok_secs` / `cache_ttl_broken_secs`. Requests
+/// are spread over hosts (`max_per_host`, `host_delay_ms`), transient
+/// failures are retried with exponential backoff or after `Retry-After`, and
+/// no request starts after `time_budget_secs`. A link broken in only one of
+/// the last `quarantine_runs` runs is reported as flaky instead of broken.
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
-    if !config.external_links.enabled {
+    let cfg = &config.external_links;
+    if !cfg.enabled {
         return Vec::new();
     }
 
-    let timeout = Duration::from_millis(config.external_links.timeout_ms);
     // Phase 1: Collect all external URLs and which pages reference them
     let mut url_to_pages: HashMap<String, HashSet<String>> = HashMap::new();
 
@@ -40,17 +79,15 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
             // Apply domain filters
             if let Ok(parsed) = url::Url::parse(&check_url) {
                 if let Some(host) = parsed.host_str() {
-                    if !config.external_links.allow_domains.is_empty()
-                        && !config
-                            .external_links
+                    if !cfg.allow_domains.is_empty()
+                        && !cfg
                             .allow_domains
                             .iter()
                             .any(|d| host == d || host.ends_with(&format!(".{}", d)))
                     {
                         continue;
                     }
-                    if config
-                        .external_links
+                    if cfg
                         .block_domains
                         .iter()
                         .any(|d| host == d || host.ends_with(&format!(".{}", d)))
@@ -71,77 +108,373 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         return Vec::new();
     }
 
-    // Phase 2: Check URLs in parallel (bounded by max_concurrent via rayon thread pool)
-    let urls: Vec<(String, Vec<String>)> = url_to_pages
-        .into_iter()
-        .map(|(url, pages)| (url, pages.into_iter().collect()))
+    // Phase 2: Request every URL without a fresh cached result
+    let now = datetime::now();
+    let mut cache = cfg
+        .cache_path
+        .as_deref()
+        .map(load_cache)
+        .unwrap_or_default();
+    let mut urls: Vec<&String> = url_to_pages
+        .keys()
+        .filter(|url| !cache.get(*url).is_some_and(|e| is_fresh(e, cfg, now)))
         .collect();
+    urls.sort();
+    let (results, unchecked) = check_urls(&urls, cfg);
+
+    let keep = cfg.quarantine_runs.max(1);
+    for (url, broken) in results {
+        let entry = cache.entry(url).or_insert_with(|| CacheEntry {
+            checked_at: now,
+            broken: None,
+            history: Vec::new(),
+        });
+        entry.checked_at = now;
+        entry.history.push(broken.is_some());
+        let excess = entry.history.len().saturating_sub(keep);
+        entry.history.drain(..excess);
+        entry.broken = broken;
+    }
+    if let Some(path) = cfg.cache_path.as_deref() {
+        // Forget links that left the site once their result has expired.
+        let max_ttl = cfg.cache_ttl_ok_secs.max(cfg.cache_ttl_broken_secs);
+        cache
+            .retain(|url, e| url_to_pages.contains_key(url) || now - e.checked_at < max_ttl as i64);
+        if let Err(e) = save_cache(path, &cache) {
+            eprintln!("Warning: could not write external link cache '{path}': {e}");
+        }
+    }
+
+    // Phase 3: Report
+    let level = if cfg.fail_on_broken {
+        Level::Error
+    } else {
+        Level::Warning
+    };
+    let mut findings = Vec::new();
+    let mut reported: Vec<(&String, &HashSet<String>)> = url_to_pages.iter().collect();
+    reported.sort_by_key(|(url, _)| *url);
+    for (url, pages) in reported {
+        let Some(entry) = cache.get(url).filter(|_| !unchecked.contains(url)) else {
+            continue;
+        };
+        let Some(broken) = &entry.broken else {
+            continue;
+        };
+        let flaky = cfg.quarantine_runs >= 2
+            && entry.history.len() >= cfg.quarantine_runs
+            && entry.history.iter().filter(|&&b| b).count() == 1;
+        let mut pages: Vec<&String> = pages.iter().collect();
+        pages.sort();
+        for page in pages {
+            findings.push(if flaky {
+                Finding {
+                    level: Level::Info,
+                    rule_id: "external-links/flaky".into(),
+                    file: page.clone(),
+                    selector: format!("a[href='{}']", url),
+                    message: format!(
+                        "{} (status: {}); it worked in {} of the last {} runs",
+                        broken.message,
+                        broken.status,
+                        entry.history.len() - 1,
+                        entry.history.len()
+                    ),
+                    help: "A single failure among successful runs is usually a timeout or rate limit. It is reported as broken again if it fails in another run.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                }
+            } else {
+                Finding {
+                    level: level.clone(),
+                    rule_id: "external-links/broken".into(),
+                    file: page.clone(),
+                    selector: format!("a[href='{}']", url),
+                    message: format!("{} (status: {})", broken.message, broken.status),
+                    help: "Fix or remove this broken external link".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                }
+            });
+        }
+    }
+
+    let mut unchecked: Vec<&String> = unchecked.iter().collect();
+    unchecked.sort();
+    if let Some(first) = unchecked.first() {
+        let page = url_to_pages[*first]
+            .iter()
+            .min()
+            .cloned()
+            .unwrap_or_default();
+        findings.push(Finding {
+            level: Level::Info,
+            rule_id: "external-links/unchecked".into(),
+            file: page,
+            selector: format!("a[href='{}']", first),
+            message: format!(
+                "{} external link(s) were not checked within the {}s time budget, e.g. '{first}'",
+                unchecked.len(),
+                cfg.time_budget_secs
+            ),
+            help: "Raise `external_links.time_budget_secs`, or set `cache_path` so later runs only request links without a fresh result.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
+    findings
+}
+
+fn is_fresh(entry: &CacheEntry, cfg: &ExternalLinksConfig, now: i64) -> bool {
+    let ttl = if entry.broken.is_some() {
+        cfg.cache_ttl_broken_secs
+    } else {
+        cfg.cache_ttl_ok_secs
+    };
+    now - entry.checked_at < ttl as i64
+}
+
+/// A missing, unreadable or outdated cache file starts an empty cache.
+fn load_cache(path: &str) -> BTreeMap<String, CacheEntry> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<CacheFile>(&raw).ok())
+        .filter(|file| file.version == CACHE_VERSION)
+        .map(|file| file.entries)
+        .unwrap_or_default()
+}
+
+fn save_cache(path: &str, entries: &BTreeMap<String, CacheEntry>) -> anyhow::Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = CacheFile {
+        version: CACHE_VERSION,
+        entries: entries.clone(),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
+
+/// A URL waiting to be requested.
+struct Job {
+    url: String,
+    host: String,
+    /// Retries so far.
+    attempt: u32,
+    not_before: Instant,
+}
+
+#[derive(Default)]
+struct HostSlots {
+    active: usize,
+    next_start: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Queue {
+    jobs: VecDeque<Job>,
+    hosts: HashMap<String, HostSlots>,
+    running: usize,
+    results: Vec<(String, Option<Broken>)>,
+}
+
+/// Hands out jobs to the worker threads so that no host gets more than
+/// `max_per_host` requests at once or requests closer than `host_delay`.
+struct Scheduler {
+    queue: Mutex<Queue>,
+    changed: Condvar,
+    max_per_host: usize,
+    host_delay: Duration,
+    retries: u32,
+    backoff: Duration,
+    deadline: Option<Instant>,
+}
+
+/// What a request means for the job.
+enum Outcome {
+    Done(Option<Broken>),
+    /// Transient failure; retry after the host's `Retry-After`, if given.
+    Retry(Option<Duration>, Broken),
+}
+
+impl Scheduler {
+    /// The next job that may start now, waiting for one if needed. None once
+    /// all work is done or the time budget is spent.
+    fn next_job(&self) -> Option<Job> {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            if self.deadline.is_some_and(|d| now >= d)
+                || (queue.jobs.is_empty() && queue.running == 0)
+            {
+                // Wake the other workers so they stop too.
+                self.changed.notify_all();
+                return None;
+            }
+
+            let mut wake = self.deadline;
+            let mut ready = None;
+            for (i, job) in queue.jobs.iter().enumerate() {
+                let slots = queue.hosts.get(&job.host);
+                if slots.is_some_and(|s| s.active >= self.max_per_host) {
+                    continue;
+                }
+                let start = slots
+                    .and_then(|s| s.next_start)
+                    .map_or(job.not_before, |s| s.max(job.not_before));
+                if start <= now {
+                    ready = Some(i);
+                    break;
+                }
+                wake = Some(wake.map_or(start, |w| w.min(start)));
+            }
+
+            if let Some(job) = ready.and_then(|i| queue.jobs.remove(i)) {
+                let slots = queue.hosts.entry(job.host.clone()).or_default();
+                slots.active += 1;
+                slots.next_start = Some(now + self.host_delay);
+                queue.running += 1;
+                return Some(job);
+            }
+            queue = match wake {
+                Some(wake) => self.changed.wait_timeout(queue, wake - now).unwrap().0,
+                None => self.changed.wait(queue).unwrap(),
+            };
+        }
+    }
+
+    fn finish(&self, mut job: Job, outcome: Outcome) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.running -= 1;
+        let now = Instant::now();
+        let slots = queue.hosts.entry(job.host.clone()).or_default();
+        slots.active -= 1;
+
+        match outcome {
+            Outcome::Done(result) => queue.results.push((job.url, result)),
+            Outcome::Retry(retry_after, broken) => {
+                let wait = retry_after.unwrap_or(self.backoff * 2u32.saturating_pow(job.attempt));
+                let in_budget = self.deadline.is_none_or(|d| now + wait < d);
+                if job.attempt < self.retries && wait <= MAX_RETRY_AFTER && in_budget {
+                    if retry_after.is_some() {
+                        // The host asked everyone to wait, not just this URL.
+                        slots.next_start =
+                            Some(slots.next_start.map_or(now, |s| s.max(now)) + wait);
+                    }
+                    job.attempt += 1;
+                    job.not_before = now + wait;
+                    queue.jobs.push_back(job);
+                } else {
+                    queue.results.push((job.url, Some(broken)));
+                }
+            }
+        }
+        self.changed.notify_all();
+    }
+}
+
+/// Request `urls` with `max_concurrent` workers. Returns the result per
+/// checked URL and the URLs left when the time budget ran out.
+fn check_urls(
+    urls: &[&String],
+    cfg: &ExternalLinksConfig,
+) -> (Vec<(String, Option<Broken>)>, HashSet<String>) {
+    if urls.is_empty() {
+        return (Vec::new(), HashSet::new());
+    }
+    let start = Instant::now();
+    let scheduler = Scheduler {
+        queue: Mutex::new(Queue {
+            jobs: urls
+                .iter()
+                .map(|url| Job {
+                    url: url.to_string(),
+                    host: host_of(url),
+                    attempt: 0,
+                    not_before: start,
+                })
+                .collect(),
+            ..Default::default()
+        }),
+        changed: Condvar::new(),
+        max_per_host: cfg.max_per_host,
+        host_delay: Duration::from_millis(cfg.host_delay_ms),
+        retries: cfg.retries,
+        backoff: Duration::from_millis(cfg.backoff_ms),
+        deadline: (cfg.time_budget_secs > 0)
+            .then(|| start + Duration::from_secs(cfg.time_budget_secs)),
+    };
     let agent = ureq::config::Config::builder()
-        .timeout_global(Some(timeout))
+        .timeout_global(Some(Duration::from_millis(cfg.timeout_ms)))
         .http_status_as_error(false)
         .build()
         .new_agent();
 
-    // Use a custom thread pool to limit concurrency
-    let pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(config.external_links.max_concurrent)
-        .build()
-        .unwrap_or_else(|_| rayon::ThreadPoolBuilder::new().build().unwrap());
-
-    pool.install(|| {
-        urls.par_iter()
-            .flat_map(|(url, pages)| {
-                let Some((status, message)) = check_url(&agent, url) else {
-                    return Vec::new();
-                };
-                let level = if config.external_links.fail_on_broken {
-                    Level::Error
-                } else {
-                    Level::Warning
-                };
-
-                pages
-                    .iter()
-                    .map(|page| Finding {
-                        level: level.clone(),
-                        rule_id: "external-links/broken".into(),
-                        file: page.clone(),
-                        selector: format!("a[href='{}']", url),
-                        message: format!("{} (status: {})", message, status),
-                        help: "Fix or remove this broken external link".into(),
-                        suggestion: None,
-                        source_hint: None,
-                        confidence: None,
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect()
-    })
-}
-
-/// Check a single URL. Returns Some((status_code, message)) if broken, None if OK.
-fn check_url(agent: &ureq::Agent, url: &str) -> Option<(String, String)> {
+    let workers = cfg.max_concurrent.min(urls.len());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some(job) = scheduler.next_job() {
+                    let outcome = check_url(&agent, &job.url);
+                    scheduler.finish(job, outcome);
+                }
+            });
+        }
+    });
+
+    let queue = scheduler.queue.into_inner().unwrap();
+    let unchecked = queue.jobs.into_iter().map(|job| job.url).collect();
+    (queue.results, unchecked)
+}
+
+fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Check a single URL.
+fn check_url(agent: &ureq::Agent, url: &str) -> Outcome {
     // Try HEAD first, fall back to GET if HEAD is not allowed (405)
-    match agent.head(url).call() {
+    let response = match agent.head(url).call() {
+        // Some hosts block/rate-limit HEAD although GET is fine.
+        Ok(response) if should_retry_with_get(response.status().as_u16()) => agent.get(url).call(),
+        other => other,
+    };
+    match response {
         Ok(response) => {
-            let status = response.status();
-            if should_retry_with_get(status.as_u16()) {
-                // Some hosts block/rate-limit HEAD although GET is fine.
-                return check_url_get(agent, url);
+            let status = response.status().as_u16();
+            if status < 400 {
+                return Outcome::Done(None);
             }
-            if status.as_u16() >= 400 {
-                Some((
-                    status.as_u16().to_string(),
-                    format!("External link '{}' returned HTTP {}", url, status.as_u16()),
-                ))
+            let broken = Broken {
+                status: status.to_string(),
+                message: format!("External link '{}' returned HTTP {}", url, status),
+            };
+            if is_transient(status) {
+                let retry_after = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| parse_retry_after(v, datetime::now()));
+                Outcome::Retry(retry_after, broken)
             } else {
-                None
+                Outcome::Done(Some(broken))
             }
         }
-        Err(e) => Some((
-            "timeout/error".into(),
-            format!("External link '{}' failed: {}", url, e),
-        )),
+        Err(e) => Outcome::Retry(
+            None,
+            Broken {
+                status: "timeout/error".into(),
+                message: format!("External link '{}' failed: {}", url, e),
+            },
+        ),
     }
 }
 
@@ -149,24 +482,19 @@ fn should_retry_with_get(status_code: u16) -> bool {
     matches!(status_code, 403 | 405 | 429)
 }
 
-fn check_url_get(agent: &ureq::Agent, url: &str) -> Option<(String, String)> {
-    match agent.get(url).call() {
-        Ok(response) => {
-            let status = response.status();
-            if status.as_u16() >= 400 {
-                Some((
-                    status.as_u16().to_string(),
-                    format!("External link '{}' returned HTTP {}", url, status.as_u16()),
-                ))
-            } else {
-                None
-            }
-        }
-        Err(e) => Some((
-            "timeout/error".into(),
-            format!("External link '{}' failed: {}", url, e),
-        )),
+/// Statuses worth asking again: timeouts, rate limits and server errors.
+fn is_transient(status_code: u16) -> bool {
+    matches!(status_code, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// `Retry-After` as delay seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: i64) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
     }
+    let at = datetime::parse_rfc2822(value)?;
+    Some(Duration::from_secs((at - now).max(0) as u64))
 }
 
 #[cfg(test)]
@@ -181,4 +509,46 @@ mod tests {
         assert!(!should_retry_with_get(404));
         assert!(!should_retry_with_get(500));
     }
+
+    #[test]
+    fn retry_after_seconds_and_dates() {
+        let now = datetime::parse_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:29:30 GMT", now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn cache_freshness_uses_separate_ttls() {
+        let cfg = ExternalLinksConfig {
+            cache_ttl_ok_secs: 100,
+            cache_ttl_broken_secs: 10,
+            ..Default::default()
+        };
+        let ok = CacheEntry {
+            checked_at: 1000,
+            broken: None,
+            history: vec![false],
+        };
+        let broken = CacheEntry {
+            broken: Some(Broken {
+                status: "404".into(),
+                message: String::new(),
+            }),
+            ..ok.clone()
+        };
+        assert!(is_fresh(&ok, &cfg, 1050));
+        assert!(!is_fresh(&broken, &cfg, 1050));
+        assert!(is_fresh(&broken, &cfg, 1005));
+    }
 }
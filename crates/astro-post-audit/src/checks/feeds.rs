@@ -0,0 +1,341 @@
+use std::collections::{HashMap, HashSet};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use url::Url;
+
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::feed::{self, Feed, FeedFile};
+use crate::normalize;
+use crate::report::{Confidence, Finding, Level};
+
+/// Audit RSS, Atom and JSON feeds (e.g. from `@astrojs/rss`): item links that
+/// do not resolve to a page, unparseable or unordered dates, duplicate GUIDs,
+/// absolute URLs on another origin than `site.base_url`, item titles that
+/// disagree with the page, and blog posts that never made it into a feed.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.feeds;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let feeds = feed::discover(index);
+    let mut findings = Vec::new();
+    // Routes linked from any feed item, for the missing-post check.
+    let mut linked_routes: HashSet<String> = HashSet::new();
+
+    for file in &feeds {
+        let feed = match &file.feed {
+            Ok(feed) => feed,
+            Err(err) => {
+                findings.push(parse_or_missing(index, file, err));
+                continue;
+            }
+        };
+        findings.extend(check_feed(index, config, file, feed, &mut linked_routes));
+    }
+
+    let parsed = feeds.iter().filter(|f| f.feed.is_ok()).count();
+    if parsed > 0 && !cfg.post_patterns.is_empty() {
+        let patterns = build_post_patterns(&cfg.post_patterns);
+        let mut pages: Vec<_> = index.pages.iter().collect();
+        pages.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+        for page in pages {
+            if page.noindex || page.is_error_page() || linked_routes.contains(&page.route) {
+                continue;
+            }
+            if page
+                .canonical
+                .as_deref()
+                .and_then(|c| Url::parse(c).ok())
+                .is_some_and(|c| {
+                    normalize::normalize_path(c.path(), &config.url_normalization) != page.route
+                })
+            {
+                continue;
+            }
+            let route = page.route.trim_end_matches('/');
+            // Numbered listing pages (/blog/2/) are pagination, not posts.
+            let last = route.rsplit('/').next().unwrap_or("");
+            if last.bytes().all(|b| b.is_ascii_digit()) || !patterns.is_match(route) {
+                continue;
+            }
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "feed/post-missing".into(),
+                file: page.rel_path.clone(),
+                selector: String::new(),
+                message: format!(
+                    "Indexable post '{}' is not in any feed ({})",
+                    page.route,
+                    feeds
+                        .iter()
+                        .filter(|f| f.feed.is_ok())
+                        .map(|f| f.rel_path.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                help: "Feeds built with `@astrojs/rss` list what the endpoint passes in; drafts filters or a hard-coded slice often drop posts. Add the post, or narrow `feeds.post_patterns`.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: Some(Confidence::Medium),
+            });
+        }
+    }
+
+    findings
+}
+
+fn parse_or_missing(index: &SiteIndex, file: &FeedFile, err: &str) -> Finding {
+    if !index.dist_path.join(&file.rel_path).is_file() {
+        return Finding {
+            level: Level::Error,
+            rule_id: "feed/link-broken".into(),
+            file: file.linked_from.clone().unwrap_or_else(|| file.rel_path.clone()),
+            selector: format!("link[rel='alternate'][href$='{}']", file.rel_path),
+            message: format!("Feed '{}' is advertised but not in dist", file.rel_path),
+            help: "Check the feed endpoint (e.g. src/pages/rss.xml.js) builds, or fix the <link rel=\"alternate\"> href.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        };
+    }
+    Finding {
+        level: Level::Error,
+        rule_id: "feed/parse-error".into(),
+        file: file.rel_path.clone(),
+        selector: String::new(),
+        message: format!("Could not parse feed '{}': {}", file.rel_path, err),
+        help: "Feed readers drop feeds that fail to parse. Validate the feed and fix the generating endpoint.".into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    }
+}
+
+fn check_feed(
+    index: &SiteIndex,
+    config: &Config,
+    file: &FeedFile,
+    feed: &Feed,
+    linked_routes: &mut HashSet<String>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let norm = &config.url_normalization;
+    let finding = |level, rule_id: &str, selector: String, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: file.rel_path.clone(),
+        selector,
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    // The site's origin: base_url, else the feed's own home link.
+    let site = index
+        .base_url
+        .as_deref()
+        .or(feed.home_url.as_deref())
+        .and_then(|u| Url::parse(u).ok());
+    let feed_url = site
+        .as_ref()
+        .and_then(|s| s.join(&format!("/{}", file.rel_path)).ok());
+
+    // Absolute URLs must use the production origin.
+    if let Some(base) = index.base_url.as_deref().and_then(|b| Url::parse(b).ok()) {
+        let urls = [feed.home_url.as_deref(), feed.self_url.as_deref()]
+            .into_iter()
+            .chain(feed.items.iter().map(|i| i.link.as_deref()))
+            .flatten();
+        let mut reported = HashSet::new();
+        for url in urls {
+            let Ok(parsed) = Url::parse(url) else {
+                continue;
+            };
+            if parsed.origin() != base.origin() {
+                let origin = parsed.origin().ascii_serialization();
+                if reported.insert(origin.clone()) {
+                    findings.push(finding(
+                        Level::Warning,
+                        "feed/wrong-origin",
+                        String::new(),
+                        format!(
+                            "{} feed '{}' uses '{}' instead of site.base_url '{}' (e.g. '{}')",
+                            feed.format.label(),
+                            file.rel_path,
+                            origin,
+                            base.origin().ascii_serialization(),
+                            url
+                        ),
+                        "Pass `site: context.site` to rss() and set `site` in astro.config.mjs to the production URL.",
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut dated: Vec<(i64, &str)> = Vec::new();
+    for (i, item) in feed.items.iter().enumerate() {
+        let label = item
+            .title
+            .clone()
+            .or_else(|| item.link.clone())
+            .unwrap_or_else(|| format!("item {}", i + 1));
+
+        // Item link resolves to a route in the build
+        match &item.link {
+            None => findings.push(finding(
+                Level::Warning,
+                "feed/item-link-missing",
+                format!("item '{label}'"),
+                format!("Feed item '{label}' in '{}' has no link", file.rel_path),
+                "Every item needs a link to its page.",
+            )),
+            Some(link) => {
+                let resolved = feed_url
+                    .as_ref()
+                    .and_then(|base| base.join(link).ok())
+                    .or_else(|| Url::parse(link).ok());
+                if Url::parse(link).is_err() {
+                    findings.push(finding(
+                        Level::Warning,
+                        "feed/relative-link",
+                        format!("item '{label}'"),
+                        format!("Feed item link '{link}' in '{}' is relative", file.rel_path),
+                        "Feed readers do not know the site URL; item links must be absolute. Set `site` so @astrojs/rss can build absolute links.",
+                    ));
+                }
+                let internal = resolved
+                    .as_ref()
+                    .filter(|u| site.as_ref().is_some_and(|s| s.host_str() == u.host_str()));
+                if let Some(url) = internal {
+                    let route = normalize::normalize_path(url.path(), norm);
+                    if index.route_exists(&route) {
+                        if config.feeds.check_titles {
+                            let page = &index.pages[index.route_to_index[&route]];
+                            if let (Some(item_title), Some(page_title)) =
+                                (&item.title, &page.title_text)
+                            {
+                                if !titles_match(item_title, page_title) {
+                                    findings.push(Finding {
+                                        confidence: Some(Confidence::Medium),
+                                        ..finding(
+                                            Level::Warning,
+                                            "feed/title-mismatch",
+                                            format!("item '{label}'"),
+                                            format!(
+                                                "Feed title '{}' does not match the <title> of {} ('{}')",
+                                                item_title, page.rel_path, page_title
+                                            ),
+                                            "Build feed items from the same frontmatter as the page so titles stay in sync.",
+                                        )
+                                    });
+                                }
+                            }
+                        }
+                        linked_routes.insert(route);
+                    } else {
+                        findings.push(finding(
+                            Level::Error,
+                            "feed/item-link-broken",
+                            format!("item '{label}'"),
+                            format!(
+                                "Feed item link '{link}' in '{}' does not resolve to a page (route '{route}')",
+                                file.rel_path
+                            ),
+                            "Remove the item or fix its link; subscribers land on a 404.",
+                        ));
+                    }
+                }
+            }
+        }
+
+        // GUID / id uniqueness (fall back to the link, as readers do)
+        if let Some(id) = item.id.as_deref().or(item.link.as_deref()) {
+            *ids.entry(id).or_default() += 1;
+        }
+
+        // Dates
+        match (&item.date, item.timestamp()) {
+            (Some(raw), None) => findings.push(finding(
+                Level::Warning,
+                "feed/invalid-date",
+                format!("item '{label}'"),
+                format!("Feed item '{label}' has an unparseable date '{raw}'"),
+                match feed.format {
+                    feed::FeedFormat::Rss => "RSS pubDate must be RFC 822, e.g. 'Mon, 01 Jan 2024 00:00:00 GMT'. Pass a Date object to @astrojs/rss.",
+                    _ => "Use an RFC 3339 timestamp, e.g. '2024-01-01T00:00:00Z'.",
+                },
+            )),
+            (_, Some(ts)) => dated.push((ts, item.title.as_deref().unwrap_or("?"))),
+            (None, None) => {}
+        }
+    }
+
+    let mut duplicates: Vec<(&str, usize)> = ids.into_iter().filter(|(_, n)| *n > 1).collect();
+    duplicates.sort();
+    for (id, count) in duplicates {
+        findings.push(finding(
+            Level::Warning,
+            "feed/duplicate-guid",
+            format!("guid '{id}'"),
+            format!(
+                "{count} items in '{}' share the id/guid '{id}'",
+                file.rel_path
+            ),
+            "Feed readers treat items with the same guid as one entry and hide the others. Use each post's URL as its guid.",
+        ));
+    }
+
+    // Items newest-first (or consistently oldest-first)
+    if dated.len() > 1 {
+        let descending = dated.windows(2).all(|w| w[0].0 >= w[1].0);
+        let ascending = dated.windows(2).all(|w| w[0].0 <= w[1].0);
+        if !descending && !ascending {
+            let pair = dated
+                .windows(2)
+                .find(|w| w[0].0 < w[1].0)
+                .map(|w| format!("'{}' comes before newer '{}'", w[0].1, w[1].1))
+                .unwrap_or_default();
+            findings.push(finding(
+                Level::Warning,
+                "feed/items-out-of-order",
+                String::new(),
+                format!("Items in '{}' are not sorted by date: {pair}", file.rel_path),
+                "Sort posts by date (newest first) before passing them to rss(); some readers only look at the first items.",
+            ));
+        }
+    }
+
+    findings
+}
+
+/// A feed title matches when the page `<title>` contains it, which allows
+/// layouts that append " | Site name".
+fn titles_match(item_title: &str, page_title: &str) -> bool {
+    let norm = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    norm(page_title).contains(&norm(item_title))
+}
+
+/// `*` matches a single path segment, `**` any number.
+fn build_post_patterns(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = GlobBuilder::new(pattern.trim_end_matches('/'))
+            .literal_separator(true)
+            .build()
+        {
+            builder.add(glob);
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
@@ -1,9 +1,20 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
 use rayon::prelude::*;
+use regex::Regex;
+use scraper::Selector;
 use url::Url;
+use walkdir::WalkDir;
 
 use crate::config::Config;
-use crate::discovery::SiteIndex;
+use crate::discovery::{PageInfo, SiteIndex};
+use crate::js;
 use crate::report::{Finding, Level};
+use crate::robots::Robots;
+
+/// Local files scanned for dev leftovers besides the HTML pages.
+const BUNDLE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "css", "json", "xml", "webmanifest"];
 
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     if !config.go_live.enabled {
@@ -41,8 +52,10 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         .flat_map(|page| {
             let mut f = Vec::new();
 
-            // noindex check
-            check_noindex(page, &mut f);
+            // noindex check (expected on error pages)
+            if !page.is_error_page() {
+                check_noindex(page, &mut f);
+            }
 
             // canonical origin check
             check_canonical_origin(page, &expected_origin, &mut f);
@@ -65,6 +78,9 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     // robots.txt global disallow check
     check_robots_txt_blocked(index, &mut findings);
 
+    // Staging domains, localhost URLs and debug leftovers in bundles
+    check_bundles(index, forbidden, &mut findings);
+
     findings
 }
 
@@ -274,7 +290,7 @@ fn check_sitemap_origins(
                 findings.push(Finding {
                     level: Level::Error,
                     rule_id: "golive/sitemap-origin".into(),
-                    file: "sitemap.xml".into(),
+                    file: index.sitemap_source(url).into(),
                     selector: "<loc>".into(),
                     message: format!(
                         "Sitemap entry '{}' uses '{}' instead of expected production origin '{}'",
@@ -309,39 +325,376 @@ fn check_sitemap_origins(
 }
 
 fn check_robots_txt_blocked(index: &SiteIndex, findings: &mut Vec<Finding>) {
-    let robots_path = index.dist_path.join("robots.txt");
-    let content = match std::fs::read_to_string(&robots_path) {
-        Ok(c) => c,
-        Err(_) => return,
+    let Some(robots) = Robots::load(&index.dist_path) else {
+        return;
     };
 
-    // Look for a block that globally disallows crawling: User-agent: * + Disallow: /
-    let mut current_user_agent_is_all = false;
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with('#') || trimmed.is_empty() {
-            continue;
+    // A crawler without a group of its own may not fetch the home page.
+    let verdict = robots.check("*", "/");
+    if let Some(rule) = verdict.rule.filter(|r| !r.allow && r.pattern == "/") {
+        findings.push(Finding {
+            level: Level::Error,
+            rule_id: "golive/robots-blocked".into(),
+            file: "robots.txt".into(),
+            selector: rule.directive(),
+            message: "robots.txt globally blocks all crawlers with 'Disallow: /'".into(),
+            help: "Remove 'Disallow: /' for the '*' user-agent before going live. Use specific path disallows if needed.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+}
+
+/// Patterns for `check_bundles`.
+struct BundlePatterns {
+    /// A forbidden domain or one of its subdomains; capture group `i + 1`
+    /// holds `forbidden[i]`. None without forbidden domains.
+    forbidden: Option<Regex>,
+    localhost: Regex,
+    debugger: Regex,
+    html_comment: Regex,
+    todo: Regex,
+}
+
+impl BundlePatterns {
+    fn new(forbidden: &[String]) -> Self {
+        let domains = forbidden
+            .iter()
+            .map(|d| format!("({})", regex::escape(d)))
+            .collect::<Vec<_>>()
+            .join("|");
+        Self {
+            // A trailing `.label` means the domain is only a prefix of
+            // another host (`example.com.evil.net`); the last group catches it.
+            forbidden: (!forbidden.is_empty()).then(|| {
+                Regex::new(&format!(
+                    r"(?i)\b(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)*(?:{domains})\b(\.[a-z0-9])?"
+                ))
+                .unwrap()
+            }),
+            localhost: Regex::new(
+                r#"(?i)(?:\b(?:https?|wss?)://)?\b(?:localhost|127\.0\.0\.1|0\.0\.0\.0)(?::[0-9]{2,5})?(?:/[^\s"'`<>()\\]*)?"#,
+            )
+            .unwrap(),
+            debugger: Regex::new(r"(?m)(?:^|[;{})])[ \t]*debugger[ \t]*(?:;|\}|$)").unwrap(),
+            html_comment: Regex::new(r"(?s)<!--(.*?)-->").unwrap(),
+            todo: Regex::new(r"\b(?:TODO|FIXME)\b").unwrap(),
         }
-        let lower = trimmed.to_lowercase();
-        if lower.starts_with("user-agent:") {
-            let agent = lower.trim_start_matches("user-agent:").trim();
-            current_user_agent_is_all = agent == "*";
-        } else if lower.starts_with("disallow:") && current_user_agent_is_all {
-            let path = lower.trim_start_matches("disallow:").trim();
-            if path == "/" {
-                findings.push(Finding {
+    }
+}
+
+/// A leftover in a bundle, first occurrence only.
+struct BundleHit {
+    rule_id: &'static str,
+    offset: usize,
+    /// What was found: URL, comment snippet or `debugger`.
+    value: String,
+    /// The forbidden domain for `golive/forbidden-domain`.
+    domain: Option<String>,
+    count: usize,
+}
+
+/// Scan every local JS, CSS, JSON and XML file for `forbidden_domains`,
+/// localhost URLs, `debugger` statements and HTML comments with TODO/FIXME.
+/// The page checks above only see HTML attributes; a staging API base inlined
+/// through `import.meta.env` only shows up here. Sitemaps are left to
+/// `check_sitemap_origins`. Findings name the pages that load the file,
+/// directly or through module imports.
+fn check_bundles(index: &SiteIndex, forbidden: &[String], findings: &mut Vec<Finding>) {
+    let sitemaps: HashSet<&str> = index
+        .sitemaps
+        .files
+        .iter()
+        .map(|f| f.rel_path.as_str())
+        .collect();
+    let files: Vec<PathBuf> = WalkDir::new(&index.dist_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            e.path()
+                .extension()
+                .and_then(|x| x.to_str())
+                .is_some_and(|x| BUNDLE_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        })
+        .map(|e| e.into_path())
+        .filter(|p| !sitemaps.contains(index.dist_rel_path(p).as_str()))
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+
+    let loaders = asset_loaders(index);
+    let patterns = BundlePatterns::new(forbidden);
+    let mut found: Vec<Finding> = files
+        .par_iter()
+        .flat_map_iter(|path| {
+            let rel = index.dist_rel_path(path);
+            let is_js = rel.ends_with(".js") || rel.ends_with(".mjs") || rel.ends_with(".cjs");
+            let hits = std::fs::read(path)
+                .map(|bytes| {
+                    scan_bundle(&String::from_utf8_lossy(&bytes), is_js, forbidden, &patterns)
+                })
+                .unwrap_or_default();
+            let loaded_by = describe_loaders(loaders.get(path));
+            hits.into_iter().map(move |hit| {
+                let times = if hit.count > 1 {
+                    format!(" ({} occurrences)", hit.count)
+                } else {
+                    String::new()
+                };
+                let (message, help) = match hit.rule_id {
+                    "golive/forbidden-domain" => (
+                        format!(
+                            "Bundle contains forbidden domain '{}': {}{times}",
+                            hit.domain.as_deref().unwrap_or(""),
+                            hit.value
+                        ),
+                        "URLs in bundles usually come from an env variable read at build time (`import.meta.env.PUBLIC_API_URL`). Build with the production environment.",
+                    ),
+                    "golive/localhost-url" => (
+                        format!("Bundle points to a local development server: {}{times}", hit.value),
+                        "A dev-server URL was baked in at build time. Check `.env` files and fallbacks such as `import.meta.env.PUBLIC_API_URL ?? 'http://localhost:4321'`.",
+                    ),
+                    "golive/debugger" => (
+                        format!("`debugger` statement in bundle{times}"),
+                        "Remove the statement, or strip them from production builds with `vite: { esbuild: { drop: ['debugger'] } }`.",
+                    ),
+                    _ => (
+                        format!("HTML comment with TODO/FIXME in bundle: '{}'{times}", hit.value),
+                        "Comments in component templates and content end up in the bundle. Resolve the note or remove the comment.",
+                    ),
+                };
+                Finding {
                     level: Level::Error,
-                    rule_id: "golive/robots-blocked".into(),
-                    file: "robots.txt".into(),
-                    selector: "Disallow: /".into(),
-                    message: "robots.txt globally blocks all crawlers with 'Disallow: /'".into(),
-                    help: "Remove 'Disallow: /' for the '*' user-agent before going live. Use specific path disallows if needed.".into(),
+                    rule_id: hit.rule_id.into(),
+                    file: rel.clone(),
+                    selector: format!("byte {}", hit.offset),
+                    message: format!("{message}{loaded_by}"),
+                    help: help.into(),
                     suggestion: None,
                     source_hint: None,
                     confidence: None,
-                });
-                return;
+                }
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| a.file.cmp(&b.file));
+    findings.extend(found);
+}
+
+fn scan_bundle(
+    content: &str,
+    is_js: bool,
+    forbidden: &[String],
+    patterns: &BundlePatterns,
+) -> Vec<BundleHit> {
+    let mut hits: Vec<BundleHit> = Vec::new();
+    let mut add =
+        |rule_id: &'static str, offset: usize, value: String, domain: Option<String>| match hits
+            .iter_mut()
+            .find(|h| h.rule_id == rule_id && h.value == value)
+        {
+            Some(hit) => hit.count += 1,
+            None => hits.push(BundleHit {
+                rule_id,
+                offset,
+                value,
+                domain,
+                count: 1,
+            }),
+        };
+
+    if let Some(regex) = &patterns.forbidden {
+        for caps in regex.captures_iter(content) {
+            let Some(m) = caps
+                .get(0)
+                .filter(|_| caps.get(forbidden.len() + 1).is_none())
+            else {
+                continue;
+            };
+            // `\b` also matches after `-`: `my-staging.net` is another host.
+            let prev = content.as_bytes()[..m.start()].last();
+            if prev.is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'-') {
+                continue;
             }
+            let domain = (0..forbidden.len())
+                .find(|i| caps.get(i + 1).is_some())
+                .map(|i| forbidden[i].clone());
+            let url = surrounding_url(content, m.start(), m.end());
+            add(
+                "golive/forbidden-domain",
+                m.start(),
+                url.to_string(),
+                domain,
+            );
         }
     }
+
+    for m in patterns.localhost.find_iter(content) {
+        let value = m.as_str();
+        let has_scheme = value.contains("://");
+        let host_and_rest = value.split_once("://").map_or(value, |(_, rest)| rest);
+        let (host, path) = host_and_rest
+            .split_once('/')
+            .map_or((host_and_rest, ""), |(h, p)| (h, p));
+        let has_port = host.contains(':');
+        // A bare `localhost` is a hostname comparison, and `new URL(path,
+        // "http://localhost")` a parse base; neither is a request target.
+        if !has_port && (!has_scheme || path.is_empty()) {
+            continue;
+        }
+        add("golive/localhost-url", m.start(), value.to_string(), None);
+    }
+
+    if is_js {
+        for m in patterns.debugger.find_iter(content) {
+            let offset = m.start() + m.as_str().find("debugger").unwrap_or(0);
+            add("golive/debugger", offset, "debugger".into(), None);
+        }
+    }
+
+    for caps in patterns.html_comment.captures_iter(content) {
+        let (Some(whole), Some(inner)) = (caps.get(0), caps.get(1)) else {
+            continue;
+        };
+        if patterns.todo.is_match(inner.as_str()) {
+            let mut snippet = inner
+                .as_str()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            if snippet.chars().count() > 60 {
+                snippet = snippet.chars().take(60).collect::<String>() + "…";
+            }
+            add("golive/todo-comment", whole.start(), snippet, None);
+        }
+    }
+
+    hits.sort_by_key(|h| h.offset);
+    hits
+}
+
+/// The URL (or bare host) around `start..end`: the run of characters that
+/// can appear in a URL, capped to keep messages short.
+fn surrounding_url(content: &str, start: usize, end: usize) -> &str {
+    let stop = |c: char| c.is_whitespace() || "\"'`<>()[]{},;\\".contains(c);
+    let from = content[..start]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| !stop(c))
+        .last()
+        .map_or(start, |(i, _)| i);
+    let to = content[end..]
+        .char_indices()
+        .find(|&(_, c)| stop(c))
+        .map_or(content.len(), |(i, _)| end + i);
+    let url = &content[from..to];
+    match url.char_indices().nth(120) {
+        Some((i, _)) => &url[..i],
+        None => url,
+    }
+}
+
+/// Local file -> routes of the pages that load it: `<script src>`, `<link
+/// href>`, island component and renderer URLs, inline module imports, and
+/// everything those scripts import.
+fn asset_loaders(index: &SiteIndex) -> HashMap<PathBuf, BTreeSet<String>> {
+    let direct: Vec<Vec<PathBuf>> = index
+        .pages
+        .par_iter()
+        .map(|page| page_assets(index, page))
+        .collect();
+
+    let mut imports: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    let mut frontier: Vec<PathBuf> = direct.iter().flatten().cloned().collect();
+    while let Some(path) = frontier.pop() {
+        if imports.contains_key(&path) {
+            continue;
+        }
+        let rel = index.dist_rel_path(&path);
+        let deps: Vec<PathBuf> = if rel.ends_with(".js") || rel.ends_with(".mjs") {
+            std::fs::read_to_string(&path)
+                .map(|src| {
+                    js::module_imports(&src)
+                        .into_iter()
+                        .filter(|m| js::is_local_specifier(&m.specifier))
+                        .filter_map(|m| index.resolve_local_asset(&m.specifier, &rel))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        frontier.extend(deps.iter().cloned());
+        imports.insert(path, deps);
+    }
+
+    let mut loaders: HashMap<PathBuf, BTreeSet<String>> = HashMap::new();
+    for (page, assets) in index.pages.iter().zip(&direct) {
+        let mut seen: HashSet<&PathBuf> = HashSet::new();
+        let mut stack: Vec<&PathBuf> = assets.iter().collect();
+        while let Some(path) = stack.pop() {
+            if !seen.insert(path) {
+                continue;
+            }
+            loaders
+                .entry(path.clone())
+                .or_default()
+                .insert(page.route.clone());
+            stack.extend(imports.get(path).into_iter().flatten());
+        }
+    }
+    loaders
+}
+
+fn page_assets(index: &SiteIndex, page: &PageInfo) -> Vec<PathBuf> {
+    let html = page.parse_html();
+    let resolve = |url: &str| index.resolve_local_asset(url, &page.rel_path);
+    let sel = Selector::parse("script[src], link[href], astro-island").unwrap();
+    let inline_module_sel = Selector::parse("script[type='module']:not([src])").unwrap();
+
+    let mut assets = Vec::new();
+    for el in html.select(&sel) {
+        let attrs = el.value();
+        for attr in [
+            "src",
+            "href",
+            "component-url",
+            "renderer-url",
+            "before-hydration-url",
+        ] {
+            if let Some(path) = attrs.attr(attr).filter(|u| !u.is_empty()).and_then(resolve) {
+                assets.push(path);
+            }
+        }
+    }
+    for el in html.select(&inline_module_sel) {
+        let text: String = el.text().collect();
+        assets.extend(
+            js::module_imports(&text)
+                .into_iter()
+                .filter(|m| js::is_local_specifier(&m.specifier))
+                .filter_map(|m| resolve(&m.specifier)),
+        );
+    }
+    assets
+}
+
+/// ` — loaded by '/', '/blog/' and 3 more page(s)`, or nothing for files no
+/// page references (fetched at runtime or unused).
+fn describe_loaders(routes: Option<&BTreeSet<String>>) -> String {
+    let Some(routes) = routes.filter(|r| !r.is_empty()) else {
+        return String::new();
+    };
+    let listed: Vec<String> = routes.iter().take(3).map(|r| format!("'{r}'")).collect();
+    match routes.len() {
+        n if n > 3 => format!(
+            " — loaded by {} and {} more page(s)",
+            listed.join(", "),
+            n - 3
+        ),
+        _ => format!(" — loaded by {}", listed.join(", ")),
+    }
 }
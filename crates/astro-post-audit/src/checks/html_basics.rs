@@ -22,7 +22,9 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
             }
 
             // meta description: presence check + length check (independent)
-            check_meta_description(page, config, &mut findings);
+            if !page.is_error_page() {
+                check_meta_description(page, config, &mut findings);
+            }
 
             // viewport
             if config.html_basics.viewport_required {
@@ -0,0 +1,454 @@
+use std::collections::{BTreeMap, HashSet};
+
+use rayon::prelude::*;
+use scraper::Selector;
+use url::Url;
+
+use crate::config::Config;
+use crate::discovery::crawl::{self, HttpResponse};
+use crate::discovery::{PageInfo, SiteIndex};
+use crate::report::{Confidence, Finding, Level};
+
+/// Extensions whose responses compress well.
+const TEXT_EXTENSIONS: &[&str] = &[
+    "js", "mjs", "css", "svg", "json", "xml", "txt", "html", "htm", "map", "wasm",
+];
+
+/// Expected media types by extension. The first entry is suggested.
+const MEDIA_TYPES: &[(&str, &[&str])] = &[
+    ("js", &["text/javascript", "application/javascript"]),
+    ("mjs", &["text/javascript", "application/javascript"]),
+    ("css", &["text/css"]),
+    ("svg", &["image/svg+xml"]),
+    ("json", &["application/json"]),
+    ("wasm", &["application/wasm"]),
+    ("woff2", &["font/woff2"]),
+    ("woff", &["font/woff"]),
+    ("png", &["image/png"]),
+    ("jpg", &["image/jpeg"]),
+    ("jpeg", &["image/jpeg"]),
+    ("webp", &["image/webp"]),
+    ("avif", &["image/avif"]),
+    ("gif", &["image/gif"]),
+    ("ico", &["image/x-icon", "image/vnd.microsoft.icon"]),
+];
+
+/// Parsed `Cache-Control` directives that matter for a static site.
+#[derive(Debug, Default)]
+struct CacheControl {
+    max_age: Option<u64>,
+    immutable: bool,
+    no_store: bool,
+    no_cache: bool,
+}
+
+impl CacheControl {
+    fn parse(value: Option<&str>) -> Self {
+        let mut cc = Self::default();
+        for directive in value.unwrap_or("").split(',') {
+            let (name, arg) = directive
+                .split_once('=')
+                .map_or((directive, None), |(n, a)| (n, Some(a)));
+            match name.trim().to_ascii_lowercase().as_str() {
+                "max-age" => cc.max_age = arg.and_then(|a| a.trim().trim_matches('"').parse().ok()),
+                "immutable" => cc.immutable = true,
+                "no-store" => cc.no_store = true,
+                "no-cache" => cc.no_cache = true,
+                _ => {}
+            }
+        }
+        cc
+    }
+
+    /// Seconds a browser may reuse the response without revalidating.
+    fn freshness(&self) -> u64 {
+        if self.no_store || self.no_cache {
+            0
+        } else {
+            self.max_age.unwrap_or(0)
+        }
+    }
+}
+
+/// Check the response headers a server actually sends (crawl mode): HTML must
+/// not be cached so long that a deploy goes unseen, hashed `_astro/` assets
+/// should be cached for a year and marked immutable, text responses should be
+/// compressed, Content-Type and charset must match the content, and
+/// `X-Robots-Tag` must not silently de-index pages.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.http_headers;
+    if !cfg.enabled || index.crawl.is_none() {
+        return Vec::new();
+    }
+
+    let mut findings: Vec<Finding> = index
+        .pages
+        .par_iter()
+        .filter_map(|page| Some((page, page.http.as_ref()?)))
+        .flat_map_iter(|(page, http)| check_page(index, config, page, http))
+        .collect();
+
+    if cfg.check_assets {
+        findings.extend(check_assets(index, config));
+    }
+    findings
+}
+
+fn check_page(
+    index: &SiteIndex,
+    config: &Config,
+    page: &PageInfo,
+    http: &HttpResponse,
+) -> Vec<Finding> {
+    let cfg = &config.http_headers;
+    let mut findings = Vec::new();
+    let finding = |level, rule_id: &str, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: page.rel_path.clone(),
+        selector: String::new(),
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    let cache = CacheControl::parse(http.header("cache-control"));
+    if cache.immutable || cache.freshness() > cfg.html_max_age {
+        findings.push(Finding {
+            suggestion: Some("Cache-Control: public, max-age=0, must-revalidate".into()),
+            ..finding(
+                Level::Warning,
+                "headers/html-cache-too-long",
+                format!(
+                    "HTML is cached for {} (Cache-Control: {})",
+                    if cache.immutable {
+                        "ever (immutable)".to_string()
+                    } else {
+                        format!("{}s", cache.freshness())
+                    },
+                    http.header("cache-control").unwrap_or("")
+                ),
+                "Visitors keep old HTML after a deploy, which points at hashed assets that no longer exist. Let HTML revalidate.",
+            )
+        });
+    }
+
+    if http.header("content-encoding").is_none()
+        && page.html_content.len() > cfg.compression_min_bytes
+    {
+        findings.push(finding(
+            Level::Warning,
+            "headers/compression-missing",
+            format!(
+                "HTML ({} bytes) is served uncompressed",
+                page.html_content.len()
+            ),
+            "Enable gzip or brotli on the server or CDN.",
+        ));
+    }
+
+    let header_charset = http
+        .header("content-type")
+        .and_then(charset_param)
+        .map(|c| normalize_charset(&c));
+    let html = page.parse_html();
+    let meta_charset = meta_charset(&html).map(|c| normalize_charset(&c));
+    match (&header_charset, &meta_charset) {
+        (None, None) => findings.push(Finding {
+            suggestion: Some("Content-Type: text/html; charset=utf-8".into()),
+            ..finding(
+                Level::Warning,
+                "headers/charset-missing",
+                "Neither the Content-Type header nor the page declares a charset".into(),
+                "Browsers fall back to a locale-dependent encoding and may garble non-ASCII text.",
+            )
+        }),
+        (Some(header), Some(meta)) if header != meta => findings.push(Finding {
+            suggestion: Some(format!("Content-Type: text/html; charset={meta}")),
+            ..finding(
+                Level::Error,
+                "headers/charset-mismatch",
+                format!("Content-Type header says charset={header} but the page declares {meta}"),
+                "The header wins, so the page is decoded with the wrong charset.",
+            )
+        }),
+        _ => {}
+    }
+
+    let robots: Vec<String> = http
+        .header_values("x-robots-tag")
+        .flat_map(robots_directives)
+        .collect();
+    if let Some(directive) = robots.iter().find(|d| *d == "noindex" || *d == "none") {
+        let in_sitemap = page
+            .absolute_url
+            .as_ref()
+            .is_some_and(|url| index.sitemap_urls.contains(url));
+        if !page.noindex {
+            findings.push(Finding {
+                confidence: Some(Confidence::Medium),
+                ..finding(
+                    if in_sitemap {
+                        Level::Error
+                    } else {
+                        Level::Warning
+                    },
+                    "headers/x-robots-noindex",
+                    format!(
+                        "X-Robots-Tag: {directive} removes this page from search results{}",
+                        if in_sitemap {
+                            ", yet it is listed in the sitemap"
+                        } else {
+                            ""
+                        }
+                    ),
+                    "Header directives are invisible in the HTML. Remove the header rule (often a staging setting left in _headers or middleware) or drop the page from the sitemap.",
+                )
+            });
+        }
+    }
+
+    findings
+}
+
+/// Request the local assets referenced by crawled pages and check caching,
+/// compression and Content-Type.
+fn check_assets(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.http_headers;
+    let Some(start) = index
+        .crawl
+        .as_ref()
+        .and_then(|c| Url::parse(&c.start_url).ok())
+    else {
+        return Vec::new();
+    };
+    let sel = Selector::parse(
+        "script[src], link[rel~='stylesheet'][href], link[rel~='modulepreload'][href], link[rel~='preload'][href], link[rel~='icon'][href], img[src]",
+    )
+    .unwrap();
+
+    // Asset URL -> number of referencing pages.
+    let mut assets: BTreeMap<String, usize> = BTreeMap::new();
+    for page in &index.pages {
+        let Some(page_url) = page.http.as_ref().and_then(|h| Url::parse(&h.url).ok()) else {
+            continue;
+        };
+        let html = page.parse_html();
+        let mut seen = HashSet::new();
+        for el in html.select(&sel) {
+            let Some(reference) = el.value().attr("src").or_else(|| el.value().attr("href")) else {
+                continue;
+            };
+            let Ok(mut url) = page_url.join(reference) else {
+                continue;
+            };
+            if url.origin() != start.origin() {
+                continue;
+            }
+            url.set_fragment(None);
+            if seen.insert(url.to_string()) {
+                *assets.entry(url.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    let agent = crawl::new_agent(config.crawl.timeout_ms);
+    let responses: Vec<(String, usize, HttpResponse, usize)> = assets
+        .into_iter()
+        .take(cfg.max_assets)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .filter_map(|(url, count)| {
+            let (response, body) = crawl::get(&agent, &url).ok()?;
+            (200..300)
+                .contains(&response.status)
+                .then_some((url, count, response, body.len()))
+        })
+        .collect();
+
+    let mut findings = Vec::new();
+    for (url, count, response, size) in responses {
+        let path = Url::parse(&url).map_or(url.clone(), |u| u.path().to_string());
+        let ext = path
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        let file = path.trim_start_matches('/').to_string();
+        let referenced = format!("referenced by {count} page(s)");
+        let finding = |level, rule_id: &str, message: String, help: &str| Finding {
+            level,
+            rule_id: rule_id.into(),
+            file: file.clone(),
+            selector: String::new(),
+            message,
+            help: help.into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        };
+
+        let cache_header = response.header("cache-control").unwrap_or("");
+        let cache = CacheControl::parse(Some(cache_header));
+        if path.starts_with("/_astro/") {
+            if cache.freshness() < cfg.asset_min_max_age || !cache.immutable {
+                findings.push(Finding {
+                    suggestion: Some("Cache-Control: public, max-age=31536000, immutable".into()),
+                    ..finding(
+                        Level::Warning,
+                        "headers/asset-cache-short",
+                        format!(
+                            "Hashed asset {path} is not cached long-term (Cache-Control: {}; {referenced})",
+                            if cache_header.is_empty() { "none" } else { cache_header }
+                        ),
+                        "Files under _astro/ change name whenever their content changes, so they can be cached for a year and marked immutable.",
+                    )
+                });
+            }
+        } else if cache.immutable {
+            findings.push(finding(
+                Level::Warning,
+                "headers/asset-cache-immutable",
+                format!("Unhashed asset {path} is marked immutable ({referenced})"),
+                "Its URL does not change when the file does, so visitors never see updates. Reserve immutable for hashed files.",
+            ));
+        }
+
+        let content_type = response
+            .header("content-type")
+            .map(|ct| {
+                ct.split(';')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .unwrap_or_default();
+        if let Some((_, expected)) = MEDIA_TYPES.iter().find(|(e, _)| *e == ext) {
+            if !expected.contains(&content_type.as_str()) {
+                let blocking = matches!(ext.as_str(), "js" | "mjs" | "css" | "wasm");
+                findings.push(Finding {
+                    suggestion: Some(format!("Content-Type: {}", expected[0])),
+                    ..finding(
+                        if blocking { Level::Error } else { Level::Warning },
+                        "headers/content-type-mismatch",
+                        format!(
+                            "{path} is served as '{}' instead of {} ({referenced})",
+                            if content_type.is_empty() { "(none)" } else { &content_type },
+                            expected[0]
+                        ),
+                        if blocking {
+                            "Browsers refuse module scripts, stylesheets and WebAssembly with the wrong MIME type."
+                        } else {
+                            "Wrong types break rendering under nosniff and confuse caches."
+                        },
+                    )
+                });
+            }
+        }
+
+        if TEXT_EXTENSIONS.contains(&ext.as_str())
+            && size > cfg.compression_min_bytes
+            && response.header("content-encoding").is_none()
+        {
+            findings.push(finding(
+                Level::Warning,
+                "headers/compression-missing",
+                format!("{path} ({size} bytes) is served uncompressed ({referenced})"),
+                "Enable gzip or brotli on the server or CDN.",
+            ));
+        }
+    }
+    findings
+}
+
+/// `charset` parameter of a Content-Type value.
+fn charset_param(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Charset declared by `<meta charset>` or `<meta http-equiv="Content-Type">`.
+fn meta_charset(html: &scraper::Html) -> Option<String> {
+    let sel = Selector::parse("meta[charset], meta[http-equiv][content]").unwrap();
+    html.select(&sel).find_map(|el| {
+        let el = el.value();
+        if let Some(charset) = el.attr("charset") {
+            return Some(charset.trim().to_string());
+        }
+        el.attr("http-equiv")
+            .filter(|e| e.eq_ignore_ascii_case("content-type"))
+            .and(el.attr("content"))
+            .and_then(charset_param)
+    })
+}
+
+/// Case-fold and treat "utf8" as "utf-8".
+fn normalize_charset(charset: &str) -> String {
+    match charset.to_ascii_lowercase().as_str() {
+        "utf8" => "utf-8".into(),
+        other => other.into(),
+    }
+}
+
+/// Directives of one X-Robots-Tag value that apply to all crawlers or to
+/// Google/Bing. `unavailable_after: <date>` and other bots are ignored.
+fn robots_directives(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let directives = match value.split_once(':') {
+        Some((bot, rest))
+            if !bot.contains(',')
+                && !bot.trim().eq_ignore_ascii_case("unavailable_after")
+                && !bot.trim().eq_ignore_ascii_case("max-snippet")
+                && !bot.trim().eq_ignore_ascii_case("max-image-preview")
+                && !bot.trim().eq_ignore_ascii_case("max-video-preview") =>
+        {
+            let bot = bot.trim().to_ascii_lowercase();
+            if !matches!(bot.as_str(), "googlebot" | "bingbot" | "*") {
+                return Vec::new();
+            }
+            rest
+        }
+        _ => value,
+    };
+    directives
+        .split(',')
+        .map(|d| d.trim().to_ascii_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_control_freshness() {
+        let cc = CacheControl::parse(Some("public, max-age=31536000, immutable"));
+        assert_eq!(cc.freshness(), 31_536_000);
+        assert!(cc.immutable);
+        assert_eq!(
+            CacheControl::parse(Some("max-age=600, no-cache")).freshness(),
+            0
+        );
+        assert_eq!(CacheControl::parse(None).freshness(), 0);
+    }
+
+    #[test]
+    fn robots_directives_by_bot() {
+        assert_eq!(
+            robots_directives("noindex, nofollow"),
+            ["noindex", "nofollow"]
+        );
+        assert_eq!(robots_directives("googlebot: noindex"), ["noindex"]);
+        assert!(robots_directives("otherbot: noindex").is_empty());
+        assert_eq!(robots_directives("max-snippet:20"), ["max-snippet:20"]);
+    }
+}
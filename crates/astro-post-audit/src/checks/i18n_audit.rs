@@ -124,25 +124,13 @@ fn infer_locale_from_route(route: &str) -> Option<&str> {
     if first.is_empty() {
         return None;
     }
-    if is_locale_token(first) {
+    if normalize::is_locale_token(first) {
         Some(first)
     } else {
         None
     }
 }
 
-fn is_locale_token(token: &str) -> bool {
-    let parts: Vec<&str> = token.split('-').collect();
-    match parts.as_slice() {
-        [lang] if lang.len() == 2 => lang.chars().all(|c| c.is_ascii_alphabetic()),
-        [lang, region] if lang.len() == 2 && (region.len() == 2 || region.len() == 3) => {
-            lang.chars().all(|c| c.is_ascii_alphabetic())
-                && region.chars().all(|c| c.is_ascii_alphabetic())
-        }
-        _ => false,
-    }
-}
-
 fn normalize_lang(lang: &str) -> String {
     lang.trim().to_lowercase().replace('_', "-")
 }
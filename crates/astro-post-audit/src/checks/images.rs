@@ -1,13 +1,31 @@
 use rayon::prelude::*;
 use scraper::Selector;
 
+use crate::checks::page_weight::parse_srcset;
 use crate::config::Config;
 use crate::discovery::SiteIndex;
-use crate::report::{Finding, Level};
+use crate::report::{Confidence, Finding, Level};
 
 const LEGACY_IMAGE_EXTENSIONS: &[&str] = &[".jpg", ".jpeg", ".png", ".gif"];
 
+/// Raster formats worth flagging when served unprocessed from `public/`.
+const RASTER_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "avif", "bmp", "tif", "tiff",
+];
+
+/// Relative difference between declared and intrinsic aspect ratio that is
+/// still treated as rounding (e.g. 1200×675 vs 16:9).
+const ASPECT_TOLERANCE: f64 = 0.05;
+
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let mut findings = check_attributes(index, config);
+    if config.images.check_intrinsic_size {
+        findings.extend(check_intrinsic_sizes(index, config));
+    }
+    findings
+}
+
+fn check_attributes(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     let img = &config.images;
     if !img.check_missing_dimensions
         && !img.warn_missing_lazy
@@ -134,3 +152,185 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         })
         .collect()
 }
+
+/// Compare markup against the real pixel size of local images (header read
+/// only): files much larger than their rendered slot, `width`/`height` with the
+/// wrong aspect ratio (layout shift once the image loads), `srcset` `w`
+/// descriptors that lie about the candidate, and big files copied verbatim
+/// from `public/` instead of going through the `astro:assets` pipeline.
+fn check_intrinsic_sizes(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let img = &config.images;
+    let img_sel = Selector::parse("img[src]").unwrap();
+    let srcset_sel = Selector::parse("img[srcset], source[srcset]").unwrap();
+
+    index
+        .pages
+        .par_iter()
+        .flat_map(|page| {
+            let mut findings = Vec::new();
+            let html = page.parse_html();
+            let intrinsic = |url: &str| {
+                index
+                    .resolve_local_asset(url, &page.rel_path)
+                    .and_then(|path| imagesize::size(&path).ok().map(|d| (path, d)))
+            };
+
+            for el in html.select(&img_sel) {
+                let attrs = el.value();
+                let src = attrs.attr("src").unwrap_or("");
+                let Some((path, dim)) = intrinsic(src) else {
+                    continue;
+                };
+                let declared_w = attrs.attr("width").and_then(parse_px);
+                let declared_h = attrs.attr("height").and_then(parse_px);
+
+                // Aspect ratio of width/height attributes vs the file
+                if let (Some(w), Some(h)) = (declared_w, declared_h) {
+                    if w > 0 && h > 0 && dim.width > 0 && dim.height > 0 {
+                        let declared = w as f64 / h as f64;
+                        let actual = dim.width as f64 / dim.height as f64;
+                        if ((declared - actual) / actual).abs() > ASPECT_TOLERANCE {
+                            findings.push(Finding {
+                                level: Level::Warning,
+                                rule_id: "images/aspect-mismatch".into(),
+                                file: page.rel_path.clone(),
+                                selector: format!("img[src='{}']", src),
+                                message: format!(
+                                    "width/height ({}×{}) disagree with the image's aspect ratio ({}×{}): src='{}'",
+                                    w, h, dim.width, dim.height, src
+                                ),
+                                help: "The browser reserves space from width/height; a different ratio shifts layout once the image loads. Use the file's real ratio, or set object-fit if cropping is intended.".into(),
+                                suggestion: declared_w.map(|w| {
+                                    let h = (w as f64 * dim.height as f64 / dim.width as f64).round();
+                                    format!("width=\"{w}\" height=\"{h}\"")
+                                }),
+                                source_hint: None,
+                                confidence: Some(Confidence::Medium),
+                            });
+                        }
+                    }
+                }
+
+                // Intrinsic size vs rendered slot
+                let slot = attrs
+                    .attr("sizes")
+                    .and_then(largest_fixed_slot)
+                    .or(declared_w);
+                let largest = attrs
+                    .attr("srcset")
+                    .map(parse_srcset)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|c| c.width.is_some())
+                    .max_by_key(|c| c.width)
+                    .and_then(|c| intrinsic(&c.url).map(|(_, d)| (c.url, d)))
+                    .unwrap_or((src.to_string(), dim));
+                if let Some(slot) = slot.filter(|s| *s > 0) {
+                    let limit = slot as f64 * img.max_oversize_ratio;
+                    if largest.1.width as f64 > limit {
+                        findings.push(Finding {
+                            level: Level::Warning,
+                            rule_id: "images/oversized".into(),
+                            file: page.rel_path.clone(),
+                            selector: format!("img[src='{}']", src),
+                            message: format!(
+                                "Image is {}px wide but rendered at most {}px ({:.1}× larger): '{}'",
+                                largest.1.width,
+                                slot,
+                                largest.1.width as f64 / slot as f64,
+                                largest.0
+                            ),
+                            help: "Resize the source or let <Image>/<Picture> from astro:assets generate widths that match the layout.".into(),
+                            suggestion: None,
+                            source_hint: None,
+                            confidence: Some(Confidence::Medium),
+                        });
+                    }
+                }
+
+                // Large raster images that bypassed the _astro/ pipeline
+                let rel = index.dist_rel_path(&path);
+                let is_raster = path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                    .is_some_and(|e| RASTER_EXTENSIONS.contains(&e.as_str()));
+                if is_raster && !rel.starts_with("_astro/") {
+                    let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                    if bytes / 1024 > img.public_max_kb {
+                        findings.push(Finding {
+                            level: Level::Warning,
+                            rule_id: "images/unoptimized-public".into(),
+                            file: page.rel_path.clone(),
+                            selector: format!("img[src='{}']", src),
+                            message: format!(
+                                "{}KB image served as-is from public/ ({}×{}): '{}'",
+                                bytes / 1024,
+                                dim.width,
+                                dim.height,
+                                rel
+                            ),
+                            help: "Move the image to src/ and render it with <Image>/<Picture> from astro:assets so it is resized, converted to WebP/AVIF and hashed.".into(),
+                            suggestion: None,
+                            source_hint: None,
+                            confidence: Some(Confidence::Medium),
+                        });
+                    }
+                }
+            }
+
+            // srcset w descriptors vs the candidate's real width
+            for el in html.select(&srcset_sel) {
+                let srcset = el.value().attr("srcset").unwrap_or("");
+                for candidate in parse_srcset(srcset) {
+                    let Some(w) = candidate.width else {
+                        continue;
+                    };
+                    let Some((_, dim)) = intrinsic(&candidate.url) else {
+                        continue;
+                    };
+                    if dim.width.abs_diff(w as usize) > 1 {
+                        findings.push(Finding {
+                            level: Level::Warning,
+                            rule_id: "images/srcset-width-mismatch".into(),
+                            file: page.rel_path.clone(),
+                            selector: format!("{}[srcset]", el.value().name()),
+                            message: format!(
+                                "srcset declares '{}' as {}w but the file is {}px wide",
+                                candidate.url, w, dim.width
+                            ),
+                            help: "Browsers pick candidates by the w descriptor; a wrong value makes them download too large or too blurry an image.".into(),
+                            suggestion: Some(format!("{} {}w", candidate.url, dim.width)),
+                            source_hint: None,
+                            confidence: None,
+                        });
+                    }
+                }
+            }
+
+            findings
+        })
+        .collect()
+}
+
+/// Integer pixel value of a `width`/`height` attribute (`"640"`, `"640px"`).
+fn parse_px(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .trim_end_matches("px")
+        .parse::<f64>()
+        .ok()
+        .map(|v| v.round() as u32)
+}
+
+/// The widest fixed (`px`) slot in a `sizes` attribute. Viewport-relative
+/// slots have no upper bound, so only px lengths count.
+fn largest_fixed_slot(sizes: &str) -> Option<u32> {
+    sizes
+        .split(',')
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            let length = entry.rfind(')').map_or(entry, |i| entry[i + 1..].trim());
+            length.strip_suffix("px").and_then(parse_px)
+        })
+        .max()
+}
@@ -268,7 +268,7 @@ fn check_orphan_pages(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     index
         .pages
         .iter()
-        .filter(|page| !linked_routes.contains(&page.route))
+        .filter(|page| !linked_routes.contains(&page.route) && !page.is_error_page())
         .map(|page| Finding {
             level: Level::Warning,
             rule_id: "links/orphan-page".into(),
@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+
+use percent_encoding::percent_decode_str;
+
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::llms::{self, ProblemKind};
+use crate::normalize;
+use crate::report::{Finding, Level};
+
+/// Validate `dist/llms.txt` against the proposed format (H1 title, blockquote
+/// summary, H2 sections of `[name](url): notes` lists), check that every
+/// internal link in it and in `llms-full.txt` resolves, and report indexable
+/// pages linked from the home page that llms.txt leaves out.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.llms_txt;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let mut findings = Vec::new();
+    let Ok(content) = std::fs::read_to_string(index.dist_path.join("llms.txt")) else {
+        if cfg.require {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "llms-txt/missing".into(),
+                file: "llms.txt".into(),
+                selector: String::new(),
+                message: "llms.txt not found in dist directory".into(),
+                help: "Add public/llms.txt, or set `llms_txt.generate` to write a draft from your pages.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+        return findings;
+    };
+    let doc = llms::parse(&content);
+
+    for problem in &doc.problems {
+        let (level, rule_id, help) = match problem.kind {
+            ProblemKind::NoTitle => (
+                Level::Error,
+                "llms-txt/no-title",
+                "The first line must be `# ` followed by the site or project name.",
+            ),
+            ProblemKind::Heading => (
+                Level::Warning,
+                "llms-txt/invalid-structure",
+                "Use one H1 for the title and H2 headings for link sections; put sub-structure into the link notes.",
+            ),
+            ProblemKind::ListItem => (
+                Level::Warning,
+                "llms-txt/invalid-link-item",
+                "Section entries are `- [Page title](https://example.com/page/): optional notes`.",
+            ),
+        };
+        findings.push(Finding {
+            level,
+            rule_id: rule_id.into(),
+            file: "llms.txt".into(),
+            selector: format!("line {}", problem.line),
+            message: problem.message.clone(),
+            help: help.into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
+    if doc.title.is_some() && doc.summary.is_none() {
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "llms-txt/no-summary".into(),
+            file: "llms.txt".into(),
+            selector: String::new(),
+            message: "llms.txt has no blockquote summary after the title".into(),
+            help: "Add a `> ` line right after the title that says what the site is about; models read it first.".into(),
+            suggestion: index
+                .route_to_index
+                .get("/")
+                .and_then(|&i| index.pages[i].meta_description.as_deref())
+                .map(|d| format!("> {}", d.trim())),
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
+    for (i, section) in doc.sections.iter().enumerate() {
+        let end = doc.sections.get(i + 1).map_or(usize::MAX, |next| next.line);
+        if !doc
+            .links
+            .iter()
+            .any(|l| l.line > section.line && l.line < end)
+        {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "llms-txt/empty-section".into(),
+                file: "llms.txt".into(),
+                selector: format!("line {}", section.line),
+                message: format!("Section '{}' lists no links", section.name),
+                help: "Each H2 section is a list of links. Fill it or remove the heading.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+    }
+
+    let mut listed: HashSet<String> = HashSet::new();
+    for link in &doc.links {
+        match resolve(index, config, &link.url) {
+            Some(Target::Route(route)) => {
+                listed.insert(route);
+            }
+            Some(Target::File) | None => {}
+            Some(Target::Missing(path)) => findings.push(broken_link("llms.txt", link, &path)),
+        }
+    }
+    if let Ok(full) = std::fs::read_to_string(index.dist_path.join("llms-full.txt")) {
+        let full_doc = llms::parse(&full);
+        // Full-text files hold whole documents, so only the title is checked.
+        if let Some(problem) = full_doc
+            .problems
+            .iter()
+            .find(|p| p.kind == ProblemKind::NoTitle)
+        {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "llms-txt/no-title".into(),
+                file: "llms-full.txt".into(),
+                selector: format!("line {}", problem.line),
+                message: "llms-full.txt must start with an H1 title (`# Site name`)".into(),
+                help: "Start the file with the same `# ` title as llms.txt.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+        for link in &full_doc.links {
+            if let Some(Target::Missing(path)) = resolve(index, config, &link.url) {
+                findings.push(broken_link("llms-full.txt", link, &path));
+            }
+        }
+    }
+
+    if cfg.check_coverage {
+        if let Some(&home) = index.route_to_index.get("/") {
+            let home = &index.pages[home];
+            let base = index.base_url.as_deref();
+            let mut seen = HashSet::new();
+            for href in &home.anchor_hrefs {
+                if !normalize::is_internal(href, base) || href.starts_with('#') {
+                    continue;
+                }
+                let Some(resolved) = normalize::resolve_href(href, "/", base) else {
+                    continue;
+                };
+                let route = normalize::normalize_path(&resolved, &config.url_normalization);
+                if route == "/" || listed.contains(&route) || !seen.insert(route.clone()) {
+                    continue;
+                }
+                let Some(&i) = index.route_to_index.get(&route) else {
+                    continue;
+                };
+                let page = &index.pages[i];
+                if !llms::is_listable(page, config) {
+                    continue;
+                }
+                let url = base
+                    .and_then(|b| normalize::to_absolute(&route, b))
+                    .unwrap_or_else(|| route.clone());
+                let title = page.title_text.as_deref().unwrap_or(&route).trim();
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "llms-txt/page-missing".into(),
+                    file: "llms.txt".into(),
+                    selector: String::new(),
+                    message: format!(
+                        "'{route}' is linked from the home page but not listed in llms.txt"
+                    ),
+                    help: "Pages in the main navigation are the ones models should know about. Add them to a section, or to `## Optional` if they are secondary.".into(),
+                    suggestion: Some(format!("- [{title}]({url})")),
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+enum Target {
+    /// A page in the index (normalized route).
+    Route(String),
+    /// A non-page file in dist, such as `/docs/intro.md`.
+    File,
+    /// Internal URL that resolves to nothing; carries the path.
+    Missing(String),
+}
+
+/// Resolve a link from llms.txt; None for external URLs.
+fn resolve(index: &SiteIndex, config: &Config, url: &str) -> Option<Target> {
+    let base = index.base_url.as_deref();
+    if url.starts_with('#') || url.starts_with("mailto:") || !normalize::is_internal(url, base) {
+        return None;
+    }
+    let path = normalize::resolve_href(url, "/", base)?;
+    let route = normalize::normalize_path(&path, &config.url_normalization);
+    if index.route_exists(&route) {
+        return Some(Target::Route(route));
+    }
+    let file = percent_decode_str(path.trim_start_matches('/')).decode_utf8_lossy();
+    if !file.is_empty() && index.dist_path.join(file.as_ref()).is_file() {
+        return Some(Target::File);
+    }
+    Some(Target::Missing(path))
+}
+
+fn broken_link(file: &str, link: &llms::Link, path: &str) -> Finding {
+    Finding {
+        level: Level::Error,
+        rule_id: "llms-txt/broken-link".into(),
+        file: file.into(),
+        selector: format!("line {}", link.line),
+        message: format!("Link '{}' ({}) does not resolve to a page or file", link.text, link.url),
+        help: format!(
+            "No page or file exists at '{path}'. Update the link; llms.txt is maintained by hand and drifts when pages move."
+        ),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    }
+}
@@ -0,0 +1,427 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use rayon::prelude::*;
+use scraper::Selector;
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::report::{Confidence, Finding, Level};
+
+/// Manifest file names browsers and generators commonly use at the dist root.
+const MANIFEST_FILE_NAMES: &[&str] = &["manifest.webmanifest", "site.webmanifest", "manifest.json"];
+
+const REQUIRED_MEMBERS: &[&str] = &["name", "icons", "start_url", "display"];
+
+const VALID_DISPLAY_VALUES: &[&str] = &["fullscreen", "standalone", "minimal-ui", "browser"];
+
+/// Resolved manifest and (rel, icon file) set of a page, compared across pages.
+type HeadSignature = (Option<PathBuf>, BTreeSet<(String, PathBuf)>);
+
+/// Icon and manifest links found on one page.
+struct PageHead {
+    rel_path: String,
+    manifest: Option<String>,
+    /// (rel, href, sizes) of `rel=icon` / `apple-touch-icon` links.
+    icons: Vec<(String, String, Option<String>)>,
+    has_theme_color: bool,
+}
+
+/// Audit the web app manifest and icon set: required manifest members, icon
+/// files that exist and have the size they claim, the favicon /
+/// apple-touch-icon / theme-color trio every page should carry, and pages
+/// whose layout links a different manifest or icon set than the rest of the
+/// site (usually a forgotten one-off layout).
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.manifest;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let manifest_sel = Selector::parse("link[rel~='manifest'][href]").unwrap();
+    let icon_sel =
+        Selector::parse("link[rel~='icon'][href], link[rel~='apple-touch-icon'][href]").unwrap();
+    let theme_sel = Selector::parse("meta[name='theme-color'][content]").unwrap();
+
+    let mut heads: Vec<PageHead> = index
+        .pages
+        .par_iter()
+        .map(|page| {
+            let html = page.parse_html();
+            let manifest = html
+                .select(&manifest_sel)
+                .next()
+                .and_then(|el| el.value().attr("href"))
+                .map(|h| h.trim().to_string());
+            let icons = html
+                .select(&icon_sel)
+                .filter_map(|el| {
+                    let attrs = el.value();
+                    let rel = attrs.attr("rel")?.to_ascii_lowercase();
+                    let rel = if rel.split_whitespace().any(|r| r == "apple-touch-icon") {
+                        "apple-touch-icon"
+                    } else {
+                        "icon"
+                    };
+                    Some((
+                        rel.to_string(),
+                        attrs.attr("href")?.trim().to_string(),
+                        attrs.attr("sizes").map(|s| s.trim().to_string()),
+                    ))
+                })
+                .collect();
+            PageHead {
+                rel_path: page.rel_path.clone(),
+                manifest,
+                icons,
+                has_theme_color: html
+                    .select(&theme_sel)
+                    .any(|el| !el.value().attr("content").unwrap_or("").trim().is_empty()),
+            }
+        })
+        .collect();
+    heads.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+    let mut findings = Vec::new();
+
+    // Manifests: every linked one, or the conventional root file if no page links one.
+    let mut manifests: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for head in &heads {
+        if let Some(href) = &head.manifest {
+            if let Some(path) = index.resolve_local_asset(href, &head.rel_path) {
+                manifests
+                    .entry(path)
+                    .or_insert_with(|| head.rel_path.clone());
+            }
+        }
+    }
+    if manifests.is_empty() {
+        if let Some(path) = MANIFEST_FILE_NAMES
+            .iter()
+            .map(|name| index.dist_path.join(name))
+            .find(|p| p.is_file())
+        {
+            manifests.insert(path, String::new());
+        } else if !heads.is_empty() {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "manifest/missing".into(),
+                file: heads[0].rel_path.clone(),
+                selector: "link[rel='manifest']".into(),
+                message: "No web app manifest: no page links one and none exists at the dist root".into(),
+                help: "Add public/manifest.webmanifest and link it from your layout with <link rel=\"manifest\" href=\"/manifest.webmanifest\">.".into(),
+                suggestion: Some("<link rel=\"manifest\" href=\"/manifest.webmanifest\">".into()),
+                source_hint: None,
+                confidence: None,
+            });
+        }
+    }
+    for (path, linked_from) in &manifests {
+        findings.extend(check_manifest(index, path, linked_from));
+    }
+
+    // Icon links: each distinct file/sizes pair once, on the first page using it.
+    let mut icon_links: BTreeMap<(PathBuf, Option<String>), (String, String)> = BTreeMap::new();
+    for head in &heads {
+        for (_, href, sizes) in &head.icons {
+            if let Some(path) = index.resolve_local_asset(href, &head.rel_path) {
+                icon_links
+                    .entry((path, sizes.clone()))
+                    .or_insert_with(|| (head.rel_path.clone(), href.clone()));
+            }
+        }
+    }
+    for ((path, sizes), (page, href)) in &icon_links {
+        findings.extend(check_icon_file(
+            index,
+            path,
+            sizes.as_deref(),
+            page,
+            &format!("link[href='{href}']"),
+        ));
+    }
+
+    // The usual favicon set, per page. Browsers and iOS fall back to
+    // /favicon.ico and /apple-touch-icon.png at the root.
+    let root_favicon = index.dist_path.join("favicon.ico").is_file();
+    let root_touch_icon = index.dist_path.join("apple-touch-icon.png").is_file();
+    for head in &heads {
+        let has_rel = |rel: &str| head.icons.iter().any(|(r, _, _)| r == rel);
+        if cfg.require_favicon && !has_rel("icon") && !root_favicon {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "manifest/favicon-missing".into(),
+                file: head.rel_path.clone(),
+                selector: "head".into(),
+                message: "No favicon: no <link rel=\"icon\"> and no /favicon.ico".into(),
+                help: "Add <link rel=\"icon\" href=\"/favicon.svg\" type=\"image/svg+xml\"> to your layout, plus /favicon.ico for older browsers.".into(),
+                suggestion: Some("<link rel=\"icon\" href=\"/favicon.svg\" type=\"image/svg+xml\">".into()),
+                source_hint: None,
+                confidence: None,
+            });
+        }
+        if cfg.require_apple_touch_icon && !has_rel("apple-touch-icon") && !root_touch_icon {
+            findings.push(Finding {
+                level: Level::Warning,
+                rule_id: "manifest/apple-touch-icon-missing".into(),
+                file: head.rel_path.clone(),
+                selector: "head".into(),
+                message: "No apple-touch-icon: no <link rel=\"apple-touch-icon\"> and no /apple-touch-icon.png".into(),
+                help: "iOS uses a screenshot of the page when saving to the home screen. Add a 180×180 PNG.".into(),
+                suggestion: Some("<link rel=\"apple-touch-icon\" href=\"/apple-touch-icon.png\">".into()),
+                source_hint: None,
+                confidence: None,
+            });
+        }
+        if cfg.require_theme_color && !head.has_theme_color {
+            findings.push(Finding {
+                level: Level::Info,
+                rule_id: "manifest/theme-color-missing".into(),
+                file: head.rel_path.clone(),
+                selector: "head".into(),
+                message: "No <meta name=\"theme-color\">".into(),
+                help: "theme-color tints the browser UI on mobile. Use the same value as the manifest's theme_color.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+    }
+
+    if cfg.check_consistency {
+        findings.extend(check_consistency(index, &heads));
+    }
+
+    findings
+}
+
+fn check_manifest(index: &SiteIndex, path: &PathBuf, linked_from: &str) -> Vec<Finding> {
+    let rel = index.dist_rel_path(path);
+    let finding = |level, rule_id: &str, selector: String, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: rel.clone(),
+        selector,
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    let Ok(content) = std::fs::read_to_string(path) else {
+        let file = if linked_from.is_empty() {
+            rel.clone()
+        } else {
+            linked_from.to_string()
+        };
+        return vec![Finding {
+            file,
+            ..finding(
+                Level::Error,
+                "manifest/broken-link",
+                "link[rel='manifest']".into(),
+                format!("Linked manifest '{rel}' does not exist"),
+                "Put the manifest in public/ so it is copied to dist, or fix the href.",
+            )
+        }];
+    };
+    let json: Value = match serde_json::from_str(&content) {
+        Ok(v @ Value::Object(_)) => v,
+        Ok(_) => {
+            return vec![finding(
+                Level::Error,
+                "manifest/invalid-json",
+                "manifest".into(),
+                format!("Manifest '{rel}' is not a JSON object"),
+                "A web app manifest must be a JSON object.",
+            )]
+        }
+        Err(e) => {
+            return vec![finding(
+                Level::Error,
+                "manifest/invalid-json",
+                "manifest".into(),
+                format!("Manifest '{rel}' is not valid JSON: {e}"),
+                "Browsers ignore a manifest that fails to parse.",
+            )]
+        }
+    };
+
+    let mut findings = Vec::new();
+    for member in REQUIRED_MEMBERS {
+        let present = match json.get(*member) {
+            Some(Value::String(s)) => !s.trim().is_empty(),
+            Some(Value::Array(a)) => !a.is_empty(),
+            Some(_) => true,
+            None => false,
+        };
+        if !present {
+            findings.push(finding(
+                Level::Warning,
+                "manifest/missing-member",
+                format!("manifest > {member}"),
+                format!("Manifest '{rel}' has no '{member}'"),
+                "name, icons, start_url and display are needed for the site to be installable.",
+            ));
+        }
+    }
+    if let Some(display) = json.get("display").and_then(Value::as_str) {
+        if !VALID_DISPLAY_VALUES.contains(&display) {
+            findings.push(finding(
+                Level::Warning,
+                "manifest/invalid-display",
+                "manifest > display".into(),
+                format!("Manifest '{rel}' has unknown display '{display}'"),
+                "Use one of: fullscreen, standalone, minimal-ui, browser.",
+            ));
+        }
+    }
+
+    for icon in json
+        .get("icons")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(src) = icon.get("src").and_then(Value::as_str) else {
+            continue;
+        };
+        let Some(icon_path) = index.resolve_local_asset(src, &rel) else {
+            continue;
+        };
+        let sizes = icon.get("sizes").and_then(Value::as_str);
+        findings.extend(check_icon_file(
+            index,
+            &icon_path,
+            sizes,
+            &rel,
+            &format!("manifest > icons[src='{src}']"),
+        ));
+    }
+
+    findings
+}
+
+/// Check that an icon file exists and that its declared `sizes` include the
+/// real dimensions. `any` and unreadable formats (SVG) skip the size check.
+fn check_icon_file(
+    index: &SiteIndex,
+    path: &PathBuf,
+    sizes: Option<&str>,
+    file: &str,
+    selector: &str,
+) -> Option<Finding> {
+    let rel = index.dist_rel_path(path);
+    if !path.is_file() {
+        return Some(Finding {
+            level: Level::Error,
+            rule_id: "manifest/icon-missing".into(),
+            file: file.to_string(),
+            selector: selector.to_string(),
+            message: format!("Icon file '{rel}' does not exist"),
+            help: "Add the icon to public/ or fix the path.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+    let sizes = sizes?.to_ascii_lowercase();
+    let declared: Vec<&str> = sizes.split_whitespace().collect();
+    if declared.is_empty() || declared.contains(&"any") {
+        return None;
+    }
+    let dim = imagesize::size(path).ok()?;
+    let actual = format!("{}x{}", dim.width, dim.height);
+    if declared.contains(&actual.as_str()) {
+        return None;
+    }
+    Some(Finding {
+        level: Level::Warning,
+        rule_id: "manifest/icon-size-mismatch".into(),
+        file: file.to_string(),
+        selector: selector.to_string(),
+        message: format!(
+            "Icon '{rel}' declares sizes '{}' but is {actual}",
+            declared.join(" ")
+        ),
+        help: "Browsers pick icons by the declared size; a wrong value gets the icon scaled or skipped. Export the icon at the declared size or fix `sizes`.".into(),
+        suggestion: Some(format!("sizes=\"{actual}\"")),
+        source_hint: None,
+        confidence: None,
+    })
+}
+
+/// Flag pages whose manifest / icon links differ from the set most pages use.
+fn check_consistency(index: &SiteIndex, heads: &[PageHead]) -> Vec<Finding> {
+    let signature = |head: &PageHead| -> HeadSignature {
+        let manifest = head
+            .manifest
+            .as_deref()
+            .and_then(|h| index.resolve_local_asset(h, &head.rel_path));
+        let icons = head
+            .icons
+            .iter()
+            .filter_map(|(rel, href, _)| {
+                index
+                    .resolve_local_asset(href, &head.rel_path)
+                    .map(|p| (rel.clone(), p))
+            })
+            .collect();
+        (manifest, icons)
+    };
+    let signatures: Vec<_> = heads.iter().map(signature).collect();
+
+    let mut counts: HashMap<&HeadSignature, usize> = HashMap::new();
+    for sig in &signatures {
+        *counts.entry(sig).or_default() += 1;
+    }
+    if counts.len() < 2 {
+        return Vec::new();
+    }
+    let Some((majority, majority_count)) = counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(sig, n)| (*sig, *n))
+    else {
+        return Vec::new();
+    };
+    let describe = |sig: &HeadSignature| {
+        let manifest = sig
+            .0
+            .as_ref()
+            .map_or("none".to_string(), |p| index.dist_rel_path(p));
+        let icons: Vec<String> = sig.1.iter().map(|(_, p)| index.dist_rel_path(p)).collect();
+        format!(
+            "manifest {manifest}, icons [{}]",
+            if icons.is_empty() {
+                "none".to_string()
+            } else {
+                icons.join(", ")
+            }
+        )
+    };
+
+    heads
+        .iter()
+        .zip(&signatures)
+        .filter(|(_, sig)| *sig != majority)
+        .map(|(head, sig)| Finding {
+            level: Level::Warning,
+            rule_id: "manifest/inconsistent".into(),
+            file: head.rel_path.clone(),
+            selector: "head".into(),
+            message: format!(
+                "Page links {} but {} other page(s) link {}",
+                describe(sig),
+                majority_count,
+                describe(majority)
+            ),
+            help: "Pages rendered by a different layout often miss or duplicate the head tags. Move the manifest and icon links into a shared head component.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        })
+        .collect()
+}
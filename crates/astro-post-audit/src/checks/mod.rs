@@ -3,25 +3,41 @@ pub mod ai_visibility;
 pub mod assets;
 pub mod content_quality;
 pub mod content_sync;
+pub mod crawl;
 pub mod crawl_budget;
+pub mod csp;
+pub mod deploy_hygiene;
+pub mod error_pages;
 pub mod external_links;
+pub mod feeds;
 pub mod golive;
 pub mod headings;
 pub mod hreflang;
 pub mod html_basics;
 pub mod html_validation;
+pub mod http_headers;
 pub mod i18n_audit;
 pub mod images;
 pub mod js_bloat;
 pub mod links;
+pub mod llms_txt;
+pub mod manifest;
+pub mod module_graph;
 pub mod opengraph;
+pub mod page_weight;
+pub mod pagination;
 pub mod privacy_security;
 pub mod redirects;
 pub mod render_blocking;
 pub mod robots_txt;
+pub mod secrets;
 pub mod security;
+pub mod security_txt;
 pub mod seo;
 pub mod sitemap;
+pub mod sri;
 pub mod structured_data;
 pub mod structured_data_graph;
+pub mod stylesheets;
+pub mod unused_css;
 pub mod ux_heuristics;
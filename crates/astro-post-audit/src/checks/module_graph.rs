@@ -0,0 +1,440 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use scraper::Selector;
+
+use crate::checks::page_weight::{self, kb, Sizes};
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::js;
+use crate::report::{Confidence, Finding, Level};
+
+/// Chunks smaller than this are ignored for duplicate detection — Vite emits
+/// many near-empty shims that are legitimately identical.
+const MIN_DUPLICATE_BYTES: u64 = 1024;
+
+/// Attributes of `<astro-island>` that point at modules the island loads.
+const ISLAND_URL_ATTRS: &[&str] = &["component-url", "renderer-url", "before-hydration-url"];
+
+#[derive(Default)]
+struct Node {
+    static_deps: Vec<PathBuf>,
+    dynamic_deps: Vec<PathBuf>,
+}
+
+struct Island {
+    name: String,
+    directive: Option<String>,
+    entries: Vec<PathBuf>,
+}
+
+/// Where an island is rendered: first page, hydration directive, page count.
+struct IslandUsage {
+    file: String,
+    directive: Option<String>,
+    pages: usize,
+}
+
+struct PageEntries {
+    entries: Vec<PathBuf>,
+    islands: Vec<Island>,
+}
+
+/// Resolve the client-side module graph per page: entry scripts, inline module
+/// imports, `modulepreload` links, and the `component-url`/`renderer-url` of
+/// every `<astro-island>`, followed through static and dynamic `import`s in the
+/// emitted chunks. `js_bloat` only sees top-level `<script src>` files; island
+/// code is mostly reached through these imports.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.module_graph;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let pages: Vec<PageEntries> = index
+        .pages
+        .par_iter()
+        .map(|page| collect_entries(index, page))
+        .collect();
+
+    let roots: HashSet<PathBuf> = pages
+        .iter()
+        .flat_map(|p| {
+            p.entries
+                .iter()
+                .chain(p.islands.iter().flat_map(|i| &i.entries))
+        })
+        .cloned()
+        .collect();
+    let graph = build_graph(index, roots);
+    let sizes: HashMap<&PathBuf, Sizes> = graph
+        .par_iter()
+        .filter_map(|(path, _)| page_weight::file_sizes(path).map(|s| (path, s)))
+        .collect();
+    let sum = |set: &HashSet<&PathBuf>| -> Sizes {
+        let mut total = Sizes::default();
+        for path in set {
+            if let Some(&s) = sizes.get(path) {
+                total += s;
+            }
+        }
+        total
+    };
+
+    let mut findings = Vec::new();
+
+    // Per route
+    for (page, entries) in index.pages.iter().zip(&pages) {
+        let mut all_entries = entries.entries.clone();
+        all_entries.extend(entries.islands.iter().flat_map(|i| i.entries.clone()));
+        if all_entries.is_empty() {
+            continue;
+        }
+        let (eager, lazy) = closure(&graph, &all_entries);
+        let eager_size = sum(&eager);
+        let lazy_size = sum(&lazy);
+        let island_note = if entries.islands.is_empty() {
+            String::new()
+        } else {
+            format!(" for {} island(s)", entries.islands.len())
+        };
+        let lazy_note = if lazy.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "; {} more in {} module(s) via dynamic import()",
+                kb(lazy_size.raw),
+                lazy.len()
+            )
+        };
+        findings.push(Finding {
+            level: Level::Info,
+            rule_id: "js/route-modules".into(),
+            file: page.rel_path.clone(),
+            selector: "script".into(),
+            message: format!(
+                "Route '{}' loads {} of JavaScript ({} brotli) in {} module(s){}{}",
+                page.route,
+                kb(eager_size.raw),
+                kb(eager_size.brotli),
+                eager.len(),
+                island_note,
+                lazy_note
+            ),
+            help: "Totals follow static imports from entry scripts, modulepreloads and island component/renderer URLs.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        });
+        if let Some(max) = cfg.max_route_kb {
+            if eager_size.raw / 1024 > max {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "js/route-budget".into(),
+                    file: page.rel_path.clone(),
+                    selector: "script".into(),
+                    message: format!(
+                        "Route '{}' loads {}KB of JavaScript at startup (max: {}KB)",
+                        page.route,
+                        eager_size.raw / 1024,
+                        max
+                    ),
+                    help: "Hydrate islands later (`client:visible`, `client:idle`), split heavy dependencies behind dynamic import(), or render static HTML instead.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: Some(Confidence::Medium),
+                });
+            }
+        }
+    }
+
+    // Per island, deduplicated across pages that render the same component.
+    let mut islands: BTreeMap<(String, Vec<PathBuf>), IslandUsage> = BTreeMap::new();
+    for (page, entries) in index.pages.iter().zip(&pages) {
+        for island in &entries.islands {
+            islands
+                .entry((island.name.clone(), island.entries.clone()))
+                .and_modify(|usage| usage.pages += 1)
+                .or_insert(IslandUsage {
+                    file: page.rel_path.clone(),
+                    directive: island.directive.clone(),
+                    pages: 1,
+                });
+        }
+    }
+    for ((name, island_entries), usage) in &islands {
+        let file = &usage.file;
+        let (eager, _) = closure(&graph, island_entries);
+        let size = sum(&eager);
+        let directive = usage
+            .directive
+            .as_deref()
+            .map(|d| format!(" ({d})"))
+            .unwrap_or_default();
+        findings.push(Finding {
+            level: Level::Info,
+            rule_id: "js/island-modules".into(),
+            file: file.clone(),
+            selector: format!("astro-island[component-url] {name}"),
+            message: format!(
+                "Island '{}'{} loads {} of JavaScript ({} brotli) in {} module(s), used on {} page(s)",
+                name,
+                directive,
+                kb(size.raw),
+                kb(size.brotli),
+                eager.len(),
+                usage.pages
+            ),
+            help: "Includes the component, its framework renderer and everything they import statically.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        });
+        if let Some(max) = cfg.max_island_kb {
+            if size.raw / 1024 > max {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "js/island-budget".into(),
+                    file: file.clone(),
+                    selector: format!("astro-island[component-url] {name}"),
+                    message: format!(
+                        "Island '{}' loads {}KB of JavaScript (max: {}KB)",
+                        name,
+                        size.raw / 1024,
+                        max
+                    ),
+                    help: "Move heavy dependencies behind dynamic import(), use a lighter framework (Preact) for the island, or render it as static HTML.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: Some(Confidence::Medium),
+                });
+            }
+        }
+    }
+
+    findings.extend(duplicate_chunks(index, &graph, &sizes));
+    findings
+}
+
+fn collect_entries(index: &SiteIndex, page: &crate::discovery::PageInfo) -> PageEntries {
+    let script_sel = Selector::parse("script[src]").unwrap();
+    let inline_module_sel = Selector::parse("script[type='module']:not([src])").unwrap();
+    let preload_sel = Selector::parse("link[rel~='modulepreload'][href]").unwrap();
+    let island_sel = Selector::parse("astro-island").unwrap();
+
+    let html = page.parse_html();
+    let resolve = |url: &str| index.resolve_local_asset(url, &page.rel_path);
+
+    let mut entries: Vec<PathBuf> = html
+        .select(&script_sel)
+        .filter_map(|el| el.value().attr("src"))
+        .chain(
+            html.select(&preload_sel)
+                .filter_map(|el| el.value().attr("href")),
+        )
+        .filter_map(resolve)
+        .collect();
+    for el in html.select(&inline_module_sel) {
+        let text: String = el.text().collect();
+        entries.extend(
+            js::module_imports(&text)
+                .into_iter()
+                .filter(|m| !m.dynamic && js::is_local_specifier(&m.specifier))
+                .filter_map(|m| resolve(&m.specifier)),
+        );
+    }
+
+    let islands = html
+        .select(&island_sel)
+        .filter_map(|el| {
+            let attrs = el.value();
+            let island_entries: Vec<PathBuf> = ISLAND_URL_ATTRS
+                .iter()
+                .filter_map(|a| attrs.attr(a))
+                .filter(|url| !url.is_empty())
+                .filter_map(resolve)
+                .collect();
+            if island_entries.is_empty() {
+                return None;
+            }
+            let opts: Option<serde_json::Value> = attrs
+                .attr("opts")
+                .and_then(|o| serde_json::from_str(o).ok());
+            let name = opts
+                .as_ref()
+                .and_then(|o| o["name"].as_str())
+                .map(str::to_string)
+                .or_else(|| {
+                    attrs
+                        .attr("component-url")
+                        .and_then(|u| u.rsplit('/').next())
+                        .map(|f| f.split('.').next().unwrap_or(f).to_string())
+                })
+                .unwrap_or_else(|| "(unnamed)".to_string());
+            // `opts.value` is the directive's argument (`true`, a media
+            // query); the directive itself is the `client` attribute.
+            let directive = attrs
+                .attr("client")
+                .filter(|v| !v.is_empty())
+                .map(|v| format!("client:{v}"));
+            Some(Island {
+                name,
+                directive,
+                entries: island_entries,
+            })
+        })
+        .collect();
+
+    PageEntries { entries, islands }
+}
+
+/// Read every module reachable from `roots`, one breadth-first level at a time
+/// so each level is parsed in parallel.
+fn build_graph(index: &SiteIndex, roots: HashSet<PathBuf>) -> HashMap<PathBuf, Node> {
+    let mut graph: HashMap<PathBuf, Node> = HashMap::new();
+    let mut frontier: Vec<PathBuf> = roots.into_iter().filter(|p| p.is_file()).collect();
+    while !frontier.is_empty() {
+        let nodes: Vec<(PathBuf, Node)> = frontier
+            .into_par_iter()
+            .map(|path| {
+                let node = parse_module(index, &path);
+                (path, node)
+            })
+            .collect();
+        let mut next: HashSet<PathBuf> = HashSet::new();
+        for (path, node) in nodes {
+            for dep in node.static_deps.iter().chain(&node.dynamic_deps) {
+                if !graph.contains_key(dep) && dep != &path && dep.is_file() {
+                    next.insert(dep.clone());
+                }
+            }
+            graph.insert(path, node);
+        }
+        next.retain(|p| !graph.contains_key(p));
+        frontier = next.into_iter().collect();
+    }
+    graph
+}
+
+fn parse_module(index: &SiteIndex, path: &Path) -> Node {
+    let Ok(src) = std::fs::read_to_string(path) else {
+        return Node::default();
+    };
+    let rel = index.dist_rel_path(path);
+    let mut node = Node::default();
+    for import in js::module_imports(&src) {
+        if !js::is_local_specifier(&import.specifier) {
+            continue;
+        }
+        let Some(dep) = index.resolve_local_asset(&import.specifier, &rel) else {
+            continue;
+        };
+        if import.dynamic {
+            node.dynamic_deps.push(dep);
+        } else {
+            node.static_deps.push(dep);
+        }
+    }
+    node
+}
+
+/// Modules loaded eagerly (static imports from `entries`) and lazily (reached
+/// only through a dynamic import).
+fn closure<'a>(
+    graph: &'a HashMap<PathBuf, Node>,
+    entries: &[PathBuf],
+) -> (HashSet<&'a PathBuf>, HashSet<&'a PathBuf>) {
+    let walk = |starts: Vec<&'a PathBuf>, follow_dynamic: bool, skip: &HashSet<&'a PathBuf>| {
+        let mut seen: HashSet<&'a PathBuf> = HashSet::new();
+        let mut stack = starts;
+        while let Some(path) = stack.pop() {
+            if skip.contains(path) || !seen.insert(path) {
+                continue;
+            }
+            if let Some(node) = graph.get(path) {
+                stack.extend(node.static_deps.iter());
+                if follow_dynamic {
+                    stack.extend(node.dynamic_deps.iter());
+                }
+            }
+        }
+        seen
+    };
+
+    let starts: Vec<&PathBuf> = entries
+        .iter()
+        .filter_map(|e| graph.get_key_value(e).map(|(k, _)| k))
+        .collect();
+    let eager = walk(starts, false, &HashSet::new());
+    let lazy_starts: Vec<&PathBuf> = eager
+        .iter()
+        .filter_map(|p| graph.get(*p))
+        .flat_map(|n| n.dynamic_deps.iter())
+        .filter_map(|d| graph.get_key_value(d).map(|(k, _)| k))
+        .collect();
+    let lazy = walk(lazy_starts, true, &eager);
+    (eager, lazy)
+}
+
+/// Chunks with byte-identical content under different file names: the same
+/// module was bundled twice, so visitors download it twice.
+fn duplicate_chunks(
+    index: &SiteIndex,
+    graph: &HashMap<PathBuf, Node>,
+    sizes: &HashMap<&PathBuf, Sizes>,
+) -> Vec<Finding> {
+    let hashed: Vec<((u64, u64), &PathBuf)> = graph
+        .par_iter()
+        .filter(|(path, _)| {
+            sizes
+                .get(path)
+                .is_some_and(|s| s.raw >= MIN_DUPLICATE_BYTES)
+        })
+        .filter_map(|(path, _)| {
+            let bytes = std::fs::read(path).ok()?;
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            Some(((bytes.len() as u64, hasher.finish()), path))
+        })
+        .collect();
+
+    let mut groups: HashMap<(u64, u64), Vec<String>> = HashMap::new();
+    for (key, path) in hashed {
+        groups
+            .entry(key)
+            .or_default()
+            .push(index.dist_rel_path(path));
+    }
+    let mut groups: Vec<(u64, Vec<String>)> = groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|((len, _), mut files)| {
+            files.sort();
+            (len, files)
+        })
+        .collect();
+    groups.sort_by(|a, b| a.1.cmp(&b.1));
+
+    groups
+        .into_iter()
+        .map(|(len, files)| Finding {
+            level: Level::Warning,
+            rule_id: "js/duplicate-chunk".into(),
+            file: files[0].clone(),
+            selector: "script".into(),
+            message: format!(
+                "'{}' is byte-identical to {} other chunk(s) ({} each): {}",
+                files[0],
+                files.len() - 1,
+                kb(len),
+                files[1..].join(", ")
+            ),
+            help: "The same module was bundled more than once. Check for duplicate dependency versions or for code copied into several entry points instead of a shared import.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        })
+        .collect()
+}
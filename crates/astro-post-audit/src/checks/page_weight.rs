@@ -0,0 +1,506 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use scraper::{ElementRef, Selector};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::css;
+use crate::discovery::SiteIndex;
+use crate::report::{Confidence, Finding, Level};
+
+/// Viewport width (CSS px, DPR 1) used to pick the `srcset` candidate and to
+/// resolve `vw` slot sizes — a typical laptop, not a worst case.
+pub const REFERENCE_VIEWPORT_PX: u32 = 1280;
+
+/// gzip level most servers and CDNs use for on-the-fly compression.
+const GZIP_LEVEL: u32 = 6;
+
+/// Brotli quality 9 stays close to the max-quality (11) size that precompressed
+/// static assets get, at a fraction of the CPU cost.
+const BROTLI_QUALITY: u32 = 9;
+const BROTLI_LGWIN: u32 = 22;
+
+/// Transfer sizes of one resource (or a sum of resources) in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Sizes {
+    pub raw: u64,
+    pub gzip: u64,
+    pub brotli: u64,
+}
+
+impl AddAssign for Sizes {
+    fn add_assign(&mut self, other: Self) {
+        self.raw += other.raw;
+        self.gzip += other.gzip;
+        self.brotli += other.brotli;
+    }
+}
+
+/// Everything a page downloads on first load, grouped by resource type.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PageWeight {
+    /// Number of distinct local files counted.
+    pub requests: usize,
+    pub total: Sizes,
+    pub document: Sizes,
+    pub scripts: Sizes,
+    pub styles: Sizes,
+    pub images: Sizes,
+    pub fonts: Sizes,
+    pub iframes: Sizes,
+}
+
+/// Local files a page loads, tagged with their resource type.
+type Resources = Vec<(Kind, PathBuf)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Document,
+    Script,
+    Style,
+    Image,
+    Font,
+    Iframe,
+}
+
+/// Report the full page weight per route: HTML, scripts and modulepreloads,
+/// stylesheets, images (the `srcset` candidate a browser would pick), fonts
+/// declared in loaded CSS, and same-site iframes. Sizes are raw plus gzip and
+/// brotli estimates, because Astro pages are served compressed and the raw
+/// number alone overstates text-heavy routes.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    if !config.page_weight.enabled {
+        return Vec::new();
+    }
+
+    let weights = measure(index);
+    let max_kb = config.page_weight.max_kb;
+    let mut findings = Vec::new();
+
+    for page in &index.pages {
+        let Some(w) = weights.get(&page.rel_path) else {
+            continue;
+        };
+        let total_kb = w.total.brotli / 1024;
+
+        findings.push(Finding {
+            level: Level::Info,
+            rule_id: "performance/page-weight".into(),
+            file: page.rel_path.clone(),
+            selector: "html".into(),
+            message: format!(
+                "Route '{}' weighs {} brotli / {} gzip / {} raw across {} request(s): HTML {}, JS {}, CSS {}, images {}, fonts {}, iframes {}",
+                page.route,
+                kb(w.total.brotli),
+                kb(w.total.gzip),
+                kb(w.total.raw),
+                w.requests,
+                kb(w.document.brotli),
+                kb(w.scripts.brotli),
+                kb(w.styles.brotli),
+                kb(w.images.brotli),
+                kb(w.fonts.brotli),
+                kb(w.iframes.brotli),
+            ),
+            help: "Per-type sizes are brotli transfer estimates; images use the srcset candidate picked at a 1280px viewport.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        });
+
+        if let Some(max) = max_kb {
+            if total_kb > max {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "performance/page-weight-budget".into(),
+                    file: page.rel_path.clone(),
+                    selector: "html".into(),
+                    message: format!(
+                        "Route '{}' transfers {}KB (brotli), over the {}KB budget",
+                        page.route, total_kb, max
+                    ),
+                    help: "Check the largest bucket first: oversized images, unused fonts, or islands that could hydrate later (`client:visible`).".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: Some(Confidence::Medium),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Compute the page weight of every page, keyed by `rel_path`. Each unique
+/// file is read and compressed once, however many pages load it.
+pub fn measure(index: &SiteIndex) -> HashMap<String, PageWeight> {
+    // Phase 1: resources referenced directly from each page's HTML.
+    let per_page: Vec<(String, Resources, Vec<PathBuf>)> = index
+        .pages
+        .par_iter()
+        .map(|page| {
+            let (resources, stylesheets) = collect_page_resources(index, page);
+            (page.rel_path.clone(), resources, stylesheets)
+        })
+        .collect();
+
+    // Phase 2: fonts declared in each unique stylesheet.
+    let stylesheets: HashSet<&PathBuf> = per_page.iter().flat_map(|(_, _, s)| s).collect();
+    let css_fonts: HashMap<&PathBuf, Vec<PathBuf>> = stylesheets
+        .into_par_iter()
+        .map(|path| (path, stylesheet_fonts(index, path)))
+        .collect();
+
+    let per_page: Vec<(String, Resources)> = per_page
+        .iter()
+        .map(|(rel, resources, sheets)| {
+            let mut resources = resources.clone();
+            let mut seen: HashSet<PathBuf> = resources.iter().map(|(_, p)| p.clone()).collect();
+            for font in sheets.iter().flat_map(|s| &css_fonts[s]) {
+                if seen.insert(font.clone()) {
+                    resources.push((Kind::Font, font.clone()));
+                }
+            }
+            (rel.clone(), resources)
+        })
+        .collect();
+
+    // Phase 3: transfer sizes per unique file.
+    let unique: HashSet<&PathBuf> = per_page
+        .iter()
+        .flat_map(|(_, r)| r.iter().map(|(_, p)| p))
+        .collect();
+    let sizes: HashMap<&PathBuf, Sizes> = unique
+        .into_par_iter()
+        .filter_map(|path| file_sizes(path).map(|s| (path, s)))
+        .collect();
+
+    per_page
+        .iter()
+        .map(|(rel, resources)| {
+            let mut w = PageWeight::default();
+            for (kind, path) in resources {
+                let Some(&s) = sizes.get(path) else {
+                    continue;
+                };
+                w.requests += 1;
+                w.total += s;
+                match kind {
+                    Kind::Document => w.document += s,
+                    Kind::Script => w.scripts += s,
+                    Kind::Style => w.styles += s,
+                    Kind::Image => w.images += s,
+                    Kind::Font => w.fonts += s,
+                    Kind::Iframe => w.iframes += s,
+                }
+            }
+            (rel.clone(), w)
+        })
+        .collect()
+}
+
+/// Local resources referenced by a page, deduplicated by path, plus the
+/// stylesheets whose `@font-face` rules still need resolving.
+fn collect_page_resources(
+    index: &SiteIndex,
+    page: &crate::discovery::PageInfo,
+) -> (Resources, Vec<PathBuf>) {
+    let script_sel = Selector::parse("script[src]").unwrap();
+    let link_sel = Selector::parse("link[rel][href]").unwrap();
+    let img_sel = Selector::parse("img").unwrap();
+    let source_sel = Selector::parse("source[srcset]").unwrap();
+    let style_sel = Selector::parse("style").unwrap();
+    let iframe_sel = Selector::parse("iframe[src]").unwrap();
+
+    let html = page.parse_html();
+    let mut resources: Resources = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut stylesheets = Vec::new();
+    let mut push = |kind: Kind, path: PathBuf| {
+        if seen.insert(path.clone()) {
+            resources.push((kind, path));
+        }
+    };
+
+    push(Kind::Document, index.dist_path.join(&page.rel_path));
+
+    for el in html.select(&script_sel) {
+        if let Some(path) = el
+            .value()
+            .attr("src")
+            .and_then(|src| index.resolve_local_asset(src, &page.rel_path))
+        {
+            push(Kind::Script, path);
+        }
+    }
+
+    for el in html.select(&link_sel) {
+        let rel = el.value().attr("rel").unwrap_or("").to_ascii_lowercase();
+        let rels: Vec<&str> = rel.split_whitespace().collect();
+        let href = el.value().attr("href").unwrap_or("");
+        let kind = if rels.contains(&"stylesheet") {
+            Kind::Style
+        } else if rels.contains(&"modulepreload") {
+            Kind::Script
+        } else if rels.contains(&"preload") {
+            match el.value().attr("as").unwrap_or("") {
+                "font" => Kind::Font,
+                "script" => Kind::Script,
+                "style" => Kind::Style,
+                "image" => Kind::Image,
+                _ => continue,
+            }
+        } else {
+            continue;
+        };
+        let Some(path) = index.resolve_local_asset(href, &page.rel_path) else {
+            continue;
+        };
+        if kind == Kind::Style {
+            stylesheets.push(path.clone());
+        }
+        push(kind, path);
+    }
+
+    for img in html.select(&img_sel) {
+        if let Some(path) = chosen_image(&img, &source_sel)
+            .and_then(|url| index.resolve_local_asset(&url, &page.rel_path))
+        {
+            push(Kind::Image, path);
+        }
+    }
+
+    // Fonts declared in inline <style> resolve relative to the page itself.
+    for el in html.select(&style_sel) {
+        let text: String = el.text().collect();
+        for url in css::font_face_sources(&text) {
+            if let Some(path) = index.resolve_local_asset(&url, &page.rel_path) {
+                push(Kind::Font, path);
+            }
+        }
+    }
+
+    for el in html.select(&iframe_sel) {
+        let src = el.value().attr("src").unwrap_or("");
+        if let Some(path) = resolve_iframe_document(index, src, &page.rel_path) {
+            push(Kind::Iframe, path);
+        }
+    }
+
+    (resources, stylesheets)
+}
+
+/// Font files declared in a stylesheet, resolved relative to the stylesheet.
+fn stylesheet_fonts(index: &SiteIndex, path: &Path) -> Vec<PathBuf> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let rel = index.dist_rel_path(path);
+    css::font_face_sources(&content)
+        .iter()
+        .filter_map(|url| index.resolve_local_asset(url, &rel))
+        .collect()
+}
+
+/// The URL a browser at the reference viewport would fetch for an `<img>`:
+/// the first `<source srcset>` of an enclosing `<picture>`, else the image's
+/// own `srcset`, else `src`.
+fn chosen_image(img: &ElementRef, source_sel: &Selector) -> Option<String> {
+    let picture_source = img
+        .parent()
+        .and_then(ElementRef::wrap)
+        .filter(|p| p.value().name() == "picture")
+        .and_then(|p| p.select(source_sel).next());
+    let (srcset, sizes) = match picture_source {
+        Some(source) => (
+            source.value().attr("srcset"),
+            source.value().attr("sizes").or(img.value().attr("sizes")),
+        ),
+        None => (img.value().attr("srcset"), img.value().attr("sizes")),
+    };
+    srcset
+        .and_then(|s| pick_srcset_candidate(s, sizes, REFERENCE_VIEWPORT_PX))
+        .or_else(|| img.value().attr("src").map(str::to_string))
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// A parsed `srcset` entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrcsetCandidate {
+    pub url: String,
+    /// Width descriptor (`640w`), if any.
+    pub width: Option<u32>,
+    /// Density descriptor (`2x`); 1.0 when neither descriptor is present.
+    pub density: f32,
+}
+
+/// Parse a `srcset` attribute into its candidates, skipping malformed ones.
+pub fn parse_srcset(srcset: &str) -> Vec<SrcsetCandidate> {
+    srcset
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split_whitespace();
+            let url = parts.next()?.to_string();
+            let mut candidate = SrcsetCandidate {
+                url,
+                width: None,
+                density: 1.0,
+            };
+            if let Some(desc) = parts.next() {
+                if let Some(w) = desc.strip_suffix('w') {
+                    candidate.width = Some(w.parse().ok()?);
+                } else if let Some(x) = desc.strip_suffix('x') {
+                    candidate.density = x.parse().ok()?;
+                }
+            }
+            Some(candidate)
+        })
+        .collect()
+}
+
+/// Pick the candidate a DPR-1 browser would load at `viewport` px: for width
+/// descriptors the smallest candidate covering the `sizes` slot (or the
+/// largest available), for density descriptors the 1x candidate.
+pub fn pick_srcset_candidate(srcset: &str, sizes: Option<&str>, viewport: u32) -> Option<String> {
+    let candidates = parse_srcset(srcset);
+    if candidates.iter().any(|c| c.width.is_some()) {
+        let slot = slot_width(sizes, viewport);
+        let mut widths: Vec<&SrcsetCandidate> =
+            candidates.iter().filter(|c| c.width.is_some()).collect();
+        widths.sort_by_key(|c| c.width);
+        widths
+            .iter()
+            .find(|c| c.width.is_some_and(|w| w >= slot))
+            .or(widths.last())
+            .map(|c| c.url.clone())
+    } else {
+        candidates
+            .iter()
+            .min_by(|a, b| (a.density - 1.0).abs().total_cmp(&(b.density - 1.0).abs()))
+            .map(|c| c.url.clone())
+    }
+}
+
+/// Resolve a `sizes` attribute to a slot width in px at `viewport`. Only
+/// `min-width`/`max-width` conditions in px are evaluated; anything else
+/// falls through to the next entry.
+pub fn slot_width(sizes: Option<&str>, viewport: u32) -> u32 {
+    let Some(sizes) = sizes else {
+        return viewport;
+    };
+    for entry in sizes.split(',') {
+        let entry = entry.trim();
+        let (condition, length) = match entry.rfind(')') {
+            Some(i) => (Some(&entry[..=i]), entry[i + 1..].trim()),
+            None => (None, entry),
+        };
+        if let Some(cond) = condition {
+            if !media_matches(cond, viewport) {
+                continue;
+            }
+        }
+        if let Some(px) = length_to_px(length, viewport) {
+            return px;
+        }
+    }
+    viewport
+}
+
+fn media_matches(condition: &str, viewport: u32) -> bool {
+    let inner = condition
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .to_ascii_lowercase();
+    let Some((feature, value)) = inner.split_once(':') else {
+        return false;
+    };
+    let Some(px) = length_to_px(value.trim(), viewport) else {
+        return false;
+    };
+    match feature.trim() {
+        "min-width" => viewport >= px,
+        "max-width" => viewport <= px,
+        _ => false,
+    }
+}
+
+fn length_to_px(length: &str, viewport: u32) -> Option<u32> {
+    let length = length.trim();
+    let parse = |n: &str| n.trim().parse::<f32>().ok();
+    let px = if let Some(n) = length.strip_suffix("px") {
+        parse(n)?
+    } else if let Some(n) = length.strip_suffix("vw") {
+        parse(n)? * viewport as f32 / 100.0
+    } else if let Some(n) = length
+        .strip_suffix("rem")
+        .or_else(|| length.strip_suffix("em"))
+    {
+        parse(n)? * 16.0
+    } else {
+        return None;
+    };
+    Some(px.round() as u32)
+}
+
+/// Map a same-site iframe `src` to the HTML file it loads.
+fn resolve_iframe_document(index: &SiteIndex, src: &str, page_rel: &str) -> Option<PathBuf> {
+    let path = index.resolve_local_asset(src, page_rel)?;
+    if path.is_file() {
+        return Some(path);
+    }
+    let as_index = path.join("index.html");
+    if as_index.is_file() {
+        return Some(as_index);
+    }
+    let as_html = path.with_extension("html");
+    as_html.is_file().then_some(as_html)
+}
+
+/// Raw, gzip and brotli size of a file. Already-compressed formats (images,
+/// fonts, media) are counted at their raw size.
+pub fn file_sizes(path: &Path) -> Option<Sizes> {
+    let bytes = std::fs::read(path).ok()?;
+    let raw = bytes.len() as u64;
+    if !is_compressible(path) {
+        return Some(Sizes {
+            raw,
+            gzip: raw,
+            brotli: raw,
+        });
+    }
+
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::new(GZIP_LEVEL));
+    gz.write_all(&bytes).ok()?;
+    let gzip = gz.finish().ok()?.len() as u64;
+
+    let mut br = brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_LGWIN);
+    br.write_all(&bytes).ok()?;
+    let brotli = br.into_inner().len() as u64;
+
+    Some(Sizes { raw, gzip, brotli })
+}
+
+fn is_compressible(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    matches!(
+        ext.as_str(),
+        "html" | "htm" | "js" | "mjs" | "css" | "svg" | "json" | "xml" | "txt" | "map"
+    )
+}
+
+/// Human-readable size: bytes below 1KB, otherwise KB with one decimal.
+pub fn kb(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes}B")
+    } else {
+        format!("{:.1}KB", bytes as f64 / 1024.0)
+    }
+}
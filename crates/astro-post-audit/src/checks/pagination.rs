@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use rayon::prelude::*;
+use scraper::Selector;
+
+use crate::config::{Config, UrlNormalizationConfig};
+use crate::discovery::{PageInfo, SiteIndex};
+use crate::normalize;
+use crate::report::{Finding, Level};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Rel {
+    Prev,
+    Next,
+}
+
+impl Rel {
+    fn as_str(self) -> &'static str {
+        match self {
+            Rel::Prev => "prev",
+            Rel::Next => "next",
+        }
+    }
+
+    fn opposite(self) -> Rel {
+        match self {
+            Rel::Prev => Rel::Next,
+            Rel::Next => Rel::Prev,
+        }
+    }
+}
+
+/// A `rel="prev"`/`rel="next"` reference on a page, on `<link>` or `<a>`.
+struct RelLink {
+    rel: Rel,
+    element: String,
+    href: String,
+    /// Normalized target route; None for external URLs.
+    target: Option<String>,
+}
+
+/// A paginated series: `/blog/`, `/blog/2/`, `/blog/3/`, ...
+struct Series<'a> {
+    /// Route of page 1 (`/blog/` for `[...page].astro`, `/blog/1/` for `[page].astro`).
+    first: String,
+    /// Page number -> page, including page 1 when it exists.
+    pages: BTreeMap<u32, &'a PageInfo>,
+}
+
+/// Validate paginated series produced by `paginate()`. A series is a set of
+/// routes `<prefix>/<n>/` that includes page 2, plus its first page at
+/// `<prefix>/` or `<prefix>/1/` — requiring page 2 keeps year archives
+/// (`/2023/`, `/2024/`) out. Series must be contiguous, each page must
+/// canonicalize to itself and have its own title, and every page past the
+/// first must be linked from another page of the series. `rel="prev"`/
+/// `rel="next"` targets are checked on every page: they must exist, point to
+/// the neighbouring page and be answered by the opposite rel.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    if !config.pagination.enabled {
+        return Vec::new();
+    }
+
+    let norm = &config.url_normalization;
+    let base = index.base_url.as_deref();
+    let rel_links: Vec<Vec<RelLink>> = index
+        .pages
+        .par_iter()
+        .map(|page| extract_rel_links(page, base, norm))
+        .collect();
+    let rel_by_route: HashMap<&str, &Vec<RelLink>> = index
+        .pages
+        .iter()
+        .zip(&rel_links)
+        .map(|(page, links)| (page.route.as_str(), links))
+        .collect();
+
+    let mut findings = Vec::new();
+
+    for (page, links) in index.pages.iter().zip(&rel_links) {
+        for link in links {
+            let Some(target) = &link.target else {
+                continue;
+            };
+            let finding = |level, rule_id: &str, message: String, help: &str| Finding {
+                level,
+                rule_id: rule_id.into(),
+                file: page.rel_path.clone(),
+                selector: format!(
+                    "{}[rel='{}'][href='{}']",
+                    link.element,
+                    link.rel.as_str(),
+                    link.href
+                ),
+                message,
+                help: help.into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            };
+            if !index.route_exists(target) {
+                findings.push(finding(
+                    Level::Error,
+                    "pagination/broken-link",
+                    format!("rel=\"{}\" points to '{}', which does not exist", link.rel.as_str(), link.href),
+                    "Pagination links usually break on the last or first page. Use page.url.next / page.url.prev from paginate(), which are undefined at the ends of the series.",
+                ));
+                continue;
+            }
+            let answers = rel_by_route.get(target.as_str()).is_some_and(|back| {
+                back.iter().any(|b| {
+                    b.rel == link.rel.opposite() && b.target.as_deref() == Some(&page.route)
+                })
+            });
+            if !answers {
+                findings.push(finding(
+                    Level::Warning,
+                    "pagination/not-reciprocal",
+                    format!(
+                        "'{}' is rel=\"{}\" of this page but has no rel=\"{}\" back to '{}'",
+                        target,
+                        link.rel.as_str(),
+                        link.rel.opposite().as_str(),
+                        page.route
+                    ),
+                    "Each page's next must list it as prev, and vice versa. Render both links from the same paginate() page props.",
+                ));
+            }
+        }
+    }
+
+    for series in detect_series(index, config) {
+        let page_number: HashMap<&str, u32> = series
+            .pages
+            .iter()
+            .map(|(n, p)| (p.route.as_str(), *n))
+            .collect();
+        let max = *series.pages.keys().next_back().unwrap_or(&1);
+
+        if !series.pages.contains_key(&1) {
+            let second = series.pages[&2];
+            findings.push(Finding {
+                level: Level::Error,
+                rule_id: "pagination/gap".into(),
+                file: second.rel_path.clone(),
+                selector: String::new(),
+                message: format!(
+                    "Paginated series '{}' has no first page (neither '{}' nor page 1)",
+                    series.first, series.first
+                ),
+                help: "paginate() renders page 1 at the base route for [...page].astro and at /1/ for [page].astro. Check getStaticPaths and any redirect for page 1.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+        let missing: Vec<u32> = (2..max).filter(|n| !series.pages.contains_key(n)).collect();
+        if !missing.is_empty() {
+            let last = series.pages[&max];
+            findings.push(Finding {
+                level: Level::Error,
+                rule_id: "pagination/gap".into(),
+                file: last.rel_path.clone(),
+                selector: String::new(),
+                message: format!(
+                    "Paginated series '{}' skips page(s) {} of {}",
+                    series.first,
+                    missing.iter().map(u32::to_string).collect::<Vec<_>>().join(", "),
+                    max
+                ),
+                help: "Every page between the first and the last must be generated. A filter applied after paginate() or a hard-coded page count usually causes gaps.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+
+        for (&n, page) in &series.pages {
+            let finding =
+                |level, rule_id: &str, selector: &str, message: String, help: &str| Finding {
+                    level,
+                    rule_id: rule_id.into(),
+                    file: page.rel_path.clone(),
+                    selector: selector.into(),
+                    message,
+                    help: help.into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                };
+
+            for link in rel_by_route
+                .get(page.route.as_str())
+                .into_iter()
+                .flat_map(|l| l.iter())
+            {
+                let Some(&target_n) = link.target.as_deref().and_then(|t| page_number.get(t))
+                else {
+                    continue;
+                };
+                let want = match link.rel {
+                    Rel::Prev => n - 1,
+                    Rel::Next => n + 1,
+                };
+                if target_n != want {
+                    findings.push(finding(
+                        Level::Warning,
+                        "pagination/wrong-target",
+                        &format!("{}[rel='{}']", link.element, link.rel.as_str()),
+                        format!(
+                            "rel=\"{}\" on page {n} points to page {target_n} instead of page {want}",
+                            link.rel.as_str()
+                        ),
+                        "prev and next must point to the adjacent pages of the series.",
+                    ));
+                }
+            }
+
+            for href in &page.canonical_hrefs {
+                let Some(resolved) = normalize::resolve_href(href, &page.route, base) else {
+                    continue;
+                };
+                let target = normalize::normalize_path(&resolved, norm);
+                if target != page.route {
+                    let first = page_number.get(target.as_str()) == Some(&1);
+                    findings.push(Finding {
+                        suggestion: Some(
+                            base.and_then(|b| normalize::to_absolute(&page.route, b))
+                                .unwrap_or_else(|| page.route.clone()),
+                        ),
+                        ..finding(
+                            Level::Warning,
+                            "pagination/canonical-not-self",
+                            "link[rel='canonical']",
+                            if first {
+                                format!("Page {n} of '{}' canonicalizes to page 1", series.first)
+                            } else {
+                                format!("Page {n} of '{}' canonicalizes to '{href}'", series.first)
+                            },
+                            "Each page of a series lists different items. Pointing the canonical at page 1 asks search engines to drop pages 2+ and the items only linked from them.",
+                        )
+                    });
+                }
+            }
+        }
+
+        let mut by_title: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+        for (&n, page) in &series.pages {
+            if let Some(title) = page.title_text.as_deref().map(str::trim) {
+                if !title.is_empty() {
+                    by_title.entry(title).or_default().push(n);
+                }
+            }
+        }
+        for (title, numbers) in by_title {
+            for &n in numbers.iter().skip(1) {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "pagination/duplicate-title".into(),
+                    file: series.pages[&n].rel_path.clone(),
+                    selector: "title".into(),
+                    message: format!(
+                        "Page {n} of '{}' has the same title as page {}: '{title}'",
+                        series.first, numbers[0]
+                    ),
+                    help: "Add the page number to the title of pages after the first, e.g. `${title} – Page ${page.currentPage}`.".into(),
+                    suggestion: Some(format!("{title} – Page {n}")),
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+
+        let mut linked: HashSet<String> = HashSet::new();
+        for page in series.pages.values() {
+            for href in &page.anchor_hrefs {
+                if !normalize::is_internal(href, base) {
+                    continue;
+                }
+                if let Some(resolved) = normalize::resolve_href(href, &page.route, base) {
+                    let target = normalize::normalize_path(&resolved, norm);
+                    if target != page.route {
+                        linked.insert(target);
+                    }
+                }
+            }
+        }
+        for (&n, page) in series.pages.range(2..) {
+            if !linked.contains(&page.route) {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "pagination/orphan".into(),
+                    file: page.rel_path.clone(),
+                    selector: String::new(),
+                    message: format!(
+                        "Page {n} of '{}' is not linked from any other page of the series",
+                        series.first
+                    ),
+                    help: "Crawlers reach deep pages only through the pagination links. Render <a href> links to the previous and next page (or numbered page links) on every page.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Group numbered routes into series. See `check_all` for the detection rule.
+fn detect_series<'a>(index: &'a SiteIndex, config: &Config) -> Vec<Series<'a>> {
+    let norm = &config.url_normalization;
+    let mut groups: BTreeMap<String, BTreeMap<u32, &PageInfo>> = BTreeMap::new();
+    for page in index.pages.iter().filter(|p| !p.is_error_page()) {
+        let route = page.route.trim_end_matches('/');
+        let Some((prefix, last)) = route.rsplit_once('/') else {
+            continue;
+        };
+        if last.is_empty() || last.starts_with('0') || !last.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        if let Ok(n) = last.parse::<u32>() {
+            groups
+                .entry(prefix.to_string())
+                .or_default()
+                .insert(n, page);
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, pages)| pages.contains_key(&2))
+        .map(|(prefix, mut pages)| {
+            let base_route = normalize::normalize_path(&format!("{prefix}/"), norm);
+            let first = match pages.get(&1) {
+                Some(page) => page.route.clone(),
+                None => {
+                    if let Some(&i) = index.route_to_index.get(&base_route) {
+                        pages.insert(1, &index.pages[i]);
+                    }
+                    base_route
+                }
+            };
+            Series { first, pages }
+        })
+        .collect()
+}
+
+fn extract_rel_links(
+    page: &PageInfo,
+    base: Option<&str>,
+    norm: &UrlNormalizationConfig,
+) -> Vec<RelLink> {
+    let html = page.parse_html();
+    let sel = Selector::parse("link[rel][href], a[rel][href]").unwrap();
+    let mut links = Vec::new();
+    for el in html.select(&sel) {
+        let rel_attr = el.value().attr("rel").unwrap_or("").to_ascii_lowercase();
+        let href = el.value().attr("href").unwrap_or("").trim();
+        for token in rel_attr.split_whitespace() {
+            let rel = match token {
+                "prev" | "previous" => Rel::Prev,
+                "next" => Rel::Next,
+                _ => continue,
+            };
+            let target = normalize::is_internal(href, base)
+                .then(|| normalize::resolve_href(href, &page.route, base))
+                .flatten()
+                .map(|p| normalize::normalize_path(&p, norm));
+            links.push(RelLink {
+                rel,
+                element: el.value().name().to_string(),
+                href: href.to_string(),
+                target,
+            });
+        }
+    }
+    links
+}
@@ -1,10 +1,14 @@
 use std::collections::HashSet;
 
-use scraper::Selector;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use scraper::{Node, Selector};
 use url::Url;
 
 use crate::config::Config;
-use crate::discovery::SiteIndex;
+use crate::discovery::crawl::HttpResponse;
+use crate::discovery::{PageInfo, SiteIndex};
 use crate::report::{Confidence, Finding, Level};
 
 const TRACKER_DOMAINS: &[&str] = &[
@@ -20,9 +24,14 @@ const TRACKER_DOMAINS: &[&str] = &[
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     let enabled = config.privacy_security.enabled;
     let gdpr = config.privacy_security.gdpr;
-    if !enabled && !gdpr {
+    let check_headers = config.privacy_security.check_headers;
+    if !enabled && !gdpr && !check_headers && !config.privacy_security.pii {
         return Vec::new();
     }
+    let pii = config
+        .privacy_security
+        .pii
+        .then(|| PiiScanner::new(&config.privacy_security.pii_allow));
 
     let mut findings = Vec::new();
     let url_sel = Selector::parse("[src], [href]").unwrap();
@@ -44,6 +53,16 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
             check_gdpr(page, &html, index, &mut findings);
         }
 
+        if let Some(scanner) = &pii {
+            check_pii(page, &html, scanner, &mut findings);
+        }
+
+        if check_headers {
+            if let Some(http) = &page.http {
+                check_privacy_headers(page, http, &mut findings);
+            }
+        }
+
         if !enabled {
             continue;
         }
@@ -162,6 +181,129 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     findings
 }
 
+/// Referrer policies that send the full URL (path and query) to other origins.
+const LEAKY_REFERRER_POLICIES: &[&str] = &["unsafe-url", "no-referrer-when-downgrade"];
+
+const REFERRER_POLICIES: &[&str] = &[
+    "no-referrer",
+    "no-referrer-when-downgrade",
+    "same-origin",
+    "origin",
+    "strict-origin",
+    "origin-when-cross-origin",
+    "strict-origin-when-cross-origin",
+    "unsafe-url",
+];
+
+/// Referrer-Policy and Permissions-Policy of a crawled page.
+fn check_privacy_headers(page: &PageInfo, http: &HttpResponse, findings: &mut Vec<Finding>) {
+    let finding = |level, rule_id: &str, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: page.rel_path.clone(),
+        selector: String::new(),
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    // The last policy the browser recognizes wins.
+    let referrer_policy = http
+        .header_values("referrer-policy")
+        .flat_map(|v| v.split(','))
+        .map(|t| t.trim().to_ascii_lowercase())
+        .filter(|t| REFERRER_POLICIES.contains(&t.as_str()))
+        .last();
+    match referrer_policy {
+        None => findings.push(Finding {
+            suggestion: Some("Referrer-Policy: strict-origin-when-cross-origin".into()),
+            ..finding(
+                Level::Info,
+                "privacy-security/referrer-policy-missing",
+                "Page is served without a Referrer-Policy header".into(),
+                "Browsers default to strict-origin-when-cross-origin; set it explicitly so older browsers and embedded views do not leak full URLs.",
+            )
+        }),
+        Some(policy) if LEAKY_REFERRER_POLICIES.contains(&policy.as_str()) => {
+            findings.push(Finding {
+                suggestion: Some("Referrer-Policy: strict-origin-when-cross-origin".into()),
+                ..finding(
+                    Level::Warning,
+                    "privacy-security/referrer-policy-unsafe",
+                    format!("Referrer-Policy '{policy}' sends full URLs, including query strings, to third parties"),
+                    "Use strict-origin-when-cross-origin or stricter.",
+                )
+            });
+        }
+        Some(_) => {}
+    }
+
+    let permissions: Vec<&str> = http.header_values("permissions-policy").collect();
+    if permissions.is_empty() {
+        let legacy = http.header("feature-policy").is_some();
+        findings.push(Finding {
+            suggestion: Some("Permissions-Policy: camera=(), microphone=(), geolocation=()".into()),
+            ..finding(
+                Level::Info,
+                "privacy-security/permissions-policy-missing",
+                if legacy {
+                    "Only the deprecated Feature-Policy header is sent; browsers now read Permissions-Policy".into()
+                } else {
+                    "Page is served without a Permissions-Policy header".into()
+                },
+                "Disable powerful features the site does not use so embedded third-party content cannot request them.",
+            )
+        });
+    }
+    for value in permissions {
+        let invalid: Vec<&str> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|member| !member.is_empty() && !is_permissions_member(member))
+            .collect();
+        if !invalid.is_empty() {
+            findings.push(finding(
+                Level::Warning,
+                "privacy-security/permissions-policy-invalid",
+                format!(
+                    "Permissions-Policy contains invalid entries: {}",
+                    invalid.join(", ")
+                ),
+                "Use structured-header syntax, e.g. `camera=()` or `geolocation=(self \"https://maps.example\")`; Feature-Policy syntax like `camera 'none'` is ignored.",
+            ));
+        }
+    }
+}
+
+/// `feature=allowlist` with an allowlist of `*`, `self`, `()` or a
+/// parenthesized list of tokens and quoted origins.
+fn is_permissions_member(member: &str) -> bool {
+    let Some((name, allowlist)) = member.split_once('=') else {
+        return false;
+    };
+    let name = name.trim();
+    let allowlist = allowlist.trim();
+    let name_ok = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    let allowlist_ok = match allowlist {
+        "*" | "self" => true,
+        _ => allowlist
+            .strip_prefix('(')
+            .and_then(|a| a.strip_suffix(')'))
+            .is_some_and(|items| {
+                items.split_whitespace().all(|item| {
+                    matches!(item, "*" | "self" | "src")
+                        || (item.len() > 2 && item.starts_with('"') && item.ends_with('"'))
+                })
+            }),
+    };
+    name_ok && allowlist_ok
+}
+
 /// Public CDN hosts whose use leaks visitor IPs to third parties (GDPR-relevant).
 const CDN_HOSTS: &[&str] = &[
     "unpkg.com",
@@ -298,6 +440,363 @@ fn check_gdpr(
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PiiKind {
+    Email,
+    Phone,
+    Iban,
+}
+
+/// Extensions that follow `@` in asset names such as `logo@2x.png`.
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "svg", "ico", "js", "css", "woff", "woff2",
+];
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// Attributes that carry text or links a visitor can read, as opposed to
+/// geometry, ids and styling.
+fn is_content_attribute(name: &str) -> bool {
+    matches!(
+        name,
+        "href" | "content" | "title" | "alt" | "value" | "placeholder"
+    ) || name.starts_with("aria-")
+        || name.starts_with("data-")
+}
+
+/// Compiled PII patterns and `pii_allow`.
+struct PiiScanner {
+    email: Regex,
+    /// `+49 30 1234567`, `0049 (0)30 1234567`; after `00` instead of `+`,
+    /// separators are required so plain digit runs do not count.
+    phone_international: Regex,
+    /// `030 1234567`, `(030) 123 45 67`; a separator after the area code is required.
+    phone_national: Regex,
+    iban: Regex,
+    /// Markers of internal notes and names in HTML comments.
+    note: Regex,
+    allow: GlobSet,
+}
+
+impl PiiScanner {
+    fn new(allow: &[String]) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in allow {
+            let kind = if pattern.contains('@') {
+                PiiKind::Email
+            } else if pattern.chars().any(|c| c.is_ascii_alphabetic()) {
+                PiiKind::Iban
+            } else {
+                PiiKind::Phone
+            };
+            if let Ok(glob) = Glob::new(&normalize_pii(kind, pattern)) {
+                builder.add(glob);
+            }
+        }
+        Self {
+            email: Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+                .unwrap(),
+            phone_international: Regex::new(
+                r"\+[1-9][0-9]{0,2}(?:[ ./-]?\(0\))?(?:[ ./-]?[0-9]{1,5}){2,6}|\b00[1-9][0-9]{0,2}(?:[ ./-]?\(0\))?(?:[ ./-][0-9]{1,5}){2,6}",
+            )
+            .unwrap(),
+            phone_national: Regex::new(r"\(?\b0[1-9][0-9]{1,4}\)?[ /-][0-9]{3,}(?:[ -][0-9]{1,5}){0,3}")
+                .unwrap(),
+            iban: Regex::new(r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,4})?\b")
+                .unwrap(),
+            note: Regex::new(
+                r"\b(?:TODO|FIXME|HACK|XXX)\b|(?i:\b(?:internal|intern|confidential|vertraulich|do not publish|nicht veröffentlichen|author|autor|assignee|ansprechpartner)\b)|(?:^|\s)@[A-Za-z][\w.-]+",
+            )
+            .unwrap(),
+            allow: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    /// PII values in `text`, in order of kind.
+    fn find(&self, text: &str) -> Vec<(PiiKind, String)> {
+        let mut found = Vec::new();
+        let mut taken: Vec<(usize, usize)> = Vec::new();
+        for m in self.email.find_iter(text) {
+            let ext = m
+                .as_str()
+                .rsplit('.')
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            if !ASSET_EXTENSIONS.contains(&ext.as_str()) {
+                taken.push((m.start(), m.end()));
+                found.push((PiiKind::Email, m.as_str().to_string()));
+            }
+        }
+        for m in self.iban.find_iter(text) {
+            if is_valid_iban(m.as_str()) {
+                taken.push((m.start(), m.end()));
+                found.push((PiiKind::Iban, m.as_str().to_string()));
+            }
+        }
+        for regex in [&self.phone_international, &self.phone_national] {
+            for m in regex.find_iter(text) {
+                if taken.iter().any(|&(s, e)| m.start() < e && s < m.end()) {
+                    continue;
+                }
+                // `1.0012 …` is a decimal number, not a `00` prefix.
+                if m.start() > 0 && matches!(text.as_bytes()[m.start() - 1], b'.' | b',') {
+                    continue;
+                }
+                let value = m.as_str().trim_end_matches([' ', '.', '/', '-']);
+                let digits = value.bytes().filter(u8::is_ascii_digit).count();
+                if (8..=15).contains(&digits) {
+                    taken.push((m.start(), m.end()));
+                    found.push((PiiKind::Phone, value.to_string()));
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Comparable form: lowercase email, compact uppercase IBAN, phone digits
+/// with `00` turned into `+`. Glob wildcards survive for allow patterns.
+fn normalize_pii(kind: PiiKind, value: &str) -> String {
+    match kind {
+        PiiKind::Email => value.trim().to_lowercase(),
+        PiiKind::Iban => value
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase(),
+        PiiKind::Phone => {
+            let compact: String = value
+                .chars()
+                .filter(|c| c.is_ascii_digit() || matches!(c, '+' | '*' | '?'))
+                .collect();
+            match compact.strip_prefix("00") {
+                Some(rest) => format!("+{rest}"),
+                None => compact,
+            }
+        }
+    }
+}
+
+/// ISO 13616 mod-97 check.
+fn is_valid_iban(value: &str) -> bool {
+    let compact = normalize_pii(PiiKind::Iban, value);
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let (head, tail) = compact.split_at(4);
+    let mut remainder = 0u32;
+    for c in tail.chars().chain(head.chars()) {
+        let Some(digit) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if digit >= 10 {
+            (remainder * 100 + digit) % 97
+        } else {
+            (remainder * 10 + digit) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// `j***@example.com`, `+49…67`, `DE89 **** 3000`.
+fn redact_pii(kind: PiiKind, value: &str) -> String {
+    match kind {
+        PiiKind::Email => {
+            let (local, domain) = value.split_once('@').unwrap_or((value, ""));
+            let first: String = local.chars().take(1).collect();
+            format!("{first}***@{domain}")
+        }
+        PiiKind::Phone => {
+            let chars: Vec<char> = value.chars().collect();
+            let head: String = chars.iter().take(3).collect();
+            let tail: String = chars[chars.len().saturating_sub(2)..].iter().collect();
+            format!("{head}…{tail}")
+        }
+        PiiKind::Iban => {
+            let compact = normalize_pii(kind, value);
+            format!("{} **** {}", &compact[..4], &compact[compact.len() - 4..])
+        }
+    }
+}
+
+/// A PII value on a page, first occurrence only.
+struct PiiHit {
+    kind: PiiKind,
+    value: String,
+    /// `text`, `[href]`, `comment` or the JSON-LD script selector.
+    location: String,
+}
+
+fn add_pii_hit(
+    hits: &mut Vec<PiiHit>,
+    scanner: &PiiScanner,
+    kind: PiiKind,
+    value: &str,
+    location: &str,
+) {
+    let key = normalize_pii(kind, value);
+    if scanner.allow.is_match(&key)
+        || hits
+            .iter()
+            .any(|h| h.kind == kind && normalize_pii(h.kind, &h.value) == key)
+    {
+        return;
+    }
+    hits.push(PiiHit {
+        kind,
+        value: value.to_string(),
+        location: location.to_string(),
+    });
+}
+
+/// Personal data in the rendered page: email addresses, phone numbers and
+/// IBANs in text, attribute values, JSON-LD and HTML comments, plus comments
+/// that carry internal notes or names. One finding per page and kind, with
+/// redacted values.
+fn check_pii(
+    page: &crate::discovery::PageInfo,
+    html: &scraper::Html,
+    scanner: &PiiScanner,
+    findings: &mut Vec<Finding>,
+) {
+    const JSON_LD: &str = "script[type='application/ld+json']";
+    let mut hits: Vec<PiiHit> = Vec::new();
+    let mut notes: Vec<String> = Vec::new();
+
+    for node in html.tree.root().descendants() {
+        match node.value() {
+            Node::Text(text) => {
+                let parent = node.parent().and_then(|p| p.value().as_element());
+                let location = match parent.map(|e| (e.name(), e.attr("type"))) {
+                    Some(("script", Some(t))) if t.eq_ignore_ascii_case("application/ld+json") => {
+                        JSON_LD
+                    }
+                    Some(("script" | "style", _)) => continue,
+                    _ => "text",
+                };
+                for (kind, value) in scanner.find(text) {
+                    add_pii_hit(&mut hits, scanner, kind, &value, location);
+                }
+            }
+            Node::Element(el) => {
+                // SVG geometry (`d`, `points`, `viewBox`) is full of digit runs.
+                if &*el.name.ns == SVG_NAMESPACE {
+                    continue;
+                }
+                for (name, value) in el.attrs() {
+                    if !is_content_attribute(name) {
+                        continue;
+                    }
+                    let location = format!("[{name}]");
+                    let value = value.trim();
+                    // `tel:` links carry numbers in any format, percent-encoded
+                    // and with optional `;ext=` parameters. Short service
+                    // numbers (`tel:112`) are not personal.
+                    if let Some(number) = value.strip_prefix("tel:") {
+                        let decoded = percent_decode_str(number).decode_utf8_lossy();
+                        let number = decoded.split(';').next().unwrap_or("").trim();
+                        let digits = number.bytes().filter(u8::is_ascii_digit).count();
+                        if (8..=15).contains(&digits) {
+                            add_pii_hit(&mut hits, scanner, PiiKind::Phone, number, &location);
+                        }
+                        continue;
+                    }
+                    for (kind, found) in scanner.find(value) {
+                        add_pii_hit(&mut hits, scanner, kind, &found, &location);
+                    }
+                }
+            }
+            Node::Comment(comment) => {
+                let found = scanner.find(comment);
+                for (kind, value) in &found {
+                    add_pii_hit(&mut hits, scanner, *kind, value, "comment");
+                }
+                if scanner.note.is_match(comment) {
+                    let mut snippet = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                    for (kind, value) in &found {
+                        snippet = snippet.replace(value.as_str(), &redact_pii(*kind, value));
+                    }
+                    if snippet.chars().count() > 60 {
+                        snippet = snippet.chars().take(60).collect::<String>() + "…";
+                    }
+                    notes.push(snippet);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for kind in [PiiKind::Email, PiiKind::Phone, PiiKind::Iban] {
+        let of_kind: Vec<&PiiHit> = hits.iter().filter(|h| h.kind == kind).collect();
+        let Some(first) = of_kind.first() else {
+            continue;
+        };
+        let (rule_id, noun, help, confidence) = match kind {
+            PiiKind::Email => (
+                "privacy-security/pii-email",
+                "email address(es)",
+                "Plain-text addresses are harvested by spam bots, and personal addresses are personal data under GDPR. Publish a role address (info@, kontakt@) or a contact form, and add intended addresses to `privacy_security.pii_allow`.",
+                None,
+            ),
+            PiiKind::Phone => (
+                "privacy-security/pii-phone",
+                "phone number(s)",
+                "Personal phone numbers are personal data under GDPR. Keep only the numbers you mean to publish (imprint, contact page) and add them to `privacy_security.pii_allow`.",
+                Some(Confidence::Medium),
+            ),
+            PiiKind::Iban => (
+                "privacy-security/pii-iban",
+                "IBAN(s)",
+                "Bank details rarely belong on a web page. Remove it, or add the published company account to `privacy_security.pii_allow`.",
+                None,
+            ),
+        };
+        let mut listed: Vec<String> = of_kind
+            .iter()
+            .take(5)
+            .map(|h| format!("{} ({})", redact_pii(kind, &h.value), h.location))
+            .collect();
+        if of_kind.len() > 5 {
+            listed.push(format!("+{} more", of_kind.len() - 5));
+        }
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: rule_id.into(),
+            file: page.rel_path.clone(),
+            selector: first.location.clone(),
+            message: format!(
+                "{} {noun} in the page: {}",
+                of_kind.len(),
+                listed.join(", ")
+            ),
+            help: help.into(),
+            suggestion: None,
+            source_hint: None,
+            confidence,
+        });
+    }
+
+    if !notes.is_empty() {
+        let listed: Vec<String> = notes.iter().take(3).map(|n| format!("'{n}'")).collect();
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "privacy-security/pii-comment".into(),
+            file: page.rel_path.clone(),
+            selector: "comment".into(),
+            message: format!(
+                "{} HTML comment(s) with internal notes or names: {}",
+                notes.len(),
+                listed.join(", ")
+            ),
+            help: "HTML comments in .astro templates are shipped to every visitor. Use `{/* ... */}` in the template or `//` in the frontmatter, which are stripped at build time.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Low),
+        });
+    }
+}
+
 fn host_from_url(value: &str) -> Option<String> {
     Url::parse(value)
         .ok()
@@ -1,12 +1,25 @@
 use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
 
 use crate::config::Config;
 use crate::discovery::SiteIndex;
+use crate::hosting::{self, HostingConfig};
 use crate::normalize;
 use crate::report::{Confidence, Finding, Level};
 
-/// Analyze Astro's static meta-refresh redirects (generated from `redirects` in
-/// `astro.config.mjs`): links pointing at redirect pages, redirect chains, and loops.
+/// Where a redirect is defined, for reporting.
+struct RedirectSource {
+    file: String,
+    selector: String,
+    /// Defined in hosting config rather than by a meta-refresh page.
+    configured: bool,
+}
+
+/// Analyze redirects: Astro's static meta-refresh pages (generated from
+/// `redirects` in `astro.config.mjs`) and the host's own rules in
+/// `_redirects`, `netlify.toml` and `vercel.json`. Reports links pointing at
+/// redirects, chains and loops across both, rule targets missing from the
+/// build, and rules shadowed by real pages.
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     if !config.redirects.enabled {
         return Vec::new();
@@ -14,17 +27,32 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
 
     let norm = &config.url_normalization;
     let base = index.base_url.as_deref();
+    let hosting = if config.redirects.hosting_config {
+        hosting::load(
+            &index.dist_path,
+            config.project_root.as_deref().map(Path::new),
+        )
+    } else {
+        HostingConfig::default()
+    };
 
     // route -> normalized internal redirect target (only internal targets tracked).
     let mut redirect_map: HashMap<String, String> = HashMap::new();
-    // route -> rel_path of the redirect page (for reporting).
-    let mut file_of: HashMap<String, String> = HashMap::new();
+    // route -> where the redirect is defined (for reporting).
+    let mut source_of: HashMap<String, RedirectSource> = HashMap::new();
 
     for page in &index.pages {
         let Some(target) = &page.meta_refresh_target else {
             continue;
         };
-        file_of.insert(page.route.clone(), page.rel_path.clone());
+        source_of.insert(
+            page.route.clone(),
+            RedirectSource {
+                file: page.rel_path.clone(),
+                selector: "meta[http-equiv='refresh']".into(),
+                configured: false,
+            },
+        );
         if !normalize::is_internal(target, base) {
             continue;
         }
@@ -37,9 +65,43 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     }
 
     let mut findings = Vec::new();
+    findings.extend(check_hosting_rules(index, config, &hosting));
+
+    // Exact configured redirects join the graph. The first rule for a route
+    // wins; unforced rules never fire where a page exists.
+    let mut configured_routes: HashSet<String> = HashSet::new();
+    for rule in &hosting.redirects {
+        if !rule.is_redirect() || rule.conditional || !rule.is_exact() {
+            continue;
+        }
+        let route = normalize::normalize_path(&rule.from, norm);
+        if configured_routes.contains(&route) || (index.route_exists(&route) && !rule.force) {
+            continue;
+        }
+        configured_routes.insert(route.clone());
+        source_of.insert(
+            route.clone(),
+            RedirectSource {
+                file: rule.file.clone(),
+                selector: rule.location.clone(),
+                configured: true,
+            },
+        );
+        redirect_map.remove(&route);
+        if !normalize::is_internal(&rule.to, base) {
+            continue;
+        }
+        if let Some(resolved) = normalize::resolve_href(&rule.to, "/", base) {
+            let target_route = normalize::normalize_path(&resolved, norm);
+            if target_route != route {
+                redirect_map.insert(route, target_route);
+            }
+        }
+    }
+
     let redirect_routes: HashSet<&String> = redirect_map.keys().collect();
 
-    // 1. Internal links that point at a redirect page instead of the final target.
+    // 1. Internal links that point at a redirect instead of the final target.
     let mut reported_links: HashSet<(String, String)> = HashSet::new();
     for page in &index.pages {
         for href in &page.anchor_hrefs {
@@ -50,21 +112,40 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
                 continue;
             };
             let route = normalize::normalize_path(&resolved, norm);
-            if route == page.route || !redirect_routes.contains(&route) {
+            if route == page.route {
                 continue;
             }
+            let (via, final_target) = if redirect_routes.contains(&route) {
+                let via = match source_of.get(&route) {
+                    Some(s) if s.configured => format!(" ({} {})", s.file, s.selector),
+                    _ => String::new(),
+                };
+                (via, resolve_final(&route, &redirect_map))
+            } else if let Some((rule, target)) = pattern_redirect(&hosting, index, &route) {
+                let final_target = if normalize::is_internal(&target, base) {
+                    let target = normalize::normalize_path(
+                        normalize::strip_fragment_and_query(&target),
+                        norm,
+                    );
+                    resolve_final(&target, &redirect_map)
+                } else {
+                    target
+                };
+                (format!(" ({} {})", rule.file, rule.location), final_target)
+            } else {
+                continue;
+            };
             if !reported_links.insert((page.rel_path.clone(), route.clone())) {
                 continue;
             }
-            let final_target = resolve_final(&route, &redirect_map);
             findings.push(Finding {
                 level: Level::Warning,
                 rule_id: "links/redirect-target".into(),
                 file: page.rel_path.clone(),
                 selector: format!("a[href='{}']", href),
                 message: format!(
-                    "Internal link points to redirect page '{}' (final target: '{}')",
-                    route, final_target
+                    "Internal link points to redirect page '{}'{} (final target: '{}')",
+                    route, via, final_target
                 ),
                 help: "Link directly to the final URL to avoid an unnecessary redirect hop.".into(),
                 suggestion: None,
@@ -78,8 +159,14 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     let mut reported_loops: HashSet<BTreeSet<String>> = HashSet::new();
     // Heads = redirect routes that are not themselves a target of another redirect.
     let targets: HashSet<&String> = redirect_map.values().collect();
+    let location = |route: &String| match source_of.get(route) {
+        Some(s) => (s.file.clone(), s.selector.clone()),
+        None => (route.clone(), String::new()),
+    };
 
-    for start in redirect_map.keys() {
+    let mut starts: Vec<&String> = redirect_map.keys().collect();
+    starts.sort();
+    for start in starts {
         let mut path: Vec<String> = vec![start.clone()];
         let mut current = start.clone();
 
@@ -88,14 +175,14 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
                 // Loop detected.
                 let cycle: BTreeSet<String> = path[cycle_start..].iter().cloned().collect();
                 if reported_loops.insert(cycle) {
-                    let file = file_of.get(start).cloned().unwrap_or_else(|| start.clone());
+                    let (file, selector) = location(start);
                     let mut display = path[cycle_start..].to_vec();
                     display.push(next.clone());
                     findings.push(Finding {
                         level: Level::Error,
                         rule_id: "redirects/loop".into(),
                         file,
-                        selector: "meta[http-equiv='refresh']".into(),
+                        selector,
                         message: format!("Redirect loop detected: {}", display.join(" -> ")),
                         help: "Break the cycle — a redirect loop makes the page unreachable."
                             .into(),
@@ -113,12 +200,12 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         // Emit a chain finding only from the head of a chain to avoid duplicates.
         let is_head = !targets.contains(start);
         if is_head && path.len() > 2 {
-            let file = file_of.get(start).cloned().unwrap_or_else(|| start.clone());
+            let (file, selector) = location(start);
             findings.push(Finding {
                 level: Level::Warning,
                 rule_id: "redirects/chain".into(),
                 file,
-                selector: "meta[http-equiv='refresh']".into(),
+                selector,
                 message: format!(
                     "Redirect chain of length {}: {}",
                     path.len() - 1,
@@ -135,6 +222,112 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     findings
 }
 
+/// Parse errors, rule targets missing from the build, and rules shadowed by
+/// real pages.
+fn check_hosting_rules(
+    index: &SiteIndex,
+    config: &Config,
+    hosting: &HostingConfig,
+) -> Vec<Finding> {
+    let norm = &config.url_normalization;
+    let base = index.base_url.as_deref();
+    let mut findings = Vec::new();
+
+    for error in &hosting.errors {
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "redirects/config-invalid".into(),
+            file: error.file.clone(),
+            selector: error.location.clone(),
+            message: format!("{}: {}", error.file, error.message),
+            help: "The host skips lines it cannot parse. Fix the syntax so the rule takes effect."
+                .into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
+    for rule in &hosting.redirects {
+        let label = format!("{} -> {}", rule.from, rule.to);
+
+        // Targets inside the site must exist (placeholders cannot be checked).
+        if normalize::is_internal(&rule.to, base)
+            && hosting::is_exact(&rule.to)
+            && !rule.to.contains('$')
+        {
+            let path = normalize::resolve_href(&rule.to, "/", base).unwrap_or_default();
+            let route = normalize::normalize_path(&path, norm);
+            let exists = index.route_exists(&route)
+                || index.file_exists(path.trim_start_matches('/'))
+                || hosting.redirects.iter().any(|other| {
+                    !std::ptr::eq(other, rule)
+                        && other.status < 400
+                        && other.target_for(&route).is_some()
+                });
+            if !exists {
+                findings.push(Finding {
+                    level: Level::Error,
+                    rule_id: "redirects/target-missing".into(),
+                    file: rule.file.clone(),
+                    selector: rule.location.clone(),
+                    message: format!(
+                        "Rule '{label}' points to '{}', which is not in the build",
+                        rule.to
+                    ),
+                    help: "Visitors following this rule land on a 404. Fix the target or remove the rule.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+
+        // Unforced rules only apply when no file exists at the source.
+        if rule.force || rule.conditional || !rule.is_exact() {
+            continue;
+        }
+        let route = normalize::normalize_path(&rule.from, norm);
+        let Some(&page_idx) = index.route_to_index.get(&route) else {
+            continue;
+        };
+        let page = &index.pages[page_idx];
+        // Astro's own meta-refresh fallback for the same redirect is fine.
+        if page.meta_refresh_target.is_some() {
+            continue;
+        }
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "redirects/shadowed".into(),
+            file: rule.file.clone(),
+            selector: rule.location.clone(),
+            message: format!(
+                "Rule '{label}' never applies: '{}' is served by {}",
+                rule.from, page.rel_path
+            ),
+            help: "Static hosts serve existing files before unforced rules. Remove the stale page, or force the rule (`301!` in _redirects, `force = true` in netlify.toml).".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Medium),
+        });
+    }
+
+    findings
+}
+
+/// The first splat / placeholder redirect rule that fires for `route`, with
+/// its expanded target.
+fn pattern_redirect<'a>(
+    hosting: &'a HostingConfig,
+    index: &SiteIndex,
+    route: &str,
+) -> Option<(&'a hosting::RedirectRule, String)> {
+    let (rule, target) = hosting.first_match(route)?;
+    let fires =
+        rule.is_redirect() && !rule.is_exact() && (rule.force || !index.route_exists(route));
+    fires.then_some((rule, target))
+}
+
 /// Follow the redirect chain from `route` to its final destination, stopping on
 /// a non-redirect target or a cycle.
 fn resolve_final(route: &str, redirect_map: &HashMap<String, String>) -> String {
@@ -3,6 +3,7 @@ use url::Url;
 use crate::config::Config;
 use crate::discovery::SiteIndex;
 use crate::report::{Finding, Level};
+use crate::robots::{self, Robots};
 
 /// AI citation bots — blocking them reduces AI search visibility.
 const AI_CITATION_BOTS: &[&str] = &[
@@ -39,83 +40,76 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         return findings;
     }
 
-    let content = match std::fs::read_to_string(&robots_path) {
-        Ok(c) => c,
-        Err(_) => return findings,
+    let Some(robots) = Robots::load(&index.dist_path) else {
+        return findings;
     };
 
+    if robots.truncated {
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "robots-txt/too-large".into(),
+            file: "robots.txt".into(),
+            selector: String::new(),
+            message: format!(
+                "robots.txt is larger than {} KiB; crawlers ignore everything past that point",
+                robots::MAX_SIZE / 1024
+            ),
+            help: "Consolidate rules with `*` wildcards and shorter path prefixes instead of listing URLs one by one.".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
+
     // Sitemap directive
-    if config.robots_txt.require_sitemap_link {
-        let has_sitemap = content
-            .lines()
-            .any(|line| line.trim().to_lowercase().starts_with("sitemap:"));
+    if config.robots_txt.require_sitemap_link && robots.sitemaps.is_empty() {
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "robots-txt/no-sitemap".into(),
+            file: "robots.txt".into(),
+            selector: String::new(),
+            message: "robots.txt does not contain a Sitemap directive".into(),
+            help: "Add 'Sitemap: https://example.com/sitemap.xml' to robots.txt".into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        });
+    }
 
-        if !has_sitemap {
+    // Check for global Disallow: / (all crawlers blocked)
+    if config.robots_txt.check_disallow_all {
+        if blocks_root(&robots, "*") {
             findings.push(Finding {
-                level: Level::Warning,
-                rule_id: "robots-txt/no-sitemap".into(),
+                level: Level::Error,
+                rule_id: "robots-txt/disallow-all".into(),
                 file: "robots.txt".into(),
                 selector: String::new(),
-                message: "robots.txt does not contain a Sitemap directive".into(),
-                help: "Add 'Sitemap: https://example.com/sitemap.xml' to robots.txt".into(),
+                message: "robots.txt blocks all crawlers with 'Disallow: /'".into(),
+                help: "Remove 'Disallow: /' for User-agent: * to allow search engine indexing"
+                    .into(),
                 suggestion: None,
                 source_hint: None,
                 confidence: None,
             });
         }
-    }
-
-    // Parse robots.txt into agent → directive pairs
-    let blocks = parse_robots_blocks(&content);
 
-    // Check for global Disallow: / (all crawlers blocked)
-    if config.robots_txt.check_disallow_all {
-        for block in &blocks {
-            let is_global = block.agents.iter().any(|a| a == "*");
-            let has_disallow_all = block.disallows.iter().any(|d| d == "/");
-            // Only flag if there's no Allow: / or Allow entries that override
-            let has_allow_all = block.allows.iter().any(|a| a == "/");
-
-            if is_global && has_disallow_all && !has_allow_all {
+        // Search bots with a group of their own ignore the * group.
+        for bot in &["Googlebot", "Bingbot"] {
+            if robots.specific_groups(bot).is_some() && blocks_root(&robots, bot) {
                 findings.push(Finding {
                     level: Level::Error,
-                    rule_id: "robots-txt/disallow-all".into(),
+                    rule_id: "robots-txt/disallow-search-bot".into(),
                     file: "robots.txt".into(),
                     selector: String::new(),
-                    message: "robots.txt blocks all crawlers with 'Disallow: /'".into(),
-                    help: "Remove 'Disallow: /' for User-agent: * to allow search engine indexing"
-                        .into(),
+                    message: format!("robots.txt blocks {} with 'Disallow: /'", bot),
+                    help: format!(
+                        "Remove 'Disallow: /' for {} to allow search engine indexing",
+                        bot
+                    ),
                     suggestion: None,
                     source_hint: None,
                     confidence: None,
                 });
-                break;
-            }
-        }
-
-        // Also check Googlebot/Bingbot specific blocks
-        for bot in &["Googlebot", "Bingbot"] {
-            for block in &blocks {
-                let is_bot = block.agents.iter().any(|a| a.eq_ignore_ascii_case(bot));
-                let has_disallow_all = block.disallows.iter().any(|d| d == "/");
-                let has_allow_all = block.allows.iter().any(|a| a == "/");
-
-                if is_bot && has_disallow_all && !has_allow_all {
-                    findings.push(Finding {
-                        level: Level::Error,
-                        rule_id: "robots-txt/disallow-search-bot".into(),
-                        file: "robots.txt".into(),
-                        selector: String::new(),
-                        message: format!("robots.txt blocks {} with 'Disallow: /'", bot),
-                        help: format!(
-                            "Remove 'Disallow: /' for {} to allow search engine indexing",
-                            bot
-                        ),
-                        suggestion: None,
-                        source_hint: None,
-                        confidence: None,
-                    });
-                }
             }
         }
     }
@@ -123,275 +117,209 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     // Crawl-delay check
     if config.robots_txt.max_crawl_delay > 0 {
         let max = config.robots_txt.max_crawl_delay;
-        for line in content.lines() {
-            let trimmed = line.trim().to_lowercase();
-            if let Some(rest) = trimmed.strip_prefix("crawl-delay:") {
-                let val_str = rest.trim();
-                if let Ok(delay) = val_str.parse::<f64>() {
-                    if delay > max as f64 {
-                        findings.push(Finding {
-                            level: Level::Warning,
-                            rule_id: "robots-txt/crawl-delay-high".into(),
-                            file: "robots.txt".into(),
-                            selector: String::new(),
-                            message: format!(
-                                "Crawl-delay of {} seconds is very high (max recommended: {})",
-                                delay, max
-                            ),
-                            help: "High crawl delays reduce how often search engines index your content. Use a value ≤ 10.".into(),
-                            suggestion: None,
-                            source_hint: None,
-                            confidence: None,
-                        });
-                    }
-                }
+        for delay in robots.groups.iter().filter_map(|g| g.crawl_delay) {
+            if delay > max as f64 {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "robots-txt/crawl-delay-high".into(),
+                    file: "robots.txt".into(),
+                    selector: String::new(),
+                    message: format!(
+                        "Crawl-delay of {} seconds is very high (max recommended: {})",
+                        delay, max
+                    ),
+                    help: "High crawl delays reduce how often search engines index your content. Use a value ≤ 10.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
             }
         }
     }
 
-    // AI bot policy
+    // AI bot policy: only bots named in a group of their own; the rest
+    // follow the * group, which disallow-all already covers.
     if config.robots_txt.ai_bot_policy {
-        for block in &blocks {
-            for agent in &block.agents {
-                let is_citation_bot = AI_CITATION_BOTS
-                    .iter()
-                    .any(|b| agent.eq_ignore_ascii_case(b));
-                let has_disallow = block.disallows.iter().any(|d| d == "/");
-                let has_allow = block.allows.iter().any(|a| a == "/");
-
-                if is_citation_bot && has_disallow && !has_allow {
-                    findings.push(Finding {
-                        level: Level::Warning,
-                        rule_id: "robots-txt/ai-citation-bot-blocked".into(),
-                        file: "robots.txt".into(),
-                        selector: String::new(),
-                        message: format!(
-                            "AI citation bot '{}' is blocked — reduces AI search visibility",
-                            agent
-                        ),
-                        help: format!(
-                            "Remove 'Disallow: /' for {} to allow AI-powered search engines to cite your content",
-                            agent
-                        ),
-                        suggestion: None,
-                        source_hint: None,
-                        confidence: None,
-                    });
-                }
-
-                let is_training_bot = AI_TRAINING_BOTS
-                    .iter()
-                    .any(|b| agent.eq_ignore_ascii_case(b));
-                if is_training_bot && !has_disallow {
-                    findings.push(Finding {
-                        level: Level::Info,
-                        rule_id: "robots-txt/ai-training-bot-allowed".into(),
-                        file: "robots.txt".into(),
-                        selector: String::new(),
-                        message: format!(
-                            "AI training bot '{}' is allowed — consider blocking if you don't want your content used for training",
-                            agent
-                        ),
-                        help: format!(
-                            "Add 'User-agent: {}\nDisallow: /' to block AI training crawlers",
-                            agent
-                        ),
-                        suggestion: None,
-                        source_hint: None,
-                        confidence: None,
-                    });
-                }
+        for bot in AI_CITATION_BOTS {
+            if robots.specific_groups(bot).is_some() && blocks_root(&robots, bot) {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "robots-txt/ai-citation-bot-blocked".into(),
+                    file: "robots.txt".into(),
+                    selector: String::new(),
+                    message: format!(
+                        "AI citation bot '{}' is blocked — reduces AI search visibility",
+                        bot
+                    ),
+                    help: format!(
+                        "Remove 'Disallow: /' for {} to allow AI-powered search engines to cite your content",
+                        bot
+                    ),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+        for bot in AI_TRAINING_BOTS {
+            if robots.specific_groups(bot).is_some() && !blocks_root(&robots, bot) {
+                findings.push(Finding {
+                    level: Level::Info,
+                    rule_id: "robots-txt/ai-training-bot-allowed".into(),
+                    file: "robots.txt".into(),
+                    selector: String::new(),
+                    message: format!(
+                        "AI training bot '{}' is allowed — consider blocking if you don't want your content used for training",
+                        bot
+                    ),
+                    help: format!(
+                        "Add 'User-agent: {}\nDisallow: /' to block AI training crawlers",
+                        bot
+                    ),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
             }
         }
     }
 
-    // Contradiction checks need the rules that apply to the wildcard (*) user-agent group.
-    if config.robots_txt.check_noindex_contradiction || config.robots_txt.check_sitemap_blocked {
-        let (disallows, allows) = wildcard_rules(&blocks);
-
-        // noindex page that is also Disallow'd: crawlers can't see the noindex tag.
-        if config.robots_txt.check_noindex_contradiction {
-            for page in &index.pages {
-                if page.noindex && path_is_disallowed(&disallows, &allows, &page.route) {
-                    findings.push(Finding {
-                        level: Level::Error,
-                        rule_id: "robots/blocked-noindex-contradiction".into(),
-                        file: page.rel_path.clone(),
-                        selector: "meta[name='robots']".into(),
-                        message: format!(
-                            "Page '{}' is Disallow'd in robots.txt but also has noindex",
-                            page.route
-                        ),
-                        help: "Crawlers blocked by robots.txt cannot read the noindex tag, so the page may stay indexed. Allow crawling, or drop the noindex and remove internal links instead.".into(),
-                        suggestion: None,
-                        source_hint: None,
-                        confidence: None,
-                    });
-                }
+    // Contradictions are judged for a crawler without a group of its own.
+    // noindex page that is also Disallow'd: crawlers can't see the noindex tag.
+    if config.robots_txt.check_noindex_contradiction {
+        for page in &index.pages {
+            if page.noindex && !robots.is_allowed("*", &page.route) {
+                findings.push(Finding {
+                    level: Level::Error,
+                    rule_id: "robots/blocked-noindex-contradiction".into(),
+                    file: page.rel_path.clone(),
+                    selector: "meta[name='robots']".into(),
+                    message: format!(
+                        "Page '{}' is Disallow'd in robots.txt but also has noindex",
+                        page.route
+                    ),
+                    help: "Crawlers blocked by robots.txt cannot read the noindex tag, so the page may stay indexed. Allow crawling, or drop the noindex and remove internal links instead.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
             }
         }
+    }
 
-        // Sitemap URLs that robots.txt blocks send mixed signals to search engines.
-        if config.robots_txt.check_sitemap_blocked {
-            for url in &index.sitemap_urls {
-                let path = Url::parse(url)
-                    .ok()
-                    .map(|u| u.path().to_string())
-                    .unwrap_or_else(|| url.clone());
-                if path_is_disallowed(&disallows, &allows, &path) {
-                    findings.push(Finding {
-                        level: Level::Warning,
-                        rule_id: "sitemap/entry-blocked-by-robots".into(),
-                        file: "sitemap.xml".into(),
-                        selector: String::new(),
-                        message: format!("Sitemap URL '{}' is blocked by robots.txt", url),
-                        help: "A sitemap should only list crawlable URLs. Remove the entry or allow it in robots.txt.".into(),
-                        suggestion: None,
-                        source_hint: None,
-                        confidence: None,
-                    });
-                }
+    // Sitemap URLs that robots.txt blocks send mixed signals to search engines.
+    if config.robots_txt.check_sitemap_blocked {
+        for url in &index.sitemap_urls {
+            let path = Url::parse(url)
+                .ok()
+                .map(|u| u.path().to_string())
+                .unwrap_or_else(|| url.clone());
+            if !robots.is_allowed("*", &path) {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "sitemap/entry-blocked-by-robots".into(),
+                    file: index.sitemap_source(url).into(),
+                    selector: String::new(),
+                    message: format!("Sitemap URL '{}' is blocked by robots.txt", url),
+                    help: "A sitemap should only list crawlable URLs. Remove the entry or allow it in robots.txt.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
             }
         }
     }
 
+    if config.robots_txt.bot_coverage {
+        findings.extend(check_bot_coverage(index, &robots));
+    }
+
     findings
 }
 
-/// Collect Disallow/Allow rules that apply to the wildcard (`*`) user-agent group.
-fn wildcard_rules(blocks: &[RobotsBlock]) -> (Vec<String>, Vec<String>) {
-    let mut disallows = Vec::new();
-    let mut allows = Vec::new();
-    for block in blocks {
-        if block.agents.iter().any(|a| a == "*") {
-            disallows.extend(block.disallows.iter().cloned());
-            allows.extend(block.allows.iter().cloned());
-        }
-    }
-    (disallows, allows)
+/// Whether `user_agent` may not fetch the home page because of a `Disallow: /`.
+fn blocks_root(robots: &Robots, user_agent: &str) -> bool {
+    let verdict = robots.check(user_agent, "/");
+    !verdict.allowed
+        && verdict
+            .rule
+            .is_some_and(|r| r.pattern == "/" || r.pattern == "/*")
 }
 
-/// Determine whether `path` is disallowed, applying RFC 9309 longest-match
-/// precedence (the more specific rule wins; on equal specificity, Allow wins).
-fn path_is_disallowed(disallows: &[String], allows: &[String], path: &str) -> bool {
-    let best_disallow = disallows
-        .iter()
-        .filter_map(|r| rule_match_len(r, path))
-        .max();
-    let best_allow = allows.iter().filter_map(|r| rule_match_len(r, path)).max();
-    match (best_disallow, best_allow) {
-        (Some(d), Some(a)) => d > a,
-        (Some(_), None) => true,
-        _ => false,
-    }
-}
+/// Number of blocked routes listed in a coverage finding.
+const COVERAGE_EXAMPLES: usize = 5;
 
-/// If `pattern` matches `path`, return its specificity (count of literal,
-/// non-wildcard characters); otherwise None. Supports `*` wildcards and the
-/// `$` end-anchor. An empty pattern (`Disallow:`) never matches (allow-all).
-fn rule_match_len(pattern: &str, path: &str) -> Option<usize> {
-    if pattern.is_empty() {
-        return None;
+/// For each major search and AI crawler, report how many indexable routes it
+/// may fetch and which ones it may not.
+fn check_bot_coverage(index: &SiteIndex, robots: &Robots) -> Vec<Finding> {
+    let mut routes: Vec<&str> = index
+        .pages
+        .iter()
+        .filter(|p| !p.noindex && !p.is_error_page())
+        .map(|p| p.route.as_str())
+        .collect();
+    routes.sort_unstable();
+    if routes.is_empty() {
+        return Vec::new();
     }
-    let anchored = pattern.ends_with('$');
-    let pat = if anchored {
-        &pattern[..pattern.len() - 1]
-    } else {
-        pattern
-    };
 
-    let parts: Vec<&str> = pat.split('*').collect();
-    let mut pos = 0usize;
-    for (i, part) in parts.iter().enumerate() {
-        if part.is_empty() {
-            continue;
-        }
-        if i == 0 {
-            if !path[pos..].starts_with(part) {
-                return None;
-            }
-            pos += part.len();
-        } else {
-            match path[pos..].find(part) {
-                Some(idx) => pos += idx + part.len(),
-                None => return None,
-            }
+    let mut bots: Vec<&str> = Vec::new();
+    for bot in robots::SEARCH_BOTS
+        .iter()
+        .chain(AI_CITATION_BOTS)
+        .chain(AI_TRAINING_BOTS)
+    {
+        // "Common Crawl" is a label, not a product token.
+        let unique = !bots.iter().any(|b| b.eq_ignore_ascii_case(bot));
+        if unique && !bot.contains(' ') {
+            bots.push(bot);
         }
     }
 
-    // With `$`, the match must consume the whole path (unless the pattern ended
-    // with a `*`, in which case any suffix is allowed).
-    if anchored && !pat.ends_with('*') && pos != path.len() {
-        return None;
-    }
-
-    Some(pat.chars().filter(|&c| c != '*').count())
-}
-
-struct RobotsBlock {
-    agents: Vec<String>,
-    disallows: Vec<String>,
-    allows: Vec<String>,
-}
-
-fn parse_robots_blocks(content: &str) -> Vec<RobotsBlock> {
-    let mut blocks: Vec<RobotsBlock> = Vec::new();
-    let mut current_agents: Vec<String> = Vec::new();
-    let mut current_disallows: Vec<String> = Vec::new();
-    let mut current_allows: Vec<String> = Vec::new();
-    let mut in_block = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            if in_block {
-                blocks.push(RobotsBlock {
-                    agents: std::mem::take(&mut current_agents),
-                    disallows: std::mem::take(&mut current_disallows),
-                    allows: std::mem::take(&mut current_allows),
-                });
-                in_block = false;
-            }
+    let mut findings = Vec::new();
+    for bot in bots {
+        let blocked: Vec<&str> = routes
+            .iter()
+            .copied()
+            .filter(|route| !robots.is_allowed(bot, route))
+            .collect();
+        if blocked.is_empty() {
             continue;
         }
-
-        let lower = trimmed.to_lowercase();
-        if let Some(rest) = lower.strip_prefix("user-agent:") {
-            let agent_val = trimmed[trimmed.to_lowercase().find(':').unwrap() + 1..]
-                .trim()
-                .to_string();
-            if in_block && !current_disallows.is_empty() {
-                blocks.push(RobotsBlock {
-                    agents: std::mem::take(&mut current_agents),
-                    disallows: std::mem::take(&mut current_disallows),
-                    allows: std::mem::take(&mut current_allows),
-                });
-            }
-            let _ = rest;
-            current_agents.push(agent_val);
-            in_block = true;
-        } else if let Some(rest) = lower.strip_prefix("disallow:") {
-            let _ = rest;
-            let val = trimmed[trimmed.to_lowercase().find(':').unwrap() + 1..]
-                .trim()
-                .to_string();
-            current_disallows.push(val);
-        } else if let Some(rest) = lower.strip_prefix("allow:") {
-            let _ = rest;
-            let val = trimmed[trimmed.to_lowercase().find(':').unwrap() + 1..]
-                .trim()
-                .to_string();
-            current_allows.push(val);
+        let search = robots::SEARCH_BOTS.contains(&bot);
+        let group = match robots.specific_groups(bot) {
+            Some(groups) => format!("its group at line {}", groups[0].line),
+            None => "the User-agent: * group".to_string(),
+        };
+        let mut examples = blocked
+            .iter()
+            .take(COVERAGE_EXAMPLES)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if blocked.len() > COVERAGE_EXAMPLES {
+            examples.push_str(&format!(" (+{} more)", blocked.len() - COVERAGE_EXAMPLES));
         }
-    }
-
-    if in_block {
-        blocks.push(RobotsBlock {
-            agents: current_agents,
-            disallows: current_disallows,
-            allows: current_allows,
+        findings.push(Finding {
+            level: if search { Level::Warning } else { Level::Info },
+            rule_id: "robots-txt/bot-coverage".into(),
+            file: "robots.txt".into(),
+            selector: format!("User-agent: {bot}"),
+            message: format!(
+                "{bot} may fetch {} of {} indexable page(s) under {group}; blocked: {examples}",
+                routes.len() - blocked.len(),
+                routes.len()
+            ),
+            help: if search {
+                "Indexable pages blocked for a search engine drop out of its results. Allow them, or mark them noindex if they should not rank."
+            } else {
+                "Check this matches your AI crawler policy. Test a single URL with `astro-post-audit robots test <path> --ua <bot>`."
+            }
+            .into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
         });
     }
-
-    blocks
+    findings
 }
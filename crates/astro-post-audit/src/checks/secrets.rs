@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use base64::Engine;
+use rayon::prelude::*;
+use regex::bytes::Regex;
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::discovery::SiteIndex;
+use crate::report::{Finding, Level};
+
+/// Files scanned: everything the browser can download as text.
+const TEXT_EXTENSIONS: &[&str] = &[
+    "html",
+    "htm",
+    "js",
+    "mjs",
+    "cjs",
+    "css",
+    "json",
+    "map",
+    "txt",
+    "xml",
+    "svg",
+    "webmanifest",
+];
+
+/// Larger files are skipped; minified bundles stay well below this.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Minimum Shannon entropy (bits per character) for `generic-secret` values.
+const MIN_ENTROPY: f64 = 3.5;
+
+/// A credential pattern from the bundled ruleset.
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    level: Level,
+    /// With a capture group, only the group is the secret.
+    pattern: &'static str,
+    help: &'static str,
+}
+
+const BUNDLED: &[Rule] = &[
+    Rule {
+        id: "aws-access-key",
+        name: "AWS access key ID",
+        level: Level::Error,
+        pattern: r"\b((?:AKIA|ASIA)[0-9A-Z]{16})\b",
+        help: "Deactivate the key in IAM and create a new one. AWS calls belong in a server endpoint or an API route, never in client code.",
+    },
+    Rule {
+        id: "aws-secret-key",
+        name: "AWS secret access key",
+        level: Level::Error,
+        pattern: r#"(?i)aws.{0,20}?(?:secret|private).{0,20}?["'`]([A-Za-z0-9/+=]{40})["'`]"#,
+        help: "Deactivate the key pair in IAM and create a new one. AWS calls belong in a server endpoint or an API route, never in client code.",
+    },
+    Rule {
+        id: "stripe-secret-key",
+        name: "Stripe secret key",
+        level: Level::Error,
+        pattern: r"\b((?:sk|rk)_live_[0-9A-Za-z]{24,})\b",
+        help: "Roll the key in the Stripe dashboard. Only the publishable key (pk_live_…) may reach the browser; create charges and sessions server-side.",
+    },
+    Rule {
+        id: "github-token",
+        name: "GitHub token",
+        level: Level::Error,
+        pattern: r"\b(gh[pousr]_[A-Za-z0-9]{36,255}|github_pat_[A-Za-z0-9_]{82})\b",
+        help: "Revoke the token in GitHub settings. Fetch from GitHub at build time (in frontmatter) or from a server endpoint instead of the client.",
+    },
+    Rule {
+        id: "private-key",
+        name: "Private key block",
+        level: Level::Error,
+        pattern: r"-----BEGIN (?:RSA |EC |DSA |OPENSSH |PGP |ENCRYPTED )?PRIVATE KEY(?: BLOCK)?-----",
+        help: "Treat the key as compromised and replace it. Check public/ and any JSON imported into client code for key files.",
+    },
+    Rule {
+        id: "jwt",
+        name: "JSON Web Token",
+        level: Level::Warning,
+        pattern: r"\b(eyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,})",
+        help: "A token baked into the build is valid for everyone until it expires. Issue tokens at runtime; if it grants more than public access, revoke it.",
+    },
+    Rule {
+        id: "generic-secret",
+        name: "Secret assignment",
+        level: Level::Warning,
+        pattern: r#"(?i)(?:secret|api[_-]?key|access[_-]?token|auth[_-]?token|password|passwd|private[_-]?key)["'`]?\s*[:=]\s*["'`]([A-Za-z0-9_\-+/=.]{16,})["'`]"#,
+        help: "Random-looking value assigned to a secret-sounding name. If it is a credential, rotate it and keep it server-side; otherwise add it to `secrets.allow`.",
+    },
+];
+
+/// A compiled bundled or user-defined pattern.
+struct Pattern {
+    id: String,
+    name: String,
+    level: Level,
+    regex: Regex,
+    help: String,
+}
+
+/// First occurrence of a secret in a file.
+struct Hit {
+    pattern: usize,
+    offset: usize,
+    value: String,
+    count: usize,
+}
+
+/// Scan every text file in dist for credentials: the bundled ruleset (AWS
+/// keys, Stripe secret keys, GitHub tokens, private key blocks, JWTs and
+/// high-entropy values assigned to secret-sounding names) plus
+/// `secrets.patterns`. Anything `import.meta.env` inlined into a bundle or a
+/// source map ends up here. Each distinct value is reported once per file at
+/// its first byte offset, redacted.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.secrets;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    // User regexes were validated in Config::validate.
+    let mut patterns: Vec<Pattern> = BUNDLED
+        .iter()
+        .map(|rule| Pattern {
+            id: rule.id.into(),
+            name: rule.name.into(),
+            level: rule.level.clone(),
+            regex: Regex::new(rule.pattern).expect("bundled secret pattern"),
+            help: rule.help.into(),
+        })
+        .collect();
+    patterns.extend(cfg.patterns.iter().filter_map(|p| {
+        Some(Pattern {
+            id: p.id.clone(),
+            name: p.id.clone(),
+            level: Level::Error,
+            regex: Regex::new(&p.regex).ok()?,
+            help: "Matched a pattern from `secrets.patterns`. Rotate the credential and keep it out of client code.".into(),
+        })
+    }));
+    let allow: Vec<regex::Regex> = cfg
+        .allow
+        .iter()
+        .filter_map(|a| regex::Regex::new(a).ok())
+        .collect();
+
+    let files: Vec<PathBuf> = WalkDir::new(&index.dist_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.metadata().is_ok_and(|m| m.len() <= MAX_FILE_BYTES))
+        .filter(|e| {
+            e.path()
+                .extension()
+                .and_then(|x| x.to_str())
+                .is_some_and(|x| TEXT_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        })
+        .map(|e| e.into_path())
+        .collect();
+
+    let patterns = &patterns;
+    let mut findings: Vec<Finding> = files
+        .par_iter()
+        .flat_map_iter(|path| {
+            let rel = index.dist_rel_path(path);
+            let hits = std::fs::read(path)
+                .map(|bytes| scan(&bytes, patterns, &allow))
+                .unwrap_or_default();
+            let is_map = rel.ends_with(".map");
+            hits.into_iter().map(move |hit| {
+                let pattern = &patterns[hit.pattern];
+                let times = if hit.count > 1 {
+                    format!(" ({} occurrences)", hit.count)
+                } else {
+                    String::new()
+                };
+                let mut help = pattern.help.clone();
+                if is_map {
+                    help.push_str(" Source maps embed the original sources; disable `vite.build.sourcemap` for production or keep .map files off the server.");
+                }
+                Finding {
+                    level: pattern.level.clone(),
+                    rule_id: format!("secrets/{}", pattern.id),
+                    file: rel.clone(),
+                    selector: format!("byte {}", hit.offset),
+                    message: format!("{} in built output: {}{times}", pattern.name, hit.value),
+                    help,
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                }
+            })
+        })
+        .collect();
+    findings.sort_by(|a, b| a.file.cmp(&b.file));
+    findings
+}
+
+/// Run all patterns over one file. `generic-secret` matches overlapping a
+/// more specific match are dropped.
+fn scan(bytes: &[u8], patterns: &[Pattern], allow: &[regex::Regex]) -> Vec<Hit> {
+    let mut claimed: Vec<(usize, usize)> = Vec::new();
+    let mut hits: BTreeMap<(usize, Vec<u8>), Hit> = BTreeMap::new();
+
+    // Specific patterns first so they claim their ranges.
+    let order = (0..patterns.len())
+        .filter(|&i| patterns[i].id != "generic-secret")
+        .chain((0..patterns.len()).filter(|&i| patterns[i].id == "generic-secret"));
+    for i in order {
+        let pattern = &patterns[i];
+        let generic = pattern.id == "generic-secret";
+        for caps in pattern.regex.captures_iter(bytes) {
+            let Some(m) = caps.get(1).or_else(|| caps.get(0)) else {
+                continue;
+            };
+            let value = m.as_bytes();
+            if value.is_empty() {
+                continue;
+            }
+            if generic {
+                let overlaps = claimed.iter().any(|&(s, e)| m.start() < e && s < m.end());
+                if overlaps || !looks_random(value) {
+                    continue;
+                }
+            } else {
+                claimed.push((m.start(), m.end()));
+            }
+            let text = String::from_utf8_lossy(value);
+            if allow.iter().any(|a| a.is_match(&text)) {
+                continue;
+            }
+            if pattern.id == "jwt" && is_public_jwt(&text) {
+                continue;
+            }
+            hits.entry((i, value.to_vec()))
+                .and_modify(|h| h.count += 1)
+                .or_insert_with(|| Hit {
+                    pattern: i,
+                    offset: m.start(),
+                    value: if pattern.id == "private-key" {
+                        text.to_string()
+                    } else {
+                        redact(&text)
+                    },
+                    count: 1,
+                });
+        }
+    }
+
+    let mut hits: Vec<Hit> = hits.into_values().collect();
+    hits.sort_by_key(|h| h.offset);
+    hits
+}
+
+/// Keep the first four characters (often the key type, e.g. `AKIA`, `ghp_`)
+/// and the length.
+fn redact(value: &str) -> String {
+    let len = value.chars().count();
+    if len <= 8 {
+        return "*".repeat(len);
+    }
+    let prefix: String = value.chars().take(4).collect();
+    format!("{prefix}… ({len} chars, redacted)")
+}
+
+/// Mixed letters and digits with high per-character entropy: filters out
+/// identifiers and placeholders such as `your-api-key-here`.
+fn looks_random(value: &[u8]) -> bool {
+    value.iter().any(u8::is_ascii_digit)
+        && value.iter().any(u8::is_ascii_alphabetic)
+        && shannon_entropy(value) >= MIN_ENTROPY
+}
+
+fn shannon_entropy(value: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &b in value {
+        counts[b as usize] += 1;
+    }
+    let len = value.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Supabase-style anon keys are JWTs meant for the browser.
+fn is_public_jwt(token: &str) -> bool {
+    let Some(payload) = token.split('.').nth(1) else {
+        return false;
+    };
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()
+        .and_then(|json| serde_json::from_slice::<serde_json::Value>(&json).ok())
+        .and_then(|claims| claims.get("role")?.as_str().map(|r| r == "anon"))
+        .unwrap_or(false)
+}
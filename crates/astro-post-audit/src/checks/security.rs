@@ -2,7 +2,8 @@ use rayon::prelude::*;
 use scraper::Selector;
 
 use crate::config::Config;
-use crate::discovery::SiteIndex;
+use crate::discovery::crawl::HttpResponse;
+use crate::discovery::{PageInfo, SiteIndex};
 use crate::report::{Finding, Level};
 
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
@@ -66,16 +67,120 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
                 }
             }
 
+            // Response headers (crawl mode only)
+            if config.security.check_headers {
+                if let Some(http) = &page.http {
+                    check_response_headers(page, http, &html, &mut findings);
+                }
+            }
+
             findings
         })
         .collect()
 }
 
-fn check_mixed_content(
-    page: &crate::discovery::PageInfo,
+/// HSTS (one year, as required for preload lists).
+const HSTS_MIN_MAX_AGE: u64 = 31_536_000;
+
+/// Security headers a crawled page should be served with.
+fn check_response_headers(
+    page: &PageInfo,
+    http: &HttpResponse,
     html: &scraper::Html,
     findings: &mut Vec<Finding>,
 ) {
+    let finding = |level, rule_id: &str, message: String, help: &str, suggestion: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: page.rel_path.clone(),
+        selector: String::new(),
+        message,
+        help: help.into(),
+        suggestion: Some(suggestion.into()),
+        source_hint: None,
+        confidence: None,
+    };
+    let suggested_hsts = "Strict-Transport-Security: max-age=31536000; includeSubDomains";
+
+    // Browsers ignore HSTS received over plain HTTP (e.g. a local preview).
+    if http.url.starts_with("https://") {
+        match http.header("strict-transport-security") {
+            None => findings.push(finding(
+                Level::Warning,
+                "security/hsts-missing",
+                "Page is served over HTTPS without Strict-Transport-Security".into(),
+                "Without HSTS the first request of a visit can be downgraded to HTTP.",
+                suggested_hsts,
+            )),
+            Some(value) => {
+                let max_age = value.split(';').find_map(|d| {
+                    let (name, v) = d.split_once('=')?;
+                    name.trim()
+                        .eq_ignore_ascii_case("max-age")
+                        .then(|| v.trim().trim_matches('"').parse::<u64>().ok())
+                        .flatten()
+                });
+                if max_age.is_none_or(|age| age < HSTS_MIN_MAX_AGE) {
+                    findings.push(finding(
+                        Level::Warning,
+                        "security/hsts-weak",
+                        format!("Strict-Transport-Security '{value}' has a max-age below one year"),
+                        "A short or missing max-age lets HSTS expire between visits; max-age=0 turns it off.",
+                        suggested_hsts,
+                    ));
+                }
+            }
+        }
+    }
+
+    if !http
+        .header("x-content-type-options")
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("nosniff"))
+    {
+        findings.push(finding(
+            Level::Warning,
+            "security/nosniff-missing",
+            "Page is served without X-Content-Type-Options: nosniff".into(),
+            "Without nosniff browsers may guess content types, turning uploads or JSON into executable scripts.",
+            "X-Content-Type-Options: nosniff",
+        ));
+    }
+
+    if http.header("content-security-policy").is_none() {
+        let meta_sel = Selector::parse("meta[http-equiv]").unwrap();
+        let has_meta = html.select(&meta_sel).any(|el| {
+            el.value()
+                .attr("http-equiv")
+                .is_some_and(|v| v.eq_ignore_ascii_case("content-security-policy"))
+        });
+        let report_only = http.header("content-security-policy-report-only").is_some();
+        let (level, message) = if has_meta {
+            (
+                Level::Info,
+                "Content-Security-Policy is only set via <meta>; frame-ancestors, report-to and sandbox need the header",
+            )
+        } else if report_only {
+            (
+                Level::Warning,
+                "Only Content-Security-Policy-Report-Only is sent; nothing is enforced",
+            )
+        } else {
+            (
+                Level::Warning,
+                "Page is served without a Content-Security-Policy header",
+            )
+        };
+        findings.push(finding(
+            level,
+            "security/csp-header-missing",
+            message.into(),
+            "Send the policy as a response header from the host (_headers, netlify.toml, vercel.json or middleware).",
+            "Content-Security-Policy: default-src 'self'",
+        ));
+    }
+}
+
+fn check_mixed_content(page: &PageInfo, html: &scraper::Html, findings: &mut Vec<Finding>) {
     let selectors = [
         ("img[src]", "src"),
         ("script[src]", "src"),
@@ -0,0 +1,343 @@
+use url::Url;
+
+use crate::config::Config;
+use crate::datetime;
+use crate::discovery::SiteIndex;
+use crate::report::{Finding, Level};
+
+const WELL_KNOWN: &str = ".well-known/security.txt";
+const LEGACY: &str = "security.txt";
+
+/// RFC 9116 recommends an Expires less than a year ahead.
+const MAX_EXPIRES_SECS: i64 = 366 * 86_400;
+
+/// A `Field: value` line.
+struct Field {
+    line: usize,
+    name: String,
+    value: String,
+}
+
+/// Validate `/.well-known/security.txt` (RFC 9116): the file must exist,
+/// carry at least one `Contact` and exactly one `Expires` in the future, use
+/// https (or mailto/tel) URIs in its URI fields, list language tags in
+/// `Preferred-Languages`, and name its own URL under `site.base_url` in
+/// `Canonical`. PGP cleartext signatures are unwrapped before parsing.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.security_txt;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let mut findings = Vec::new();
+    let (rel_path, content) = match std::fs::read_to_string(index.dist_path.join(WELL_KNOWN)) {
+        Ok(content) => (WELL_KNOWN, content),
+        Err(_) => match std::fs::read_to_string(index.dist_path.join(LEGACY)) {
+            Ok(content) => {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "security-txt/legacy-location".into(),
+                    file: LEGACY.into(),
+                    selector: String::new(),
+                    message: "security.txt is served from the site root only".into(),
+                    help: "RFC 9116 places it at /.well-known/security.txt. Move it to public/.well-known/security.txt (the root copy may stay as a fallback).".into(),
+                    suggestion: Some(format!("public/{WELL_KNOWN}")),
+                    source_hint: None,
+                    confidence: None,
+                });
+                (LEGACY, content)
+            }
+            Err(_) => {
+                findings.push(Finding {
+                    level: Level::Error,
+                    rule_id: "security-txt/missing".into(),
+                    file: WELL_KNOWN.into(),
+                    selector: String::new(),
+                    message: "No /.well-known/security.txt in the build".into(),
+                    help: "Add public/.well-known/security.txt with at least `Contact:` and `Expires:` so researchers know where to report vulnerabilities (https://securitytxt.org).".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+                return findings;
+            }
+        },
+    };
+
+    let finding =
+        |level, rule_id: &str, line: Option<usize>, message: String, help: &str| Finding {
+            level,
+            rule_id: rule_id.into(),
+            file: rel_path.into(),
+            selector: line.map(|l| format!("line {l}")).unwrap_or_default(),
+            message,
+            help: help.into(),
+            suggestion: None,
+            source_hint: None,
+            confidence: None,
+        };
+    let invalid = |field: &Field, message: String, help: &str| {
+        finding(
+            Level::Error,
+            "security-txt/invalid-field",
+            Some(field.line),
+            message,
+            help,
+        )
+    };
+
+    let (fields, malformed) = parse(&content);
+    for line in malformed {
+        findings.push(finding(
+            Level::Error,
+            "security-txt/invalid-field",
+            Some(line),
+            format!("Line {line} is neither a comment nor a `Field: value` pair"),
+            "Every line is a comment (`#`), blank, or `Field-Name: value`.",
+        ));
+    }
+    let named = |name: &str| -> Vec<&Field> {
+        fields
+            .iter()
+            .filter(|f| f.name.eq_ignore_ascii_case(name))
+            .collect()
+    };
+
+    let contacts = named("Contact");
+    if contacts.is_empty() {
+        findings.push(finding(
+            Level::Error,
+            "security-txt/contact-missing",
+            None,
+            "security.txt has no Contact field".into(),
+            "Add `Contact: mailto:security@example.com` or an https:// reporting page.",
+        ));
+    }
+    for field in contacts {
+        if let Err(reason) = check_uri(&field.value, &["mailto", "tel"]) {
+            findings.push(invalid(
+                field,
+                format!("Contact '{}' {reason}", field.value),
+                "Contact takes a mailto:, tel: or https:// URI, e.g. `Contact: mailto:security@example.com`.",
+            ));
+        }
+    }
+
+    let expires = named("Expires");
+    match expires.as_slice() {
+        [] => findings.push(finding(
+            Level::Error,
+            "security-txt/expires-missing",
+            None,
+            "security.txt has no Expires field".into(),
+            "Add `Expires:` with an RFC 3339 timestamp less than a year ahead, e.g. `Expires: 2026-12-31T23:00:00.000Z`.",
+        )),
+        [field, rest @ ..] => {
+            for extra in rest {
+                findings.push(invalid(
+                    extra,
+                    "Expires appears more than once".into(),
+                    "Keep a single Expires field.",
+                ));
+            }
+            let parsed = field
+                .value
+                .contains(['T', 't'])
+                .then(|| datetime::parse_w3c(&field.value))
+                .flatten();
+            match parsed {
+                None => findings.push(invalid(
+                    field,
+                    format!("Expires '{}' is not an RFC 3339 date-time", field.value),
+                    "Use a full timestamp with a time zone, e.g. `2026-12-31T23:00:00.000Z`.",
+                )),
+                Some(ts) => {
+                    let now = datetime::now();
+                    let warn_secs = i64::from(cfg.expires_warn_days) * 86_400;
+                    if ts <= now {
+                        findings.push(finding(
+                            Level::Error,
+                            "security-txt/expired",
+                            Some(field.line),
+                            format!("security.txt expired on {}", field.value),
+                            "Researchers treat an expired file as stale and may not trust its contacts. Review it and set a new Expires date.",
+                        ));
+                    } else if ts - now <= warn_secs {
+                        findings.push(finding(
+                            Level::Warning,
+                            "security-txt/expires-soon",
+                            Some(field.line),
+                            format!(
+                                "security.txt expires in {} day(s) ({})",
+                                (ts - now) / 86_400,
+                                field.value
+                            ),
+                            "Review the contacts and move Expires forward before it lapses.",
+                        ));
+                    } else if ts - now > MAX_EXPIRES_SECS {
+                        findings.push(finding(
+                            Level::Info,
+                            "security-txt/expires-too-far",
+                            Some(field.line),
+                            format!("Expires {} is more than a year ahead", field.value),
+                            "RFC 9116 recommends less than a year so the file is reviewed regularly.",
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    for (name, schemes) in [
+        ("Encryption", &["dns", "openpgp4fpr"][..]),
+        ("Policy", &[][..]),
+        ("Acknowledgments", &[][..]),
+        ("Hiring", &[][..]),
+        ("CSAF", &[][..]),
+    ] {
+        for field in named(name) {
+            if let Err(reason) = check_uri(&field.value, schemes) {
+                findings.push(invalid(
+                    field,
+                    format!("{name} '{}' {reason}", field.value),
+                    "This field takes an https:// URI.",
+                ));
+            }
+        }
+    }
+
+    let languages = named("Preferred-Languages");
+    for extra in languages.iter().skip(1) {
+        findings.push(invalid(
+            extra,
+            "Preferred-Languages appears more than once".into(),
+            "List all languages in a single comma-separated field.",
+        ));
+    }
+    if let Some(field) = languages.first() {
+        let bad: Vec<&str> = field
+            .value
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !is_language_tag(tag))
+            .collect();
+        if !bad.is_empty() {
+            findings.push(invalid(
+                field,
+                format!(
+                    "Preferred-Languages has invalid language tag(s): {}",
+                    bad.join(", ")
+                ),
+                "Use comma-separated language tags, e.g. `Preferred-Languages: en, de`.",
+            ));
+        }
+    }
+
+    let canonicals = named("Canonical");
+    let mut canonical_urls = Vec::new();
+    for field in &canonicals {
+        match check_uri(&field.value, &[]) {
+            Ok(()) => canonical_urls.push(field.value.as_str()),
+            Err(reason) => findings.push(invalid(
+                field,
+                format!("Canonical '{}' {reason}", field.value),
+                "Canonical is the https:// URL the file is served from.",
+            )),
+        }
+    }
+    if let (Some(base), Some(first)) = (index.base_url.as_deref(), canonicals.first()) {
+        let expected = Url::parse(base)
+            .ok()
+            .and_then(|b| b.join(&format!("/{WELL_KNOWN}")).ok());
+        if let Some(expected) = expected {
+            let matches = canonical_urls
+                .iter()
+                .filter_map(|c| Url::parse(c).ok())
+                .any(|c| c.origin() == expected.origin() && c.path() == expected.path());
+            if !matches && !canonical_urls.is_empty() {
+                findings.push(Finding {
+                    suggestion: Some(format!("Canonical: {expected}")),
+                    ..finding(
+                        Level::Error,
+                        "security-txt/canonical-mismatch",
+                        Some(first.line),
+                        format!(
+                            "Canonical ({}) does not include {expected}",
+                            canonical_urls.join(", ")
+                        ),
+                        "Canonical must name the URL this file is served from. A copied file from another site or a staging domain points reporters elsewhere.",
+                    )
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Split into fields, unwrapping a PGP cleartext signature. Returns the fields
+/// and the line numbers that are not `Field: value`.
+fn parse(content: &str) -> (Vec<Field>, Vec<usize>) {
+    let mut fields = Vec::new();
+    let mut malformed = Vec::new();
+    let signed = content
+        .lines()
+        .any(|l| l.trim() == "-----BEGIN PGP SIGNED MESSAGE-----");
+    // Skip the armor header (up to the first blank line) of a signed file.
+    let mut in_header = false;
+    for (i, raw) in content.lines().enumerate() {
+        let mut line = raw.trim();
+        if signed {
+            if line == "-----BEGIN PGP SIGNED MESSAGE-----" {
+                in_header = true;
+                continue;
+            }
+            if in_header {
+                in_header = !line.is_empty();
+                continue;
+            }
+            if line == "-----BEGIN PGP SIGNATURE-----" {
+                break;
+            }
+            line = line.strip_prefix("- ").unwrap_or(line);
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(':') {
+            Some((name, value))
+                if !name.is_empty()
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+            {
+                fields.push(Field {
+                    line: i + 1,
+                    name: name.to_string(),
+                    value: value.trim().to_string(),
+                });
+            }
+            _ => malformed.push(i + 1),
+        }
+    }
+    (fields, malformed)
+}
+
+/// An absolute URI whose scheme is https or one of `extra_schemes`.
+fn check_uri(value: &str, extra_schemes: &[&str]) -> Result<(), &'static str> {
+    let url = Url::parse(value).map_err(|_| "is not an absolute URI")?;
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" => Err("uses http://; web URIs must use https://"),
+        scheme if extra_schemes.contains(&scheme) => Ok(()),
+        _ => Err("uses an unsupported scheme"),
+    }
+}
+
+/// A BCP 47 tag in its common shape: a 2–8 letter primary subtag followed by
+/// 1–8 character alphanumeric subtags (`en`, `de-AT`, `zh-Hant-TW`).
+fn is_language_tag(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or("");
+    (2..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| (1..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
@@ -15,6 +15,11 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         .flat_map(|page| {
             let mut findings = Vec::new();
 
+            // Error pages have their own rules (checks/error_pages.rs).
+            if page.is_error_page() {
+                return findings;
+            }
+
             // Canonical checks
             if config.canonical.require {
                 check_canonical(page, index, config, &mut findings);
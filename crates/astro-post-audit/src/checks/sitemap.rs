@@ -1,6 +1,12 @@
+use std::collections::BTreeSet;
+
+use rayon::prelude::*;
+use scraper::Selector;
 use url::Url;
 
 use crate::config::{Config, UrlNormalizationConfig};
+use crate::datetime;
+use crate::discovery::sitemap::{SitemapEntry, SitemapKind, MAX_ENTRIES_PER_FILE, MAX_FILE_BYTES};
 use crate::discovery::SiteIndex;
 use crate::normalize;
 use crate::report::{Finding, Level};
@@ -21,16 +27,17 @@ fn normalize_url(url_str: &str, norm_config: &UrlNormalizationConfig) -> String
 pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     let mut findings = Vec::new();
 
-    // Check if sitemap exists
-    let sitemap_path = index.dist_path.join("sitemap.xml");
-    if !sitemap_path.exists() {
+    let sitemaps = &index.sitemaps;
+
+    // Check if any sitemap exists
+    if sitemaps.files.is_empty() && sitemaps.missing.is_empty() {
         if config.sitemap.require {
             findings.push(Finding {
                 level: Level::Error,
                 rule_id: "sitemap/missing".into(),
                 file: "sitemap.xml".into(),
                 selector: String::new(),
-                message: "sitemap.xml not found in dist directory".into(),
+                message: "No sitemap found in dist directory (sitemap-index.xml, sitemap.xml or a robots.txt Sitemap: line)".into(),
                 help: "Add a sitemap integration to astro.config.mjs (e.g. `@casoon/astro-sitemap` or `@astrojs/sitemap`) and ensure `site` is set".into(),
                 suggestion: None,
                 source_hint: None,
@@ -40,24 +47,105 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         return findings;
     }
 
-    if let Some(parse_error) = &index.sitemap_parse_error {
+    for (parent, rel) in &sitemaps.missing {
         findings.push(Finding {
-            level: if config.sitemap.require {
-                Level::Error
+            level: Level::Error,
+            rule_id: "sitemap/referenced-file-missing".into(),
+            file: parent.clone(),
+            selector: if parent == "robots.txt" {
+                format!("Sitemap: {}", rel)
             } else {
-                Level::Warning
+                format!("<sitemap><loc>{}</loc>", rel)
             },
-            rule_id: "sitemap/parse-error".into(),
-            file: "sitemap.xml".into(),
-            selector: String::new(),
-            message: format!("Could not parse sitemap.xml: {}", parse_error),
-            help: "Fix sitemap.xml syntax and regenerate the file".into(),
+            message: format!("'{}' references sitemap '{}', which is not in dist", parent, rel),
+            help: "Regenerate the sitemap, or fix the reference so it points at a file the build emits".into(),
             suggestion: None,
             source_hint: None,
             confidence: None,
         });
     }
 
+    for file in &sitemaps.files {
+        if let Some(parse_error) = &file.parse_error {
+            findings.push(Finding {
+                level: if config.sitemap.require {
+                    Level::Error
+                } else {
+                    Level::Warning
+                },
+                rule_id: "sitemap/parse-error".into(),
+                file: file.rel_path.clone(),
+                selector: String::new(),
+                message: format!("Could not parse {}: {}", file.rel_path, parse_error),
+                help: "Fix the sitemap syntax and regenerate the file".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+
+        if file.size > MAX_FILE_BYTES {
+            findings.push(Finding {
+                level: Level::Error,
+                rule_id: "sitemap/too-large".into(),
+                file: file.rel_path.clone(),
+                selector: String::new(),
+                message: format!(
+                    "{} exceeds the 50 MB uncompressed sitemap limit; it was not parsed",
+                    file.rel_path
+                ),
+                help: "Split the sitemap into several files listed in a sitemap index (`@astrojs/sitemap` does this via `entryLimit`)".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+
+        if file.entry_count > MAX_ENTRIES_PER_FILE {
+            findings.push(Finding {
+                level: Level::Error,
+                rule_id: "sitemap/too-many-urls".into(),
+                file: file.rel_path.clone(),
+                selector: String::new(),
+                message: format!(
+                    "{} has {} entries; the sitemap protocol allows {} per file",
+                    file.rel_path, file.entry_count, MAX_ENTRIES_PER_FILE
+                ),
+                help: "Split the sitemap into several files listed in a sitemap index (`@astrojs/sitemap` does this via `entryLimit`)".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            });
+        }
+
+        // An index may only list urlsets.
+        if file.kind == Some(SitemapKind::Index) {
+            let parent_is_index = file.parent.as_ref().is_some_and(|parent| {
+                sitemaps
+                    .files
+                    .iter()
+                    .any(|f| &f.rel_path == parent && f.kind == Some(SitemapKind::Index))
+            });
+            if parent_is_index {
+                findings.push(Finding {
+                    level: Level::Error,
+                    rule_id: "sitemap/nested-index".into(),
+                    file: file.parent.clone().unwrap_or_default(),
+                    selector: format!("<sitemap><loc>{}</loc>", file.rel_path),
+                    message: format!(
+                        "Sitemap index '{}' lists another sitemap index '{}'",
+                        file.parent.as_deref().unwrap_or_default(),
+                        file.rel_path
+                    ),
+                    help: "Search engines do not follow nested sitemap indexes. List the child sitemaps directly in the top-level index".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+    }
+
     if index.sitemap_urls.is_empty() {
         return findings;
     }
@@ -74,8 +162,8 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     // Check: canonical URLs should be in sitemap
     if config.sitemap.canonical_must_be_in_sitemap {
         for page in &index.pages {
-            if page.noindex {
-                continue; // noindex pages shouldn't be in sitemap
+            if page.noindex || page.is_error_page() {
+                continue; // noindex and error pages shouldn't be in sitemap
             }
             if let Some(ref canonical) = page.canonical {
                 let norm_canonical = normalize_url(canonical, norm);
@@ -87,7 +175,7 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
                         file: page.rel_path.clone(),
                         selector: format!("link[rel='canonical'][href='{}']", canonical),
                         message: format!(
-                            "Canonical URL '{}' is not listed in sitemap.xml",
+                            "Canonical URL '{}' is not listed in the sitemap",
                             canonical
                         ),
                         help: "Add this URL to your sitemap or check the canonical".into(),
@@ -109,7 +197,7 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
                     findings.push(Finding {
                         level: Level::Warning,
                         rule_id: "sitemap/entry-not-in-dist".into(),
-                        file: "sitemap.xml".into(),
+                        file: index.sitemap_source(url_str).into(),
                         selector: format!("<loc>{}</loc>", url_str),
                         message: format!(
                             "Sitemap entry '{}' (route '{}') not found in dist",
@@ -141,7 +229,7 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
                             findings.push(Finding {
                                 level: Level::Warning,
                                 rule_id: "sitemap/non-canonical-entry".into(),
-                                file: "sitemap.xml".into(),
+                                file: index.sitemap_source(url_str).into(),
                                 selector: format!("<loc>{}</loc>", url_str),
                                 message: format!(
                                     "Sitemap contains '{}' but page canonical is '{}'",
@@ -159,5 +247,260 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
         }
     }
 
+    if config.sitemap.check_entry_metadata {
+        findings.extend(check_entry_metadata(index));
+    }
+    if config.sitemap.check_alternates {
+        findings.extend(check_alternates(index, norm));
+    }
+    if config.sitemap.check_media {
+        findings.extend(check_media(index));
+    }
+
+    findings
+}
+
+const CHANGEFREQ_VALUES: &[&str] = &[
+    "always", "hourly", "daily", "weekly", "monthly", "yearly", "never",
+];
+
+/// A lastmod this far ahead is treated as clock skew / timezone rounding.
+const FUTURE_LASTMOD_TOLERANCE_SECS: i64 = 86_400;
+
+/// `lastmod`, `changefreq` and `priority` values per the sitemap protocol.
+fn check_entry_metadata(index: &SiteIndex) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let now = datetime::now();
+    let finding = |entry: &SitemapEntry, rule_id: &str, message: String, help: &str| Finding {
+        level: Level::Warning,
+        rule_id: rule_id.into(),
+        file: entry.source.clone(),
+        selector: format!("<loc>{}</loc>", entry.loc),
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    for entry in &index.sitemaps.entries {
+        if let Some(lastmod) = &entry.lastmod {
+            match datetime::parse_w3c(lastmod) {
+                None => findings.push(finding(
+                    entry,
+                    "sitemap/invalid-lastmod",
+                    format!("Sitemap entry '{}' has invalid lastmod '{}'", entry.loc, lastmod),
+                    "Use a W3C datetime: YYYY-MM-DD or YYYY-MM-DDThh:mm:ss+00:00. Search engines ignore unparseable values.",
+                )),
+                Some(ts) if ts > now + FUTURE_LASTMOD_TOLERANCE_SECS => findings.push(finding(
+                    entry,
+                    "sitemap/future-lastmod",
+                    format!("Sitemap entry '{}' has lastmod '{}' in the future", entry.loc, lastmod),
+                    "lastmod should be the date the page last changed. Future dates make search engines distrust the sitemap's lastmod values.",
+                )),
+                Some(_) => {}
+            }
+        }
+        if let Some(changefreq) = &entry.changefreq {
+            if !CHANGEFREQ_VALUES.contains(&changefreq.as_str()) {
+                findings.push(finding(
+                    entry,
+                    "sitemap/invalid-changefreq",
+                    format!(
+                        "Sitemap entry '{}' has invalid changefreq '{}'",
+                        entry.loc, changefreq
+                    ),
+                    "Use one of: always, hourly, daily, weekly, monthly, yearly, never (lowercase).",
+                ));
+            }
+        }
+        if let Some(priority) = &entry.priority {
+            let valid = priority
+                .parse::<f64>()
+                .is_ok_and(|p| (0.0..=1.0).contains(&p));
+            if !valid {
+                findings.push(finding(
+                    entry,
+                    "sitemap/invalid-priority",
+                    format!(
+                        "Sitemap entry '{}' has invalid priority '{}'",
+                        entry.loc, priority
+                    ),
+                    "priority must be a number between 0.0 and 1.0.",
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+/// Cross-check `xhtml:link` alternates against the page's HTML hreflang
+/// links. Only runs when the sitemap uses alternates at all
+/// (`@astrojs/sitemap` with `i18n`); then an entry without them counts too.
+fn check_alternates(index: &SiteIndex, norm: &UrlNormalizationConfig) -> Vec<Finding> {
+    let entries = &index.sitemaps.entries;
+    if !entries.iter().any(|e| !e.alternates.is_empty()) {
+        return Vec::new();
+    }
+    let hreflang_sel = Selector::parse("link[rel~='alternate'][hreflang][href]").unwrap();
+
+    entries
+        .par_iter()
+        .filter_map(|entry| {
+            let parsed = Url::parse(&entry.loc).ok()?;
+            let route = normalize::normalize_path(parsed.path(), norm);
+            let page = &index.pages[*index.route_to_index.get(&route)?];
+
+            let in_sitemap: BTreeSet<(String, String)> = entry
+                .alternates
+                .iter()
+                .map(|(lang, href)| (lang.to_ascii_lowercase(), normalize_url(href, norm)))
+                .collect();
+            let html = page.parse_html();
+            let in_html: BTreeSet<(String, String)> = html
+                .select(&hreflang_sel)
+                .filter_map(|el| {
+                    let lang = el.value().attr("hreflang")?.trim().to_ascii_lowercase();
+                    let href = parsed.join(el.value().attr("href")?.trim()).ok()?;
+                    Some((lang, normalize_url(href.as_str(), norm)))
+                })
+                .collect();
+            if in_sitemap == in_html {
+                return None;
+            }
+
+            let describe = |set: Vec<&(String, String)>| {
+                if set.is_empty() {
+                    "none".to_string()
+                } else {
+                    set.iter()
+                        .map(|(lang, href)| format!("{lang} → {href}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+            };
+            let only_sitemap = describe(in_sitemap.difference(&in_html).collect());
+            let only_html = describe(in_html.difference(&in_sitemap).collect());
+            Some(Finding {
+                level: Level::Warning,
+                rule_id: "sitemap/hreflang-mismatch".into(),
+                file: entry.source.clone(),
+                selector: format!("<loc>{}</loc>", entry.loc),
+                message: format!(
+                    "Sitemap alternates for '{}' differ from the hreflang links in {} (only in sitemap: {}; only in HTML: {})",
+                    entry.loc, page.rel_path, only_sitemap, only_html
+                ),
+                help: "Search engines combine both sources; contradicting sets make them drop the hreflang cluster. Generate both from the same i18n config.".into(),
+                suggestion: None,
+                source_hint: None,
+                confidence: None,
+            })
+        })
+        .collect()
+}
+
+/// `image:loc` and `video:` URLs on the site's own host must exist in dist;
+/// video entries need the members Google requires.
+fn check_media(index: &SiteIndex) -> Vec<Finding> {
+    let own_host = index
+        .base_url
+        .as_deref()
+        .and_then(|b| Url::parse(b).ok())
+        .and_then(|u| u.host_str().map(str::to_string));
+    let missing_locally = |url: &str| -> bool {
+        let path = match Url::parse(url) {
+            Ok(parsed) => {
+                let same_host = match (&own_host, parsed.host_str()) {
+                    (Some(own), Some(host)) => own.eq_ignore_ascii_case(host),
+                    _ => false,
+                };
+                if !same_host {
+                    return false;
+                }
+                parsed.path().to_string()
+            }
+            Err(_) => url.to_string(),
+        };
+        index
+            .resolve_local_asset(&path, "")
+            .is_some_and(|p| !p.is_file())
+    };
+
+    let mut findings = Vec::new();
+    for entry in &index.sitemaps.entries {
+        for image in &entry.images {
+            if missing_locally(image) {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "sitemap/image-missing".into(),
+                    file: entry.source.clone(),
+                    selector: format!("<image:loc>{}</image:loc>", image),
+                    message: format!(
+                        "Sitemap image '{}' (listed for '{}') is not in dist",
+                        image, entry.loc
+                    ),
+                    help: "Image sitemap entries must point at files the build emits. Astro renames optimized images into _astro/ with a hash, so generate these URLs from the imported asset.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+        for video in &entry.videos {
+            for url in [&video.thumbnail_loc, &video.content_loc]
+                .into_iter()
+                .flatten()
+            {
+                if missing_locally(url) {
+                    findings.push(Finding {
+                        level: Level::Warning,
+                        rule_id: "sitemap/video-missing".into(),
+                        file: entry.source.clone(),
+                        selector: format!("<loc>{}</loc>", entry.loc),
+                        message: format!(
+                            "Sitemap video file '{}' (listed for '{}') is not in dist",
+                            url, entry.loc
+                        ),
+                        help: "Video sitemap entries must point at files the build emits.".into(),
+                        suggestion: None,
+                        source_hint: None,
+                        confidence: None,
+                    });
+                }
+            }
+            let mut absent = Vec::new();
+            if video.thumbnail_loc.is_none() {
+                absent.push("thumbnail_loc");
+            }
+            if video.title.is_none() {
+                absent.push("title");
+            }
+            if video.description.is_none() {
+                absent.push("description");
+            }
+            if video.content_loc.is_none() && video.player_loc.is_none() {
+                absent.push("content_loc or player_loc");
+            }
+            if !absent.is_empty() {
+                findings.push(Finding {
+                    level: Level::Warning,
+                    rule_id: "sitemap/video-incomplete".into(),
+                    file: entry.source.clone(),
+                    selector: format!("<loc>{}</loc>", entry.loc),
+                    message: format!(
+                        "Sitemap video for '{}' is missing {}",
+                        entry.loc,
+                        absent.join(", ")
+                    ),
+                    help: "Google ignores video entries without thumbnail_loc, title, description and a content_loc or player_loc.".into(),
+                    suggestion: None,
+                    source_hint: None,
+                    confidence: None,
+                });
+            }
+        }
+    }
+
     findings
 }
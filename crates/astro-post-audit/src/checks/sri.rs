@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use rayon::prelude::*;
+use scraper::Selector;
+use url::Url;
+
+use crate::config::Config;
+use crate::csp::digest_source;
+use crate::discovery::SiteIndex;
+use crate::normalize;
+use crate::report::{Confidence, Finding, Level};
+
+/// Hash algorithms allowed in `integrity`, weakest first. Browsers only
+/// check the strongest one present.
+const ALGORITHMS: &[&str] = &["sha256", "sha384", "sha512"];
+
+/// A script or stylesheet reference that can carry `integrity`.
+struct Reference {
+    page: String,
+    selector: String,
+    url: String,
+    integrity: Option<String>,
+    crossorigin: bool,
+    /// Resolved file in dist, for local references.
+    local: Option<PathBuf>,
+}
+
+/// Verify Subresource Integrity: `integrity` values on scripts, stylesheets
+/// and preloads must be well-formed, match the local file's digest (a rebuilt
+/// `_astro/` chunk keeps its old hash otherwise and the browser refuses to
+/// run it), and come with `crossorigin` on cross-origin URLs. Optionally
+/// reports the correct value for every local asset.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.sri;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let base = index.base_url.as_deref();
+    let sel = Selector::parse(
+        "script[src], link[rel~='stylesheet'][href], link[rel~='modulepreload'][href], link[rel~='preload'][href]",
+    )
+    .unwrap();
+
+    let mut references: Vec<Reference> = index
+        .pages
+        .par_iter()
+        .flat_map_iter(|page| {
+            let html = page.parse_html();
+            html.select(&sel)
+                .filter_map(|el| {
+                    let el = el.value();
+                    let (attr, url) = match el.attr("src") {
+                        Some(src) => ("src", src),
+                        None => ("href", el.attr("href")?),
+                    };
+                    if el.name() == "link"
+                        && el.attr("rel").is_some_and(|r| {
+                            r.split_whitespace()
+                                .any(|t| t.eq_ignore_ascii_case("preload"))
+                        })
+                        && !matches!(el.attr("as"), Some("script" | "style"))
+                    {
+                        return None;
+                    }
+                    let local = if normalize::is_internal(url, base) {
+                        let path =
+                            Url::parse(url).map_or(url.to_string(), |u| u.path().to_string());
+                        index.resolve_local_asset(&path, &page.rel_path)
+                    } else {
+                        None
+                    };
+                    Some(Reference {
+                        page: page.rel_path.clone(),
+                        selector: format!("{}[{attr}='{url}']", el.name()),
+                        url: url.to_string(),
+                        integrity: el.attr("integrity").map(str::to_string),
+                        crossorigin: el.attr("crossorigin").is_some(),
+                        local,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    references.sort_by(|a, b| (&a.page, &a.selector).cmp(&(&b.page, &b.selector)));
+
+    // File contents, read once per asset.
+    let mut contents: HashMap<PathBuf, Option<Vec<u8>>> = HashMap::new();
+    for path in references.iter().filter_map(|r| r.local.as_ref()) {
+        contents
+            .entry(path.clone())
+            .or_insert_with(|| std::fs::read(path).ok());
+    }
+
+    let mut findings = Vec::new();
+    let finding = |r: &Reference, level, rule_id: &str, message: String, help: &str| Finding {
+        level,
+        rule_id: rule_id.into(),
+        file: r.page.clone(),
+        selector: r.selector.clone(),
+        message,
+        help: help.into(),
+        suggestion: None,
+        source_hint: None,
+        confidence: None,
+    };
+
+    for r in &references {
+        let Some(integrity) = r.integrity.as_deref() else {
+            continue;
+        };
+        let (valid, invalid) = parse_integrity(integrity);
+
+        if !invalid.is_empty() {
+            findings.push(finding(
+                r,
+                Level::Warning,
+                "sri/invalid-syntax",
+                if valid.is_empty() {
+                    format!(
+                        "integrity=\"{integrity}\" on '{}' has no valid hash, so the browser ignores it",
+                        r.url
+                    )
+                } else {
+                    format!(
+                        "integrity on '{}' contains invalid metadata: {}",
+                        r.url,
+                        invalid.join(", ")
+                    )
+                },
+                "Use `sha256-`, `sha384-` or `sha512-` followed by the base64 digest, separated by spaces.",
+            ));
+        }
+
+        let cross_origin = !normalize::is_internal(&r.url, index.base_url.as_deref());
+        if cross_origin && !r.crossorigin && !valid.is_empty() {
+            findings.push(Finding {
+                suggestion: Some("crossorigin=\"anonymous\"".into()),
+                ..finding(
+                    r,
+                    Level::Error,
+                    "sri/missing-crossorigin",
+                    format!(
+                        "'{}' has integrity but no crossorigin attribute; the browser cannot verify it and blocks the resource",
+                        r.url
+                    ),
+                    "Cross-origin resources with integrity must be requested with CORS: add crossorigin=\"anonymous\".",
+                )
+            });
+        }
+
+        let Some(bytes) = r.local.as_ref().and_then(|p| contents[p].as_ref()) else {
+            continue;
+        };
+        // Only the strongest algorithm present is checked.
+        let Some(strongest) = ALGORITHMS
+            .iter()
+            .rev()
+            .find(|alg| valid.iter().any(|(a, _)| a == *alg))
+        else {
+            continue;
+        };
+        let expected = digest_source(strongest, bytes);
+        let matches = valid
+            .iter()
+            .filter(|(a, _)| a == strongest)
+            .any(|(a, digest)| format!("{a}-{digest}") == expected);
+        if !matches {
+            findings.push(Finding {
+                suggestion: Some(format!("integrity=\"{expected}\"")),
+                ..finding(
+                    r,
+                    Level::Error,
+                    "sri/mismatch",
+                    format!(
+                        "integrity on '{}' does not match the file in dist; the browser will refuse to load it",
+                        r.url
+                    ),
+                    "The asset was rebuilt after the hash was written. Regenerate integrity values as part of the build.",
+                )
+            });
+        }
+    }
+
+    if cfg.report_hashes {
+        let alg = cfg.report_algorithm.to_ascii_lowercase();
+        let mut pages_per_asset: BTreeMap<String, (usize, &PathBuf)> = BTreeMap::new();
+        for r in &references {
+            if let Some(path) = r.local.as_ref().filter(|p| contents[*p].is_some()) {
+                pages_per_asset
+                    .entry(index.dist_rel_path(path))
+                    .or_insert((0, path))
+                    .0 += 1;
+            }
+        }
+        for (rel_path, (count, path)) in pages_per_asset {
+            let Some(bytes) = contents[path].as_ref() else {
+                continue;
+            };
+            let value = digest_source(&alg, bytes);
+            findings.push(Finding {
+                level: Level::Info,
+                rule_id: "sri/report".into(),
+                file: rel_path.clone(),
+                selector: String::new(),
+                message: format!("{rel_path}: {value} (referenced {count} time(s))"),
+                help: "Correct integrity value for this asset as built.".into(),
+                suggestion: Some(format!("integrity=\"{value}\"")),
+                source_hint: None,
+                confidence: Some(Confidence::Medium),
+            });
+        }
+    }
+
+    findings
+}
+
+/// Split integrity metadata into valid `(algorithm, base64 digest)` pairs and
+/// invalid tokens. Options after `?` are ignored, as in browsers.
+fn parse_integrity(value: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for token in value.split_whitespace() {
+        let expression = token.split('?').next().unwrap_or(token);
+        let parsed = expression.split_once('-').and_then(|(alg, digest)| {
+            let alg = alg.to_ascii_lowercase();
+            let expected_len = match alg.as_str() {
+                "sha256" => 44,
+                "sha384" => 64,
+                "sha512" => 88,
+                _ => return None,
+            };
+            let chars_ok = digest.bytes().all(|b| {
+                b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_')
+            });
+            (chars_ok && digest.len() == expected_len).then(|| (alg, digest.to_string()))
+        });
+        match parsed {
+            Some(pair) => valid.push(pair),
+            None => invalid.push(format!("'{token}'")),
+        }
+    }
+    (valid, invalid)
+}
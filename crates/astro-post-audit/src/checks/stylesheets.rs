@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use scraper::Selector;
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::css;
+use crate::discovery::SiteIndex;
+use crate::report::{Finding, Level};
+
+/// A stylesheet to audit: a local `.css` file or an inline `<style>` block.
+struct Source {
+    /// `Finding.file`: the stylesheet path, or the first page carrying the block.
+    file: String,
+    /// Path that relative `url()`s resolve against (dist-relative).
+    base_rel: String,
+    content: String,
+    /// For inline blocks: how many pages carry the identical block.
+    inline_pages: Option<usize>,
+}
+
+/// Audit the contents of stylesheets: `url()` references to missing files,
+/// `@import` chains that serialize CSS loading, `@font-face` rules without
+/// `font-display`, and `http://` URLs that break on HTTPS. `assets` only checks
+/// that `<link rel=stylesheet>` targets exist; this looks inside them.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    let cfg = &config.stylesheets;
+    if !cfg.enabled {
+        return Vec::new();
+    }
+
+    let mut sources = local_css_files(index);
+    sources.extend(inline_style_blocks(index));
+
+    // Local imports per stylesheet file, for chain depth.
+    let import_graph: HashMap<PathBuf, Vec<PathBuf>> = sources
+        .iter()
+        .filter(|s| s.inline_pages.is_none())
+        .map(|s| {
+            let targets = css::imports(&s.content)
+                .iter()
+                .filter_map(|url| index.resolve_local_asset(url, &s.base_rel))
+                .collect();
+            (index.dist_path.join(&s.file), targets)
+        })
+        .collect();
+
+    sources
+        .par_iter()
+        .flat_map(|source| {
+            let mut findings = Vec::new();
+            let stripped = css::strip_comments(&source.content);
+            let where_ = match source.inline_pages {
+                Some(n) if n > 1 => format!("inline <style> (same block on {n} pages)"),
+                Some(_) => "inline <style>".to_string(),
+                None => format!("'{}'", source.file),
+            };
+            let selector = if source.inline_pages.is_some() {
+                "style"
+            } else {
+                "stylesheet"
+            };
+
+            let mut seen_urls = HashSet::new();
+            for url in css::url_refs(&stripped) {
+                if !seen_urls.insert(url.clone()) {
+                    continue;
+                }
+                if cfg.check_mixed_content && url.starts_with("http://") {
+                    findings.push(Finding {
+                        level: Level::Warning,
+                        rule_id: "css/mixed-content".into(),
+                        file: source.file.clone(),
+                        selector: format!("{selector} url({url})"),
+                        message: format!("HTTP resource in {where_}: 'url({url})'"),
+                        help: "Browsers block or warn about insecure subresources on HTTPS pages. Use an https:// URL or a local asset.".into(),
+                        suggestion: Some(url.replacen("http://", "https://", 1)),
+                        source_hint: None,
+                        confidence: None,
+                    });
+                }
+                if cfg.check_urls {
+                    if let Some(path) = index.resolve_local_asset(&url, &source.base_rel) {
+                        if !path.exists() {
+                            findings.push(Finding {
+                                level: Level::Error,
+                                rule_id: "css/broken-url".into(),
+                                file: source.file.clone(),
+                                selector: format!("{selector} url({url})"),
+                                message: format!("Broken url() reference in {where_}: '{url}'"),
+                                help: "Fix the path or add the missing file. Files in public/ are copied to the dist root; assets imported in components land in _astro/.".into(),
+                                suggestion: None,
+                                source_hint: None,
+                                confidence: None,
+                            });
+                        }
+                    }
+                }
+            }
+
+            if cfg.check_imports {
+                for url in css::imports(&source.content) {
+                    let mut chain = vec![source.file.clone(), url.clone()];
+                    if let Some(target) = index.resolve_local_asset(&url, &source.base_rel) {
+                        let mut visited = HashSet::new();
+                        extend_chain(index, &import_graph, &target, &mut visited, &mut chain);
+                    }
+                    findings.push(Finding {
+                        level: Level::Warning,
+                        rule_id: "css/import-chain".into(),
+                        file: source.file.clone(),
+                        selector: format!("@import '{url}'"),
+                        message: format!(
+                            "@import in {where_} delays '{url}' until the importing CSS has downloaded (chain: {})",
+                            chain.join(" → ")
+                        ),
+                        help: "Load each stylesheet with its own <link rel=\"stylesheet\">, or import the CSS from a component so Vite bundles it.".into(),
+                        suggestion: None,
+                        source_hint: None,
+                        confidence: None,
+                    });
+                }
+            }
+
+            if cfg.require_font_display {
+                for face in css::font_faces(&source.content) {
+                    if face.font_display.is_some() {
+                        continue;
+                    }
+                    let family = face.family.unwrap_or_else(|| "(unnamed)".to_string());
+                    findings.push(Finding {
+                        level: Level::Warning,
+                        rule_id: "css/font-display-missing".into(),
+                        file: source.file.clone(),
+                        selector: format!("@font-face {family}"),
+                        message: format!(
+                            "@font-face for '{family}' in {where_} has no font-display; text stays invisible while the font loads"
+                        ),
+                        help: "Add `font-display: swap` (or `optional` for non-critical fonts).".into(),
+                        suggestion: Some("font-display: swap;".into()),
+                        source_hint: None,
+                        confidence: None,
+                    });
+                }
+            }
+
+            findings
+        })
+        .collect()
+}
+
+/// Follow local `@import`s from `target`, appending the deepest chain.
+fn extend_chain(
+    index: &SiteIndex,
+    graph: &HashMap<PathBuf, Vec<PathBuf>>,
+    target: &Path,
+    visited: &mut HashSet<PathBuf>,
+    chain: &mut Vec<String>,
+) {
+    if !visited.insert(target.to_path_buf()) {
+        return;
+    }
+    let Some(children) = graph.get(target) else {
+        return;
+    };
+    let mut best: Vec<String> = Vec::new();
+    for child in children {
+        let mut sub = vec![index.dist_rel_path(child)];
+        extend_chain(index, graph, child, visited, &mut sub);
+        if sub.len() > best.len() {
+            best = sub;
+        }
+    }
+    chain.extend(best);
+}
+
+fn local_css_files(index: &SiteIndex) -> Vec<Source> {
+    let mut files: Vec<Source> = WalkDir::new(&index.dist_path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "css"))
+        .filter_map(|e| {
+            let content = std::fs::read_to_string(e.path()).ok()?;
+            let rel = index.dist_rel_path(e.path());
+            Some(Source {
+                file: rel.clone(),
+                base_rel: rel,
+                content,
+                inline_pages: None,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+    files
+}
+
+/// Inline `<style>` blocks, deduplicated: Astro inlines the same small
+/// stylesheet into every page of a layout, so each distinct block is reported
+/// once, on the first page that carries it.
+fn inline_style_blocks(index: &SiteIndex) -> Vec<Source> {
+    let style_sel = Selector::parse("style").unwrap();
+    let per_page: Vec<(String, Vec<String>)> = index
+        .pages
+        .par_iter()
+        .map(|page| {
+            let html = page.parse_html();
+            let blocks = html
+                .select(&style_sel)
+                .map(|el| el.text().collect::<String>())
+                .filter(|t| !t.trim().is_empty())
+                .collect();
+            (page.rel_path.clone(), blocks)
+        })
+        .collect();
+
+    let mut blocks: BTreeMap<String, (String, usize)> = BTreeMap::new();
+    let mut sorted = per_page;
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    for (rel, page_blocks) in sorted {
+        for block in page_blocks {
+            blocks
+                .entry(block)
+                .and_modify(|(_, n)| *n += 1)
+                .or_insert((rel.clone(), 1));
+        }
+    }
+
+    blocks
+        .into_iter()
+        .map(|(content, (rel, pages))| Source {
+            file: rel.clone(),
+            base_rel: rel,
+            content,
+            inline_pages: Some(pages),
+        })
+        .collect()
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use scraper::Selector;
+
+use crate::config::Config;
+use crate::css;
+use crate::discovery::SiteIndex;
+use crate::report::{Confidence, Finding, Level};
+
+/// Maximum unused selectors listed in a finding's help text.
+const MAX_LISTED: usize = 10;
+
+/// One selector of a style rule, prepared for matching.
+struct SelectorEntry {
+    text: String,
+    /// None when the selector cannot be evaluated statically or is
+    /// safelisted — it is then always treated as used.
+    compiled: Option<Selector>,
+    used: AtomicBool,
+}
+
+struct Sheet {
+    rel_path: String,
+    size: usize,
+    rules: Vec<(std::ops::Range<usize>, Vec<SelectorEntry>)>,
+    pages: usize,
+}
+
+/// Find selectors in local stylesheets that match no element on any page that
+/// loads them. Astro bundles CSS per route, so rules from removed components
+/// or old designs keep shipping until someone deletes them by hand. State
+/// pseudo-classes and pseudo-elements are ignored when matching, and classes
+/// added by JavaScript can be safelisted.
+pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
+    if !config.unused_css.enabled {
+        return Vec::new();
+    }
+
+    let safelist = build_safelist(&config.unused_css.safelist);
+    let link_sel = Selector::parse("link[rel~='stylesheet'][href]").unwrap();
+
+    // Which stylesheets each page loads.
+    let page_sheets: Vec<Vec<PathBuf>> = index
+        .pages
+        .par_iter()
+        .map(|page| {
+            let html = page.parse_html();
+            let mut sheets: Vec<PathBuf> = html
+                .select(&link_sel)
+                .filter_map(|el| el.value().attr("href"))
+                .filter_map(|href| index.resolve_local_asset(href, &page.rel_path))
+                .filter(|p| p.is_file())
+                .collect();
+            sheets.sort();
+            sheets.dedup();
+            sheets
+        })
+        .collect();
+
+    let mut load_counts: HashMap<&PathBuf, usize> = HashMap::new();
+    for path in page_sheets.iter().flatten() {
+        *load_counts.entry(path).or_default() += 1;
+    }
+
+    let sheets: HashMap<&PathBuf, Sheet> = load_counts
+        .into_par_iter()
+        .filter_map(|(path, pages)| {
+            let content = std::fs::read_to_string(path).ok()?;
+            let stripped = css::strip_comments(&content);
+            let rules = css::style_rules(&stripped)
+                .into_iter()
+                .map(|rule| {
+                    let entries = rule
+                        .selectors
+                        .into_iter()
+                        .map(|text| prepare_selector(text, safelist.as_ref()))
+                        .collect();
+                    (rule.span, entries)
+                })
+                .collect();
+            Some((
+                path,
+                Sheet {
+                    rel_path: index.dist_rel_path(path),
+                    size: content.len(),
+                    rules,
+                    pages,
+                },
+            ))
+        })
+        .collect();
+
+    // Match every page against the stylesheets it loads, skipping selectors
+    // an earlier page already used.
+    index
+        .pages
+        .par_iter()
+        .zip(page_sheets.par_iter())
+        .for_each(|(page, loaded)| {
+            if loaded.is_empty() {
+                return;
+            }
+            let html = page.parse_html();
+            for sheet in loaded.iter().filter_map(|p| sheets.get(p)) {
+                for entry in sheet.rules.iter().flat_map(|(_, e)| e) {
+                    let Some(sel) = &entry.compiled else {
+                        continue;
+                    };
+                    if entry.used.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    if html.select(sel).next().is_some() {
+                        entry.used.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        });
+
+    let mut sheets: Vec<Sheet> = sheets.into_values().collect();
+    sheets.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+    let mut findings = Vec::new();
+    for sheet in &sheets {
+        let is_unused = |e: &SelectorEntry| e.compiled.is_some() && !e.used.load(Ordering::Relaxed);
+        let total: usize = sheet.rules.iter().map(|(_, e)| e.len()).sum();
+        let unused: Vec<&str> = sheet
+            .rules
+            .iter()
+            .flat_map(|(_, e)| e)
+            .filter(|e| is_unused(e))
+            .map(|e| e.text.as_str())
+            .collect();
+        if unused.is_empty() {
+            continue;
+        }
+        // Only rules where every selector is unused can be deleted outright.
+        let wasted: usize = sheet
+            .rules
+            .iter()
+            .filter(|(_, e)| e.iter().all(is_unused))
+            .map(|(span, _)| span.len())
+            .sum();
+
+        let mut listed = unused
+            .iter()
+            .take(MAX_LISTED)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if unused.len() > MAX_LISTED {
+            listed.push_str(&format!(", … ({} more)", unused.len() - MAX_LISTED));
+        }
+
+        findings.push(Finding {
+            level: Level::Warning,
+            rule_id: "css/unused-selectors".into(),
+            file: sheet.rel_path.clone(),
+            selector: unused[0].to_string(),
+            message: format!(
+                "{} of {} selector(s) in '{}' match no element on the {} page(s) that load it; {} of {} bytes are in rules that never apply",
+                unused.len(),
+                total,
+                sheet.rel_path,
+                sheet.pages,
+                wasted,
+                sheet.size
+            ),
+            help: format!(
+                "Unused: {listed}. Remove the dead rules, or add classes set by JavaScript to `unused_css.safelist`."
+            ),
+            suggestion: None,
+            source_hint: None,
+            confidence: Some(Confidence::Low),
+        });
+    }
+
+    findings
+}
+
+fn prepare_selector(text: String, safelist: Option<&GlobSet>) -> SelectorEntry {
+    let safelisted = safelist.is_some_and(|set| {
+        css::selector_classes(&text)
+            .iter()
+            .any(|class| set.is_match(class))
+    });
+    let compiled = if safelisted {
+        None
+    } else {
+        css::static_selector(&text).and_then(|s| Selector::parse(&s).ok())
+    };
+    SelectorEntry {
+        text,
+        compiled,
+        used: AtomicBool::new(false),
+    }
+}
+
+fn build_safelist(patterns: &[String]) -> Option<GlobSet> {
+    if patterns.is_empty() {
+        return None;
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = Glob::new(pattern) {
+            builder.add(glob);
+        }
+    }
+    builder.build().ok()
+}
@@ -75,6 +75,7 @@ pub fn check_all(index: &SiteIndex, config: &Config) -> Vec<Finding> {
     index
         .pages
         .par_iter()
+        .filter(|page| !page.is_error_page())
         .flat_map(|page| {
             let mut findings = Vec::new();
             let html = page.parse_html();
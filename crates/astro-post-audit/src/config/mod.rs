@@ -62,6 +62,22 @@ pub struct Config {
     pub structured_data_graph: StructuredDataGraphConfig,
     pub redirects: RedirectsConfig,
     pub js_bloat: JsBloatConfig,
+    pub page_weight: PageWeightConfig,
+    pub unused_css: UnusedCssConfig,
+    pub stylesheets: StylesheetsConfig,
+    pub module_graph: ModuleGraphConfig,
+    pub manifest: ManifestConfig,
+    pub feeds: FeedsConfig,
+    pub csp: CspConfig,
+    pub sri: SriConfig,
+    pub crawl: CrawlConfig,
+    pub http_headers: HttpHeadersConfig,
+    pub error_pages: ErrorPagesConfig,
+    pub pagination: PaginationConfig,
+    pub llms_txt: LlmsTxtConfig,
+    pub security_txt: SecurityTxtConfig,
+    pub secrets: SecretsConfig,
+    pub deploy_hygiene: DeployHygieneConfig,
     pub content_sync: ContentSyncConfig,
     pub html_validation: HtmlValidationConfig,
     pub images: ImagesConfig,
@@ -171,6 +187,12 @@ pub struct SitemapConfig {
     pub canonical_must_be_in_sitemap: bool,
     pub forbid_noncanonical_in_sitemap: bool,
     pub entries_must_exist_in_dist: bool,
+    /// Validate lastmod (W3C datetime, not in the future), changefreq and priority values. @default true
+    pub check_entry_metadata: bool,
+    /// Compare xhtml:link hreflang alternates with each page's HTML hreflang links. @default true
+    pub check_alternates: bool,
+    /// Check image:/video: extension URLs against files in dist and required video members. @default true
+    pub check_media: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -276,6 +298,8 @@ pub struct SecurityConfig {
     pub check_target_blank: bool,
     pub check_mixed_content: bool,
     pub warn_inline_scripts: bool,
+    /// Check HSTS, X-Content-Type-Options and CSP response headers of crawled pages. @default false
+    pub check_headers: bool,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -296,6 +320,24 @@ pub struct ExternalLinksConfig {
     pub fail_on_broken: bool,
     pub allow_domains: Vec<String>,
     pub block_domains: Vec<String>,
+    /// JSON file that keeps results between runs (e.g. `node_modules/.cache/astro-post-audit/links.json`). Without it every URL is requested on every run. @default undefined
+    pub cache_path: Option<String>,
+    /// Reuse a cached OK result for this many seconds. @default 604800
+    pub cache_ttl_ok_secs: u64,
+    /// Reuse a cached broken result for this many seconds. @default 3600
+    pub cache_ttl_broken_secs: u64,
+    /// Maximum concurrent requests to one host. @default 2
+    pub max_per_host: usize,
+    /// Minimum delay between request starts to the same host, in milliseconds. @default 0
+    pub host_delay_ms: u64,
+    /// Retries after a timeout, connection error, 408, 429 or 5xx. @default 2
+    pub retries: u32,
+    /// Wait before the first retry in milliseconds, doubled for each further retry. A `Retry-After` header (up to 60s) takes precedence. @default 1000
+    pub backoff_ms: u64,
+    /// Start no requests after this many seconds; unchecked links are reported as info. 0 = no limit. @default 0
+    pub time_budget_secs: u64,
+    /// Links broken in only one of this many recent runs are reported as flaky (info) instead of broken. Needs `cache_path`; 0 = off. @default 3
+    pub quarantine_runs: usize,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -313,6 +355,8 @@ pub struct RobotsTxtConfig {
     pub check_noindex_contradiction: bool,
     /// Warn when a sitemap URL is blocked by robots.txt. @default false
     pub check_sitemap_blocked: bool,
+    /// Report, per search and AI crawler, which indexable pages robots.txt blocks. @default false
+    pub bot_coverage: bool,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -339,6 +383,12 @@ pub struct PrivacySecurityConfig {
     pub enabled: bool,
     /// Enable GDPR/DSGVO third-party transfer checks (Google Fonts, YouTube, Maps, CDNs, external images). @default false
     pub gdpr: bool,
+    /// Check Referrer-Policy and Permissions-Policy response headers of crawled pages. @default false
+    pub check_headers: bool,
+    /// Report plain-text email addresses, phone numbers and IBANs (text, attributes, JSON-LD, comments) and HTML comments with internal notes. @default false
+    pub pii: bool,
+    /// Values the PII scan accepts, e.g. the contact address in the imprint. Glob patterns (`*@example.com`); phone numbers are compared by digits.
+    pub pii_allow: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -347,11 +397,22 @@ pub struct StructuredDataGraphConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct RedirectsConfig {
-    /// Analyze static meta-refresh redirects (chains, loops, links to redirect pages). @default false
+    /// Analyze redirects: static meta-refresh pages and hosting config (chains, loops, links to redirects, missing targets, shadowed rules). @default false
     pub enabled: bool,
+    /// Read `_redirects` and `_headers` from dist and `netlify.toml` / `vercel.json` from the project root. @default true
+    pub hosting_config: bool,
+}
+
+impl Default for RedirectsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hosting_config: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -374,9 +435,326 @@ impl Default for JsBloatConfig {
 
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
+pub struct PageWeightConfig {
+    /// Report full page weight per route (HTML, JS, CSS, images, fonts, iframes; raw/gzip/brotli). @default false
+    pub enabled: bool,
+    /// Warn when a route's brotli transfer size exceeds this size in KB. No budget when unset.
+    pub max_kb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UnusedCssConfig {
+    /// Report selectors in local stylesheets that match no element on the pages loading them. @default false
+    pub enabled: bool,
+    /// Class names (glob patterns, e.g. "is-open", "swiper-*") added at runtime by JavaScript; selectors using them are never reported. @default []
+    pub safelist: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StylesheetsConfig {
+    /// Audit the contents of local .css files and inline <style> blocks. @default false
+    pub enabled: bool,
+    /// Error on url() references to files missing from dist. @default true
+    pub check_urls: bool,
+    /// Warn on @import, which serializes stylesheet downloads. @default true
+    pub check_imports: bool,
+    /// Warn on @font-face rules without font-display. @default true
+    pub require_font_display: bool,
+    /// Warn on http:// URLs inside url(). @default true
+    pub check_mixed_content: bool,
+}
+
+impl Default for StylesheetsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_urls: true,
+            check_imports: true,
+            require_font_display: true,
+            check_mixed_content: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ModuleGraphConfig {
+    /// Resolve the JS module graph (entry scripts, islands, modulepreload, imports) and report JS per route and island. @default false
+    pub enabled: bool,
+    /// Warn when a route's eagerly loaded JS exceeds this size in KB. No budget when unset.
+    pub max_route_kb: Option<u64>,
+    /// Warn when a single island's JS exceeds this size in KB. No budget when unset.
+    pub max_island_kb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ManifestConfig {
+    /// Audit the web app manifest, favicon set and theme-color. @default false
+    pub enabled: bool,
+    /// Warn on pages without <link rel="icon"> when there is no /favicon.ico. @default true
+    pub require_favicon: bool,
+    /// Warn on pages without <link rel="apple-touch-icon"> when there is no /apple-touch-icon.png. @default true
+    pub require_apple_touch_icon: bool,
+    /// Info on pages without <meta name="theme-color">. @default true
+    pub require_theme_color: bool,
+    /// Warn on pages linking a different manifest or icon set than most pages. @default true
+    pub check_consistency: bool,
+}
+
+impl Default for ManifestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            require_favicon: true,
+            require_apple_touch_icon: true,
+            require_theme_color: true,
+            check_consistency: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FeedsConfig {
+    /// Discover and audit RSS, Atom and JSON feeds. @default false
+    pub enabled: bool,
+    /// Route globs of posts that must appear in a feed (`*` matches one path segment). Empty disables the check. @default ["/blog/*", "/posts/*"]
+    pub post_patterns: Vec<String>,
+    /// Warn when a feed item's title does not match the linked page's <title>. @default true
+    pub check_titles: bool,
+}
+
+impl Default for FeedsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            post_patterns: vec!["/blog/*".into(), "/posts/*".into()],
+            check_titles: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CspConfig {
+    /// Check every page against its Content-Security-Policy (from `_headers`, host config or `<meta http-equiv>`). @default false
+    pub enabled: bool,
+    /// Policy to evaluate instead of the deployed one, e.g. a draft before shipping it. @default null
+    pub policy: Option<String>,
+    /// Warn about pages served without a policy. @default true
+    pub require_policy: bool,
+    /// Emit a suggested policy with `sha256-` hashes for every inline script and style. @default false
+    pub suggest_policy: bool,
+}
+
+impl Default for CspConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            policy: None,
+            require_policy: true,
+            suggest_policy: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SriConfig {
+    /// Verify `integrity` attributes on scripts and stylesheets against the files in dist. @default false
+    pub enabled: bool,
+    /// Report the correct integrity value for every local script and stylesheet. @default false
+    pub report_hashes: bool,
+    /// Algorithm for reported values: sha256, sha384 or sha512. @default "sha384"
+    pub report_algorithm: String,
+}
+
+impl Default for SriConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            report_hashes: false,
+            report_algorithm: "sha384".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CrawlConfig {
+    /// Crawl this server (e.g. `astro preview` at http://localhost:4321) instead of reading HTML from dist. @default undefined
+    pub url: Option<String>,
+    /// Stop after requesting this many URLs. @default 500
+    pub max_pages: usize,
+    /// Follow links at most this many clicks away from `/` and the sitemap. @default 10
+    pub max_depth: usize,
+    /// Timeout per request in milliseconds. @default 10000
+    pub timeout_ms: u64,
+    /// Also start from every URL in the sitemap (dist and server). @default true
+    pub follow_sitemap: bool,
+}
+
+impl Default for CrawlConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            max_pages: 500,
+            max_depth: 10,
+            timeout_ms: 10_000,
+            follow_sitemap: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpHeadersConfig {
+    /// Check caching, compression, Content-Type and X-Robots-Tag headers of crawled pages and their assets. @default false
+    pub enabled: bool,
+    /// Also request local scripts, stylesheets, images and fonts referenced by crawled pages. @default true
+    pub check_assets: bool,
+    /// Maximum number of assets requested. @default 200
+    pub max_assets: usize,
+    /// Warn when HTML may be cached longer than this many seconds. @default 3600
+    pub html_max_age: u64,
+    /// Minimum max-age in seconds for hashed `_astro/` assets. @default 31536000
+    pub asset_min_max_age: u64,
+    /// Text responses larger than this many bytes should be compressed. @default 1024
+    pub compression_min_bytes: usize,
+}
+
+impl Default for HttpHeadersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_assets: true,
+            max_assets: 200,
+            html_max_age: 3600,
+            asset_min_max_age: 31_536_000,
+            compression_min_bytes: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ErrorPagesConfig {
+    /// Audit 404.html / 500.html: presence, noindex or absent from the sitemap, no self-canonical, a way back home, root-relative asset URLs. @default false
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PaginationConfig {
+    /// Validate paginate() series (/blog/, /blog/2/, ...): contiguous pages, working and reciprocal rel=prev/next, self-canonicals, unique titles, no orphaned pages. @default false
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LlmsTxtConfig {
+    /// Validate dist/llms.txt: format, internal links (also in llms-full.txt), coverage. @default false
+    pub enabled: bool,
+    /// Warn when llms.txt is missing. @default false
+    pub require: bool,
+    /// Warn about indexable pages linked from the home page that llms.txt does not list. @default true
+    pub check_coverage: bool,
+    /// Write a draft llms.txt built from page titles, descriptions and URL sections to this path. Works without `enabled`. @default null
+    pub generate: Option<String>,
+}
+
+impl Default for LlmsTxtConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            require: false,
+            check_coverage: true,
+            generate: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SecurityTxtConfig {
+    /// Require /.well-known/security.txt and validate it against RFC 9116. @default false
+    pub enabled: bool,
+    /// Warn when Expires is less than this many days away. @default 30
+    pub expires_warn_days: u32,
+}
+
+impl Default for SecurityTxtConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            expires_warn_days: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// Scan text files in dist (HTML, JS, CSS, JSON, source maps) for credentials. @default false
+    pub enabled: bool,
+    /// Extra patterns scanned for in addition to the bundled ruleset.
+    pub patterns: Vec<SecretPattern>,
+    /// Regexes for matched values to ignore, e.g. publishable keys that are meant to be public.
+    pub allow: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecretPattern {
+    /// Rule ID suffix; findings are reported as `secrets/<id>`.
+    pub id: String,
+    /// Regular expression. With a capture group, only the group is reported.
+    pub regex: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DeployHygieneConfig {
+    /// Walk all of dist for files that should not be deployed: dotfiles, backups, VCS and editor leftovers, source maps. @default false
+    pub enabled: bool,
+    /// Extra glob patterns to flag. Patterns without `/` match a file or directory name anywhere in dist.
+    pub deny: Vec<String>,
+    /// Glob patterns exempt from the deny-list, e.g. an `.env.example` published on purpose.
+    pub allow: Vec<String>,
+    /// Report source maps that embed original sources, and `sourceMappingURL` comments pointing at missing or deployed maps. @default true
+    pub check_source_maps: bool,
+}
+
+impl Default for DeployHygieneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            deny: Vec::new(),
+            allow: Vec::new(),
+            check_source_maps: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct ContentSyncConfig {
     /// Warn about content collection items (src/content) with no generated page. @default false
     pub enabled: bool,
+    /// Report items marked `draft: true` or dated in the future (`pubDate`, `publishDate`, else `date` at lower confidence) that still produced a page, sitemap entry or feed item. @default true
+    pub check_drafts: bool,
+}
+
+impl Default for ContentSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_drafts: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -415,6 +793,12 @@ pub struct ImagesConfig {
     pub info_missing_srcset: bool,
     /// Info if <img> src uses legacy format (.jpg/.jpeg/.png/.gif). @default false
     pub format_hints: bool,
+    /// Read the intrinsic size of local images: oversized files, aspect-ratio mismatches, wrong srcset w descriptors, large public/ images. @default false
+    pub check_intrinsic_size: bool,
+    /// Warn when an image is wider than its rendered slot (largest px `sizes` entry or width attribute) by more than this factor. @default 3
+    pub max_oversize_ratio: f64,
+    /// Warn on raster images over this size in KB served from public/ instead of _astro/. @default 200
+    pub public_max_kb: u64,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -496,6 +880,9 @@ impl Default for SitemapConfig {
             canonical_must_be_in_sitemap: true,
             forbid_noncanonical_in_sitemap: false,
             entries_must_exist_in_dist: true,
+            check_entry_metadata: true,
+            check_alternates: true,
+            check_media: true,
         }
     }
 }
@@ -569,6 +956,9 @@ impl Default for ImagesConfig {
             warn_missing_lazy: true,
             info_missing_srcset: true,
             format_hints: false,
+            check_intrinsic_size: false,
+            max_oversize_ratio: 3.0,
+            public_max_kb: 200,
         }
     }
 }
@@ -589,6 +979,7 @@ impl Default for SecurityConfig {
             check_target_blank: true,
             check_mixed_content: true,
             warn_inline_scripts: false,
+            check_headers: false,
         }
     }
 }
@@ -603,6 +994,7 @@ impl Default for RobotsTxtConfig {
             ai_bot_policy: false,
             check_noindex_contradiction: false,
             check_sitemap_blocked: false,
+            bot_coverage: false,
         }
     }
 }
@@ -616,6 +1008,15 @@ impl Default for ExternalLinksConfig {
             fail_on_broken: false,
             allow_domains: Vec::new(),
             block_domains: Vec::new(),
+            cache_path: None,
+            cache_ttl_ok_secs: 7 * 86_400,
+            cache_ttl_broken_secs: 3600,
+            max_per_host: 2,
+            host_delay_ms: 0,
+            retries: 2,
+            backoff_ms: 1000,
+            time_budget_secs: 0,
+            quarantine_runs: 3,
         }
     }
 }
@@ -678,6 +1079,80 @@ impl Config {
                 "html_basics.meta_description_max_length must be greater than 0 when set"
             );
         }
+        if matches!(self.page_weight.max_kb, Some(0)) {
+            anyhow::bail!("page_weight.max_kb must be greater than 0 when set");
+        }
+        if matches!(self.module_graph.max_route_kb, Some(0))
+            || matches!(self.module_graph.max_island_kb, Some(0))
+        {
+            anyhow::bail!("module_graph budgets must be greater than 0 when set");
+        }
+        if self.images.max_oversize_ratio < 1.0 {
+            anyhow::bail!("images.max_oversize_ratio must be at least 1.0");
+        }
+        if !matches!(
+            self.sri.report_algorithm.to_ascii_lowercase().as_str(),
+            "sha256" | "sha384" | "sha512"
+        ) {
+            anyhow::bail!(
+                "sri.report_algorithm must be sha256, sha384 or sha512, got '{}'",
+                self.sri.report_algorithm
+            );
+        }
+        if let Some(url) = &self.crawl.url {
+            let parsed = url::Url::parse(url)
+                .map_err(|e| anyhow::anyhow!("crawl.url: invalid URL '{url}': {e}"))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                anyhow::bail!("crawl.url must be an http(s) URL, got '{url}'");
+            }
+            if self.crawl.max_pages == 0 || self.crawl.timeout_ms == 0 {
+                anyhow::bail!("crawl.max_pages and crawl.timeout_ms must be greater than 0");
+            }
+        }
+        for pattern in &self.unused_css.safelist {
+            globset::Glob::new(pattern).map_err(|e| {
+                anyhow::anyhow!("unused_css.safelist: invalid pattern '{pattern}': {e}")
+            })?;
+        }
+        for pattern in &self.feeds.post_patterns {
+            globset::Glob::new(pattern).map_err(|e| {
+                anyhow::anyhow!("feeds.post_patterns: invalid pattern '{pattern}': {e}")
+            })?;
+        }
+        for pattern in &self.secrets.patterns {
+            if pattern.id.is_empty()
+                || !pattern
+                    .id
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+            {
+                anyhow::bail!(
+                    "secrets.patterns: id must be lowercase letters, digits and '-', got '{}'",
+                    pattern.id
+                );
+            }
+            regex::Regex::new(&pattern.regex).map_err(|e| {
+                anyhow::anyhow!("secrets.patterns.{}: invalid regex: {e}", pattern.id)
+            })?;
+        }
+        for pattern in &self.privacy_security.pii_allow {
+            globset::Glob::new(pattern).map_err(|e| {
+                anyhow::anyhow!("privacy_security.pii_allow: invalid pattern '{pattern}': {e}")
+            })?;
+        }
+        for pattern in &self.secrets.allow {
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("secrets.allow: invalid regex '{pattern}': {e}"))?;
+        }
+        for (key, patterns) in [
+            ("deploy_hygiene.deny", &self.deploy_hygiene.deny),
+            ("deploy_hygiene.allow", &self.deploy_hygiene.allow),
+        ] {
+            for pattern in patterns {
+                globset::Glob::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("{key}: invalid pattern '{pattern}': {e}"))?;
+            }
+        }
         if self.external_links.enabled {
             if self.external_links.timeout_ms == 0 {
                 anyhow::bail!("external_links.timeout_ms must be greater than 0 when enabled");
@@ -685,6 +1160,9 @@ impl Config {
             if self.external_links.max_concurrent == 0 {
                 anyhow::bail!("external_links.max_concurrent must be greater than 0 when enabled");
             }
+            if self.external_links.max_per_host == 0 {
+                anyhow::bail!("external_links.max_per_host must be greater than 0 when enabled");
+            }
         }
         Ok(())
     }
@@ -0,0 +1,401 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use url::Url;
+
+/// Fetch and navigation directives defined by CSP Level 3.
+const KNOWN_DIRECTIVES: &[&str] = &[
+    "default-src",
+    "script-src",
+    "script-src-elem",
+    "script-src-attr",
+    "style-src",
+    "style-src-elem",
+    "style-src-attr",
+    "img-src",
+    "font-src",
+    "media-src",
+    "connect-src",
+    "object-src",
+    "frame-src",
+    "child-src",
+    "worker-src",
+    "manifest-src",
+    "base-uri",
+    "form-action",
+    "frame-ancestors",
+    "sandbox",
+    "upgrade-insecure-requests",
+    "block-all-mixed-content",
+    "report-uri",
+    "report-to",
+    "require-trusted-types-for",
+    "trusted-types",
+    "prefetch-src",
+];
+
+/// Directives a `<meta http-equiv>` policy cannot set.
+pub const META_IGNORED_DIRECTIVES: &[&str] =
+    &["frame-ancestors", "report-uri", "sandbox", "report-to"];
+
+/// Keywords that only work in single quotes; unquoted they are host names.
+const KEYWORDS: &[&str] = &[
+    "self",
+    "none",
+    "unsafe-inline",
+    "unsafe-eval",
+    "unsafe-hashes",
+    "strict-dynamic",
+    "wasm-unsafe-eval",
+    "report-sample",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Directive {
+    /// Lowercase directive name.
+    pub name: String,
+    pub sources: Vec<String>,
+}
+
+/// One serialized policy (`directive src src; directive ...`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Policy {
+    pub directives: Vec<Directive>,
+}
+
+impl Policy {
+    /// Parse a single policy. Directive names are case-insensitive and only
+    /// the first occurrence of a directive counts.
+    pub fn parse(policy: &str) -> Policy {
+        let mut directives: Vec<Directive> = Vec::new();
+        for part in policy.split(';') {
+            let mut tokens = part.split_whitespace();
+            let Some(name) = tokens.next() else {
+                continue;
+            };
+            let name = name.to_ascii_lowercase();
+            if directives.iter().any(|d| d.name == name) {
+                continue;
+            }
+            directives.push(Directive {
+                name,
+                sources: tokens.map(str::to_string).collect(),
+            });
+        }
+        Policy { directives }
+    }
+
+    /// Parse a header value, which may hold several comma-separated
+    /// policies (all of them are enforced).
+    pub fn parse_list(value: &str) -> Vec<Policy> {
+        value
+            .split(',')
+            .map(Policy::parse)
+            .filter(|p| !p.directives.is_empty())
+            .collect()
+    }
+
+    pub fn directive(&self, name: &str) -> Option<&Directive> {
+        self.directives.iter().find(|d| d.name == name)
+    }
+
+    /// The directive that governs `name`, following the CSP fallback list
+    /// (e.g. `script-src-elem` -> `script-src` -> `default-src`). None when
+    /// the policy does not restrict it.
+    pub fn effective(&self, name: &str) -> Option<&Directive> {
+        fallback_chain(name)
+            .iter()
+            .find_map(|candidate| self.directive(candidate))
+    }
+
+    /// Problems with the policy text: unknown directives, unquoted keywords,
+    /// and hash or nonce sources without quotes.
+    pub fn syntax_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for d in &self.directives {
+            if !KNOWN_DIRECTIVES.contains(&d.name.as_str()) {
+                problems.push(format!("unknown directive '{}'", d.name));
+            }
+            for source in &d.sources {
+                let bare = source.to_ascii_lowercase();
+                if KEYWORDS.contains(&bare.as_str())
+                    || ["sha256-", "sha384-", "sha512-", "nonce-"]
+                        .iter()
+                        .any(|p| bare.starts_with(p))
+                {
+                    problems.push(format!(
+                        "'{}' in {} must be quoted ('{}'), otherwise it is read as a host name",
+                        source, d.name, source
+                    ));
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// Fallback order for a directive (CSP Level 3, section 6.8).
+fn fallback_chain(name: &str) -> &'static [&'static str] {
+    match name {
+        "script-src-elem" => &["script-src-elem", "script-src", "default-src"],
+        "script-src-attr" => &["script-src-attr", "script-src", "default-src"],
+        "style-src-elem" => &["style-src-elem", "style-src", "default-src"],
+        "style-src-attr" => &["style-src-attr", "style-src", "default-src"],
+        "worker-src" => &["worker-src", "child-src", "script-src", "default-src"],
+        "frame-src" => &["frame-src", "child-src", "default-src"],
+        "img-src" => &["img-src", "default-src"],
+        "font-src" => &["font-src", "default-src"],
+        "media-src" => &["media-src", "default-src"],
+        "connect-src" => &["connect-src", "default-src"],
+        "object-src" => &["object-src", "default-src"],
+        "manifest-src" => &["manifest-src", "default-src"],
+        // Navigation directives do not fall back to default-src.
+        "form-action" => &["form-action"],
+        "base-uri" => &["base-uri"],
+        _ => &["default-src"],
+    }
+}
+
+impl Directive {
+    fn has(&self, keyword: &str) -> bool {
+        self.sources.iter().any(|s| s.eq_ignore_ascii_case(keyword))
+    }
+
+    fn has_nonce_or_hash(&self) -> bool {
+        self.sources.iter().any(|s| is_nonce(s) || is_hash(s))
+    }
+
+    fn allows_nonce(&self, nonce: Option<&str>) -> bool {
+        nonce.is_some_and(|n| {
+            !n.is_empty()
+                && self.sources.iter().any(|s| {
+                    s.strip_prefix("'nonce-").and_then(|v| v.strip_suffix('\'')) == Some(n)
+                })
+        })
+    }
+
+    /// The algorithm prefix is case-insensitive; the base64 digest is not.
+    fn allows_hash(&self, content: &str) -> bool {
+        self.sources
+            .iter()
+            .filter(|s| is_hash(s))
+            .filter_map(|s| s.trim_matches('\'').split_once('-'))
+            .any(|(alg, digest)| digest_source(alg, content)[alg.len() + 1..] == *digest)
+    }
+
+    /// Whether an inline `<script>` / `<style>` with this text and `nonce`
+    /// attribute may run.
+    pub fn allows_inline(&self, content: &str, nonce: Option<&str>) -> bool {
+        if self.allows_nonce(nonce) || self.allows_hash(content) {
+            return true;
+        }
+        // Nonces, hashes and 'strict-dynamic' switch 'unsafe-inline' off.
+        self.has("'unsafe-inline'") && !self.has_nonce_or_hash() && !self.has("'strict-dynamic'")
+    }
+
+    /// Whether an event-handler or `style` attribute with this value may run.
+    pub fn allows_attribute(&self, value: &str) -> bool {
+        if self.has("'unsafe-hashes'") && self.allows_hash(value) {
+            return true;
+        }
+        self.has("'unsafe-inline'") && !self.has_nonce_or_hash() && !self.has("'strict-dynamic'")
+    }
+
+    /// Whether a resource at `url` may load on a page at `page`.
+    pub fn allows_url(&self, url: &Url, page: &Url, nonce: Option<&str>) -> bool {
+        if self.allows_nonce(nonce) {
+            return true;
+        }
+        // With 'strict-dynamic', host and scheme sources are ignored.
+        if self.name.starts_with("script-src") && self.has("'strict-dynamic'") {
+            return false;
+        }
+        self.sources
+            .iter()
+            .any(|source| source_matches(source, url, page))
+    }
+}
+
+fn is_nonce(source: &str) -> bool {
+    source.starts_with("'nonce-") && source.ends_with('\'')
+}
+
+fn is_hash(source: &str) -> bool {
+    ["'sha256-", "'sha384-", "'sha512-"]
+        .iter()
+        .any(|p| source.to_ascii_lowercase().starts_with(p))
+        && source.ends_with('\'')
+}
+
+/// `sha256-<base64>` (without quotes) for `content`. `alg` is one of
+/// sha256, sha384, sha512. The same format is used by CSP hash sources and
+/// Subresource Integrity metadata.
+pub fn digest_source(alg: &str, content: impl AsRef<[u8]>) -> String {
+    let content = content.as_ref();
+    let digest = match alg.to_ascii_lowercase().as_str() {
+        "sha384" => STANDARD.encode(Sha384::digest(content)),
+        "sha512" => STANDARD.encode(Sha512::digest(content)),
+        _ => STANDARD.encode(Sha256::digest(content)),
+    };
+    format!("{}-{}", alg.to_ascii_lowercase(), digest)
+}
+
+/// Match one source expression against a URL (CSP Level 3, section 6.7.2).
+fn source_matches(source: &str, url: &Url, page: &Url) -> bool {
+    let lower = source.to_ascii_lowercase();
+    if lower == "'self'" {
+        return url.origin() == page.origin()
+            || (page.scheme() == "http"
+                && url.scheme() == "https"
+                && url.host_str() == page.host_str());
+    }
+    if lower.starts_with('\'') {
+        return false;
+    }
+    if lower == "*" {
+        // Matches network schemes, and the page's own scheme.
+        return matches!(url.scheme(), "http" | "https" | "ws" | "wss")
+            || url.scheme() == page.scheme();
+    }
+    // Scheme source, e.g. `https:` or `data:`.
+    if let Some(scheme) = lower.strip_suffix(':') {
+        if !scheme.contains('/') {
+            return scheme_matches(scheme, url.scheme());
+        }
+    }
+
+    // Host source: [scheme://]host[:port][/path]
+    let (scheme, rest) = match lower.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, lower.as_str()),
+    };
+    match scheme {
+        Some(scheme) if !scheme_matches(scheme, url.scheme()) => return false,
+        None if !scheme_matches(page.scheme(), url.scheme()) => return false,
+        _ => {}
+    }
+    let (host_port, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], Some(&rest[i..])),
+        None => (rest, None),
+    };
+    let (host, port) = match host_port.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (host_port, None),
+    };
+    let Some(url_host) = url.host_str().map(str::to_ascii_lowercase) else {
+        return false;
+    };
+    let host_ok = match host.strip_prefix("*.") {
+        Some(suffix) => url_host.ends_with(&format!(".{suffix}")),
+        None => host == "*" || url_host == host,
+    };
+    if !host_ok {
+        return false;
+    }
+    let port_ok = match port {
+        Some("*") => true,
+        Some(port) => {
+            url.port_or_known_default()
+                .map(|p| p.to_string())
+                .as_deref()
+                == Some(port)
+        }
+        // Default port for the scheme (80 also allows an upgrade to 443).
+        None => url.port().is_none(),
+    };
+    if !port_ok {
+        return false;
+    }
+    match path {
+        None | Some("/") => true,
+        Some(path) if path.ends_with('/') => url.path().starts_with(path),
+        Some(path) => url.path() == path,
+    }
+}
+
+/// `http` sources also allow `https` (and `ws` allows `wss`).
+fn scheme_matches(source: &str, actual: &str) -> bool {
+    source == actual
+        || (source == "http" && actual == "https")
+        || (source == "ws" && actual == "wss")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page() -> Url {
+        Url::parse("https://example.com/blog/").unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_fallback() {
+        let policy = Policy::parse(
+            "default-src 'self'; Script-Src 'self' https://cdn.example.net; script-src 'none'",
+        );
+        assert_eq!(policy.directives.len(), 2);
+        assert_eq!(
+            policy.effective("script-src-elem").unwrap().name,
+            "script-src"
+        );
+        assert_eq!(policy.effective("img-src").unwrap().name, "default-src");
+        assert!(policy.effective("form-action").is_none());
+        assert_eq!(Policy::parse_list("default-src 'self', img-src *").len(), 2);
+    }
+
+    #[test]
+    fn test_source_matching() {
+        let policy = Policy::parse(
+            "img-src 'self' data: *.cdn.com https://img.example.org/photos/ http://legacy.test:8080",
+        );
+        let img = policy.effective("img-src").unwrap();
+        let allows = |u: &str| img.allows_url(&Url::parse(u).unwrap(), &page(), None);
+        assert!(allows("https://example.com/a.png"));
+        assert!(allows("data:image/png;base64,AAAA"));
+        assert!(allows("https://a.cdn.com/x.png"));
+        assert!(!allows("https://cdn.com/x.png"));
+        assert!(allows("https://img.example.org/photos/1.jpg"));
+        assert!(!allows("https://img.example.org/other/1.jpg"));
+        assert!(allows("https://legacy.test:8080/x.png"));
+        assert!(!allows("https://legacy.test/x.png"));
+        assert!(!allows("https://evil.com/x.png"));
+    }
+
+    #[test]
+    fn test_inline_hashes_and_nonces() {
+        let hash = digest_source("sha256", "alert(1)");
+        let policy = Policy::parse(&format!(
+            "script-src 'self' '{hash}' 'nonce-abc' 'unsafe-inline'; style-src 'unsafe-inline'"
+        ));
+        let script = policy.effective("script-src-elem").unwrap();
+        assert!(script.allows_inline("alert(1)", None));
+        assert!(script.allows_inline("other()", Some("abc")));
+        // 'unsafe-inline' is ignored next to a hash or nonce.
+        assert!(!script.allows_inline("other()", None));
+        assert!(!script.allows_attribute("alert(1)"));
+        let style = policy.effective("style-src-attr").unwrap();
+        assert!(style.allows_attribute("color: red"));
+        assert_eq!(hash, "sha256-bhHHL3z2vDgxUt0W3dWQOrprscmda2Y5pLsLg4GF+pI=");
+
+        let digest = hash.strip_prefix("sha256-").unwrap();
+        let upper = Policy::parse(&format!("script-src 'SHA256-{digest}'"));
+        assert!(upper
+            .effective("script-src-elem")
+            .unwrap()
+            .allows_inline("alert(1)", None));
+        let wrong_case = Policy::parse(&format!(
+            "script-src 'sha256-{}'",
+            digest.to_ascii_lowercase()
+        ));
+        assert!(!wrong_case
+            .effective("script-src-elem")
+            .unwrap()
+            .allows_inline("alert(1)", None));
+    }
+
+    #[test]
+    fn test_syntax_problems() {
+        let policy = Policy::parse("default-src self; scritp-src 'none'");
+        assert_eq!(policy.syntax_problems().len(), 2);
+    }
+}
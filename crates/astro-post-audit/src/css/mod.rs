@@ -0,0 +1,568 @@
+/// Remove `/* ... */` comments, keeping string literals intact so a `/*`
+/// inside `content: "/*"` is not mistaken for a comment.
+pub fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut chars = css.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// All `url(...)` references in a CSS fragment (comments must already be
+/// stripped), with quotes removed.
+pub fn url_refs(css: &str) -> Vec<String> {
+    let lower = css.to_ascii_lowercase();
+    let mut refs = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = lower[pos..].find("url(") {
+        let start = pos + found + 4;
+        let Some(end) = css[start..].find(')') else {
+            break;
+        };
+        let raw = css[start..start + end].trim();
+        let value = raw.trim_matches(|c| c == '"' || c == '\'').trim();
+        if !value.is_empty() {
+            refs.push(value.to_string());
+        }
+        pos = start + end + 1;
+    }
+    refs
+}
+
+/// An `@font-face` rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFace {
+    pub family: Option<String>,
+    pub font_display: Option<String>,
+    /// `url()` references of the `src` descriptor, in order.
+    pub sources: Vec<String>,
+}
+
+/// All `@font-face` rules of a stylesheet.
+pub fn font_faces(css: &str) -> Vec<FontFace> {
+    let css = strip_comments(css);
+    at_rules(&css)
+        .into_iter()
+        .filter(|r| r.name == "font-face")
+        .filter_map(|r| {
+            let body = r.block?;
+            let unquote = |v: &str| v.trim_matches(|c| c == '"' || c == '\'').to_string();
+            Some(FontFace {
+                family: declaration_value(body, "font-family").map(unquote),
+                font_display: declaration_value(body, "font-display").map(str::to_string),
+                sources: declaration_value(body, "src")
+                    .map(url_refs)
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// The font file a browser would download for each `@font-face` rule: the
+/// first non-data `url()` in its `src` descriptor.
+pub fn font_face_sources(css: &str) -> Vec<String> {
+    font_faces(css)
+        .into_iter()
+        .filter_map(|f| f.sources.into_iter().find(|u| !u.starts_with("data:")))
+        .collect()
+}
+
+/// URLs of the `@import` statements of a stylesheet, in order. Only
+/// top-level imports before any other rule count (`@charset` and `@layer`
+/// statements may precede them); browsers ignore the rest, including imports
+/// nested in `@media` or `@supports`.
+pub fn imports(css: &str) -> Vec<String> {
+    let css = strip_comments(css);
+    let bytes = css.as_bytes();
+    let end = css.len();
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while pos < end {
+        while pos < end && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b';') {
+            pos += 1;
+        }
+        if pos >= end {
+            break;
+        }
+        let delim = find_top_level(&css, pos, end, b"{;}").unwrap_or(end);
+        if delim < end && bytes[delim] != b';' {
+            break;
+        }
+        let Some(at) = css[pos..delim].trim().strip_prefix('@') else {
+            break;
+        };
+        let name_len = at
+            .find(|c: char| c.is_whitespace() || c == '(' || c == '"' || c == '\'')
+            .unwrap_or(at.len());
+        match at[..name_len].to_ascii_lowercase().as_str() {
+            "import" => out.extend(import_url(at[name_len..].trim())),
+            "charset" | "layer" => {}
+            _ => break,
+        }
+        pos = delim + 1;
+    }
+    out
+}
+
+/// The URL of an `@import` prelude: `url(...)` or a quoted string.
+fn import_url(prelude: &str) -> Option<String> {
+    let url = if prelude.to_ascii_lowercase().starts_with("url(") {
+        url_refs(prelude).into_iter().next()?
+    } else {
+        let quote = prelude.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        prelude[1..].split(quote).next()?.to_string()
+    };
+    (!url.is_empty()).then_some(url)
+}
+
+/// Value of the last `name: value` declaration in a block body.
+pub fn declaration_value<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    declarations(body)
+        .into_iter()
+        .filter(|(prop, _)| prop.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+        .next_back()
+}
+
+/// Split a block body into `(property, value)` pairs. Semicolons inside
+/// parentheses or strings (e.g. `url(data:font/woff2;base64,...)`) do not
+/// end a declaration.
+pub fn declarations(body: &str) -> Vec<(&str, &str)> {
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                push_declaration(&body[start..i], &mut out);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    push_declaration(&body[start..], &mut out);
+    out
+}
+
+fn push_declaration<'a>(decl: &'a str, out: &mut Vec<(&'a str, &'a str)>) {
+    if let Some((prop, value)) = decl.split_once(':') {
+        let prop = prop.trim();
+        if !prop.is_empty() {
+            out.push((prop, value.trim()));
+        }
+    }
+}
+
+/// A style rule: its comma-separated selectors and the byte span of the whole
+/// rule (prelude through closing brace) in the comment-stripped source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule {
+    pub selectors: Vec<String>,
+    pub span: std::ops::Range<usize>,
+}
+
+/// An at-rule: `@name prelude;` or `@name prelude { block }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtRule<'a> {
+    /// Lowercased name without the `@`.
+    pub name: String,
+    pub prelude: &'a str,
+    /// Block contents for block at-rules, None for statements like `@import`.
+    pub block: Option<&'a str>,
+}
+
+/// Parse the style rules of a stylesheet, descending into conditional group
+/// rules (`@media`, `@supports`, `@layer`, `@container`). Other at-rule blocks
+/// (`@font-face`, `@keyframes`, …) are skipped. `css` must be comment-stripped.
+pub fn style_rules(css: &str) -> Vec<StyleRule> {
+    let mut out = Vec::new();
+    walk(css, 0, css.len(), &mut |item| {
+        if let Item::Style(rule) = item {
+            out.push(rule);
+        }
+    });
+    out
+}
+
+/// All at-rules of a stylesheet, including those nested in conditional group
+/// rules. `css` must be comment-stripped.
+pub fn at_rules(css: &str) -> Vec<AtRule<'_>> {
+    let mut out = Vec::new();
+    walk(css, 0, css.len(), &mut |item| {
+        if let Item::At(rule) = item {
+            out.push(rule);
+        }
+    });
+    out
+}
+
+enum Item<'a> {
+    Style(StyleRule),
+    At(AtRule<'a>),
+}
+
+fn walk<'a>(css: &'a str, mut pos: usize, end: usize, f: &mut impl FnMut(Item<'a>)) {
+    let bytes = css.as_bytes();
+    while pos < end {
+        while pos < end && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b';') {
+            pos += 1;
+        }
+        if pos >= end {
+            break;
+        }
+        let start = pos;
+        let delim = find_top_level(css, pos, end, b"{;}").unwrap_or(end);
+        let prelude = css[start..delim].trim();
+        let at_name = prelude.strip_prefix('@').map(|at| {
+            at.split(|c: char| c.is_whitespace() || c == '(' || c == '"' || c == '\'')
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase()
+        });
+        if delim == end || bytes[delim] != b'{' {
+            // Statement at-rule (`@import`, `@charset`) or a stray brace.
+            if let Some(name) = at_name {
+                f(Item::At(AtRule {
+                    prelude: prelude[1 + name.len()..].trim(),
+                    name,
+                    block: None,
+                }));
+            }
+            pos = delim + 1;
+            continue;
+        }
+        let close = matching_brace(css, delim, end);
+        if let Some(name) = at_name {
+            if matches!(
+                name.as_str(),
+                "media" | "supports" | "layer" | "container" | "scope" | "starting-style"
+            ) {
+                walk(css, delim + 1, close, f);
+            }
+            f(Item::At(AtRule {
+                prelude: prelude[1 + name.len()..].trim(),
+                name,
+                block: Some(&css[delim + 1..close]),
+            }));
+        } else if !prelude.is_empty() {
+            f(Item::Style(StyleRule {
+                selectors: split_top_level(prelude, b','),
+                span: start..(close + 1).min(css.len()),
+            }));
+        }
+        pos = close + 1;
+    }
+}
+
+/// Index of the first byte in `needles` at nesting depth 0 (outside strings,
+/// parentheses and brackets) within `css[from..end]`.
+fn find_top_level(css: &str, from: usize, end: usize, needles: &[u8]) -> Option<usize> {
+    let bytes = css.as_bytes();
+    let mut depth = 0usize;
+    let mut quote: Option<u8> = None;
+    let mut i = from;
+    while i < end {
+        let b = bytes[i];
+        if let Some(q) = quote {
+            if b == b'\\' {
+                i += 1;
+            } else if b == q {
+                quote = None;
+            }
+        } else {
+            match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'\\' => i += 1,
+                _ if depth == 0 && needles.contains(&b) => return Some(i),
+                b'(' | b'[' => depth += 1,
+                b')' | b']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Index of the `}` closing the block opened at `open`, or `end` when the
+/// block is unterminated.
+fn matching_brace(css: &str, open: usize, end: usize) -> usize {
+    let mut depth = 0usize;
+    let mut pos = open;
+    while let Some(i) = find_top_level(css, pos, end, b"{}") {
+        if css.as_bytes()[i] == b'{' {
+            depth += 1;
+        } else {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+        pos = i + 1;
+    }
+    end
+}
+
+/// Split on a separator at nesting depth 0, trimming and dropping empty parts.
+pub fn split_top_level(s: &str, sep: u8) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut pos = 0;
+    while let Some(i) = find_top_level(s, pos, s.len(), &[sep]) {
+        parts.push(s[pos..i].trim().to_string());
+        pos = i + 1;
+    }
+    parts.push(s[pos..].trim().to_string());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Pseudo-classes that depend only on document structure. Every other
+/// pseudo-class reflects user or runtime state.
+const STRUCTURAL_PSEUDO_CLASSES: &[&str] = &[
+    "not",
+    "is",
+    "where",
+    "has",
+    "root",
+    "empty",
+    "first-child",
+    "last-child",
+    "only-child",
+    "first-of-type",
+    "last-of-type",
+    "only-of-type",
+    "nth-child",
+    "nth-last-child",
+    "nth-of-type",
+    "nth-last-of-type",
+];
+
+/// Rewrite a selector so it can be matched against static markup: state
+/// pseudo-classes (`:hover`, `:focus-visible`, `:checked`, …) and
+/// pseudo-elements are dropped, since whether they apply depends on
+/// interaction rather than the DOM. Returns None when dropping would change
+/// the meaning, i.e. a state pseudo-class inside `:not()`.
+pub fn static_selector(selector: &str) -> Option<String> {
+    let bytes = selector.as_bytes();
+    let mut out = String::with_capacity(selector.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'\\' => {
+                let ch_len = selector[i + 1..].chars().next().map_or(0, char::len_utf8);
+                out.push_str(&selector[i..i + 1 + ch_len]);
+                i += 1 + ch_len;
+            }
+            b'[' => {
+                let end = find_top_level(selector, i + 1, bytes.len(), b"]")
+                    .map_or(bytes.len(), |e| e + 1);
+                out.push_str(&selector[i..end]);
+                i = end;
+            }
+            b':' => {
+                let element = bytes.get(i + 1) == Some(&b':');
+                let name_start = i + if element { 2 } else { 1 };
+                let name_end = selector[name_start..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                    .map_or(bytes.len(), |n| name_start + n);
+                let name = selector[name_start..name_end].to_ascii_lowercase();
+                let (args, token_end) = if bytes.get(name_end) == Some(&b'(') {
+                    let close = find_top_level(selector, name_end + 1, bytes.len(), b")")
+                        .unwrap_or(bytes.len());
+                    (
+                        Some(&selector[name_end + 1..close]),
+                        (close + 1).min(bytes.len()),
+                    )
+                } else {
+                    (None, name_end)
+                };
+
+                if !element && STRUCTURAL_PSEUDO_CLASSES.contains(&name.as_str()) {
+                    out.push(':');
+                    out.push_str(&name);
+                    if let Some(args) = args {
+                        let rewritten = if name.starts_with("nth-") {
+                            args.to_string()
+                        } else {
+                            let list = split_top_level(args, b',');
+                            let mapped = list
+                                .iter()
+                                .map(|s| static_selector(s))
+                                .collect::<Option<Vec<_>>>()?;
+                            if name == "not" && mapped != list {
+                                return None;
+                            }
+                            mapped.join(", ")
+                        };
+                        out.push('(');
+                        out.push_str(&rewritten);
+                        out.push(')');
+                    }
+                } else {
+                    // Dropped: keep the compound non-empty (`:hover` → `*`).
+                    let before_empty = out
+                        .chars()
+                        .next_back()
+                        .is_none_or(|c| c.is_whitespace() || matches!(c, '>' | '+' | '~'));
+                    let after_empty = selector[token_end..]
+                        .chars()
+                        .next()
+                        .is_none_or(|c| c.is_whitespace() || matches!(c, '>' | '+' | '~'));
+                    if before_empty && after_empty {
+                        out.push('*');
+                    }
+                }
+                i = token_end;
+            }
+            _ => {
+                let ch_len = selector[i..].chars().next().map_or(1, char::len_utf8);
+                out.push_str(&selector[i..i + ch_len]);
+                i += ch_len;
+            }
+        }
+    }
+    Some(out.trim().to_string())
+}
+
+/// Class names used in a selector, with CSS escapes removed
+/// (`.md\:flex` → `md:flex`).
+pub fn selector_classes(selector: &str) -> Vec<String> {
+    let mut classes = Vec::new();
+    let mut chars = selector.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+            continue;
+        }
+        if c != '.' {
+            continue;
+        }
+        let mut name = String::new();
+        while let Some(&n) = chars.peek() {
+            if n == '\\' {
+                chars.next();
+                if let Some(escaped) = chars.next() {
+                    name.push(escaped);
+                }
+            } else if n.is_alphanumeric() || n == '-' || n == '_' || !n.is_ascii() {
+                name.push(n);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if !name.is_empty() {
+            classes.push(name);
+        }
+    }
+    classes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_rules_descend_into_media() {
+        let css = ".a{color:red}@media (min-width:600px){.b,.c:hover{margin:0}}@font-face{font-family:X}@keyframes k{from{opacity:0}}";
+        let rules = style_rules(css);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].selectors, vec![".a"]);
+        assert_eq!(&css[rules[0].span.clone()], ".a{color:red}");
+        assert_eq!(rules[1].selectors, vec![".b", ".c:hover"]);
+    }
+
+    #[test]
+    fn test_style_rules_skip_statements_and_strings() {
+        let css = "@import url(\"x;y.css\");a[title=\"{\"]{content:\"}\"}";
+        let rules = style_rules(css);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].selectors, vec!["a[title=\"{\"]"]);
+    }
+
+    #[test]
+    fn test_static_selector() {
+        assert_eq!(static_selector(".btn:hover").unwrap(), ".btn");
+        assert_eq!(static_selector("a::before").unwrap(), "a");
+        assert_eq!(static_selector(":focus-visible").unwrap(), "*");
+        assert_eq!(static_selector(".a :hover > b").unwrap(), ".a * > b");
+        assert_eq!(
+            static_selector("li:nth-child(2n+1):focus").unwrap(),
+            "li:nth-child(2n+1)"
+        );
+        assert_eq!(static_selector(":is(.a:hover, .b)").unwrap(), ":is(.a, .b)");
+        assert_eq!(static_selector(".a:not(.b)").unwrap(), ".a:not(.b)");
+        assert!(static_selector(".a:not(:hover)").is_none());
+    }
+
+    #[test]
+    fn test_selector_classes() {
+        assert_eq!(
+            selector_classes(".md\\:flex > .is-open"),
+            vec!["md:flex", "is-open"]
+        );
+    }
+
+    #[test]
+    fn test_font_face_sources_with_data_url() {
+        let css = "@font-face{src:url(data:font/woff2;base64,AAA) format('woff2'),url(/f.woff) format('woff')}";
+        assert_eq!(font_face_sources(css), vec!["/f.woff"]);
+    }
+
+    #[test]
+    fn test_imports_and_font_faces() {
+        let css = "@charset \"utf-8\";@layer base;@import url('a.css') screen;@import \"b.css\";@media print{@import 'ignored.css';}@import 'late.css';@font-face{font-family:'Inter';font-display:swap;src:url(i.woff2)}";
+        assert_eq!(imports(css), vec!["a.css", "b.css"]);
+        assert!(imports("body{color:red}@import 'late.css';").is_empty());
+        let faces = font_faces(css);
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].family.as_deref(), Some("Inter"));
+        assert_eq!(faces[0].font_display.as_deref(), Some("swap"));
+        assert_eq!(faces[0].sources, vec!["i.woff2"]);
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current time as Unix seconds.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Parse a W3C datetime (the ISO 8601 profile used by sitemaps and feeds)
+/// into Unix seconds: `YYYY`, `YYYY-MM`, `YYYY-MM-DD`, or a date with
+/// `Thh:mm`, `Thh:mm:ss` or `Thh:mm:ss.s` and a `Z` / `±hh:mm` offset.
+/// Date-only values are taken as midnight UTC.
+pub fn parse_w3c(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (date, time) = match value.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (value, None),
+    };
+
+    let mut parts = date.split('-');
+    let year = parse_digits(parts.next()?, 4)?;
+    let month = parts.next().map_or(Some(1), |m| parse_digits(m, 2))?;
+    let day = parts.next().map_or(Some(1), |d| parse_digits(d, 2))?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || day < 1 {
+        return None;
+    }
+    if day > days_in_month(year, month) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+
+    let Some(time) = time else {
+        return Some(days * 86_400);
+    };
+    // The offset is required once a time is given.
+    let (clock, offset) = if let Some(clock) = time.strip_suffix('Z') {
+        (clock, 0)
+    } else {
+        let sign_at = time.rfind(['+', '-'])?;
+        let (clock, tz) = time.split_at(sign_at);
+        let sign = if tz.starts_with('-') { -1 } else { 1 };
+        let (h, m) = tz[1..].split_once(':')?;
+        let (h, m) = (parse_digits(h, 2)?, parse_digits(m, 2)?);
+        if h > 23 || m > 59 {
+            return None;
+        }
+        (clock, sign * (h * 3600 + m * 60))
+    };
+
+    let mut fields = clock.split(':');
+    let hour = parse_digits(fields.next()?, 2)?;
+    let minute = parse_digits(fields.next()?, 2)?;
+    let second = match fields.next() {
+        Some(s) => {
+            let (whole, frac) = s.split_once('.').unwrap_or((s, "0"));
+            if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            parse_digits(whole, 2)?
+        }
+        None => 0,
+    };
+    if fields.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    Some(days * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Parse an RFC 822 / RFC 2822 date as used by RSS `pubDate`
+/// (`Mon, 01 Jan 2024 10:30:00 GMT`, `1 Jan 24 10:30 +0100`) into Unix
+/// seconds. The weekday is optional and not cross-checked.
+pub fn parse_rfc2822(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let value = match value.split_once(',') {
+        Some((_, rest)) => rest,
+        None => value,
+    };
+    let mut parts = value.split_whitespace();
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month = match parts.next()?.to_ascii_lowercase().as_str() {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    let year = parts.next()?;
+    let year: i64 = match year.len() {
+        4 => year.parse().ok()?,
+        2 => 1900 + year.parse::<i64>().ok()? + if year < "50" { 100 } else { 0 },
+        _ => return None,
+    };
+    if day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let mut clock = parts.next()?.split(':');
+    let hour = parse_digits(clock.next()?, 2)?;
+    let minute = parse_digits(clock.next()?, 2)?;
+    let second = clock.next().map_or(Some(0), |s| parse_digits(s, 2))?;
+    if clock.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let zone = parts.next()?;
+    let offset = match zone.to_ascii_uppercase().as_str() {
+        "GMT" | "UT" | "UTC" | "Z" => 0,
+        "EST" => -5 * 3600,
+        "EDT" => -4 * 3600,
+        "CST" => -6 * 3600,
+        "CDT" => -5 * 3600,
+        "MST" => -7 * 3600,
+        "MDT" => -6 * 3600,
+        "PST" => -8 * 3600,
+        "PDT" => -7 * 3600,
+        z if z.len() == 5 && (z.starts_with('+') || z.starts_with('-')) => {
+            // `get` keeps a multibyte character in the token from panicking.
+            let h = parse_digits(z.get(1..3)?, 2)?;
+            let m = parse_digits(z.get(3..5)?, 2)?;
+            let sign = if z.starts_with('-') { -1 } else { 1 };
+            sign * (h * 3600 + m * 60)
+        }
+        _ => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parse exactly `len` ASCII digits.
+fn parse_digits(s: &str, len: usize) -> Option<i64> {
+    (s.len() == len && s.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| s.parse().ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_w3c_formats() {
+        assert_eq!(parse_w3c("1970"), Some(0));
+        assert_eq!(parse_w3c("1970-01"), Some(0));
+        assert_eq!(parse_w3c("2024-03-01"), Some(1_709_251_200));
+        assert_eq!(parse_w3c("2024-03-01T10:30Z"), Some(1_709_289_000));
+        assert_eq!(parse_w3c("2024-03-01T12:30:00+02:00"), Some(1_709_289_000));
+        assert_eq!(parse_w3c("2024-03-01T10:30:00.123Z"), Some(1_709_289_000));
+    }
+
+    #[test]
+    fn test_parse_rfc2822() {
+        assert_eq!(
+            parse_rfc2822("Fri, 01 Mar 2024 10:30:00 GMT"),
+            Some(1_709_289_000)
+        );
+        assert_eq!(parse_rfc2822("1 Mar 2024 12:30 +0200"), Some(1_709_289_000));
+        assert_eq!(
+            parse_rfc2822("Fri, 01 Mar 24 05:30:00 EST"),
+            Some(1_709_289_000)
+        );
+        assert_eq!(parse_rfc2822("2024-03-01T10:30:00Z"), None);
+        assert_eq!(parse_rfc2822("Fri, 30 Feb 2024 10:30:00 GMT"), None);
+        assert_eq!(parse_rfc2822("Fri, 01 Mar 2024 10:30:00"), None);
+        assert_eq!(parse_rfc2822("Fri, 01 Mar 2024 10:30:00 +1é1"), None);
+        assert_eq!(parse_rfc2822("Fri, 01 Mar 2024 10:30:00 +é11"), None);
+    }
+
+    #[test]
+    fn test_parse_w3c_rejects_invalid() {
+        assert_eq!(parse_w3c("2024-02-30"), None);
+        assert_eq!(parse_w3c("2024-13-01"), None);
+        assert_eq!(parse_w3c("2024/03/01"), None);
+        assert_eq!(parse_w3c("2024-03-01T10:30"), None);
+        assert_eq!(parse_w3c("2024-03-01 10:30:00Z"), None);
+        assert_eq!(parse_w3c("March 1, 2024"), None);
+    }
+}
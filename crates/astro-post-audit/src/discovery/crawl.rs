@@ -0,0 +1,450 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use url::Url;
+
+use super::sitemap::{self, SitemapKind};
+use super::{PageExtractor, PageInfo, PathFilter, SiteIndex};
+use crate::config::Config;
+use crate::normalize;
+
+/// Redirect hops followed per URL before giving up.
+const MAX_REDIRECTS: usize = 10;
+/// Largest response body read (10 MB).
+const MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+/// Remote sitemap files fetched at most (indexes included).
+const MAX_SITEMAPS: usize = 50;
+/// Sitemaps tried at the server root when crawling.
+const SITEMAP_ENTRY_POINTS: &[&str] = &["/sitemap-index.xml", "/sitemap.xml"];
+
+/// HTTP details of a page fetched in crawl mode.
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpResponse {
+    /// Final URL after redirects.
+    pub url: String,
+    pub status: u16,
+    /// Redirect hops taken to reach `url`: (status, Location as sent).
+    pub redirects: Vec<(u16, String)>,
+    /// Response headers of the final response, names lowercased.
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpResponse {
+    /// First value of a header (case-insensitive name).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Every value of a header, for headers that may be sent more than once.
+    pub fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.headers
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// An internal URL the crawler could not load.
+#[derive(Debug, Clone)]
+pub struct CrawlError {
+    pub url: String,
+    /// Dist-relative path of the page that linked to `url`; None for seeds.
+    pub referrer: Option<String>,
+    /// HTTP status, or None for network errors.
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+/// Summary of a crawl.
+#[derive(Debug, Clone, Default)]
+pub struct CrawlReport {
+    pub start_url: String,
+    /// URLs requested (pages, redirects and errors).
+    pub fetched: usize,
+    pub errors: Vec<CrawlError>,
+    /// `max_pages` stopped the crawl with URLs still queued.
+    pub limit_reached: bool,
+}
+
+/// A response read by `fetch`, after following same-origin redirects.
+struct Fetched {
+    response: HttpResponse,
+    content_type: String,
+    body: String,
+}
+
+impl SiteIndex {
+    /// Build the index by crawling `config.crawl.url` (typically `astro
+    /// preview`) instead of walking dist, so SSR and hybrid routes are audited
+    /// too. Starts at `/` and the sitemap, follows internal links up to the
+    /// configured depth and page limits, and fills the same `PageInfo` as
+    /// `build`. `dist_path` is still used for assets, sitemaps and robots.txt.
+    pub fn crawl(
+        dist_path: &Path,
+        config: &Config,
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Self> {
+        let cfg = &config.crawl;
+        let start = cfg.url.as_deref().context("crawl.url is not set")?;
+        let start = Url::parse(start).with_context(|| format!("invalid crawl.url '{start}'"))?;
+
+        let dist_path = dist_path.canonicalize()?;
+        let filter = PathFilter::new(include, exclude)?;
+        let extractor = PageExtractor::new(config);
+        let norm = &config.url_normalization;
+        let base_url = config.site.base_url.clone();
+        let site_origin = base_url
+            .as_deref()
+            .and_then(|b| Url::parse(b).ok())
+            .map(|u| u.origin());
+
+        let agent = new_agent(cfg.timeout_ms);
+
+        // Map a URL on the crawl server or the production site to a URL on
+        // the crawl server, without fragment or query. None for other hosts.
+        let to_crawl_url = |url: &Url| -> Option<Url> {
+            let origin = url.origin();
+            if origin != start.origin() && Some(&origin) != site_origin.as_ref() {
+                return None;
+            }
+            let mut mapped = start.join(url.path()).ok()?;
+            mapped.set_query(None);
+            mapped.set_fragment(None);
+            Some(mapped)
+        };
+
+        let sitemaps = sitemap::discover(&dist_path, base_url.as_deref());
+        let sitemap_urls = sitemaps.urls();
+
+        let mut queue: VecDeque<(Url, usize, Option<String>)> = VecDeque::new();
+        queue.push_back((start.join("/")?, 0, None));
+        if cfg.follow_sitemap {
+            let mut seeds: Vec<String> = sitemap_urls.iter().cloned().collect();
+            seeds.sort();
+            seeds.extend(fetch_sitemap_urls(&agent, &start, &to_crawl_url));
+            for loc in seeds {
+                if let Some(url) = Url::parse(&loc).ok().as_ref().and_then(to_crawl_url) {
+                    queue.push_back((url, 0, None));
+                }
+            }
+        }
+
+        let mut report = CrawlReport {
+            start_url: start.to_string(),
+            ..Default::default()
+        };
+        let mut pages: Vec<PageInfo> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut html_total = 0;
+
+        while let Some((url, depth, referrer)) = queue.pop_front() {
+            let route = normalize::normalize_path(url.path(), norm);
+            if !seen.insert(route) {
+                continue;
+            }
+            if report.fetched >= cfg.max_pages {
+                report.limit_reached = true;
+                break;
+            }
+            report.fetched += 1;
+
+            let fetched = match fetch(&agent, &url, &to_crawl_url) {
+                Ok(f) => f,
+                Err(e) => {
+                    report.errors.push(CrawlError {
+                        url: url.to_string(),
+                        referrer,
+                        status: None,
+                        message: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let status = fetched.response.status;
+            if status >= 400 {
+                report.errors.push(CrawlError {
+                    url: url.to_string(),
+                    referrer,
+                    status: Some(status),
+                    message: format!("HTTP {status}"),
+                });
+                continue;
+            }
+            // Redirects off the site and non-HTML responses are not pages.
+            let Ok(final_url) = Url::parse(&fetched.response.url) else {
+                continue;
+            };
+            if !(200..300).contains(&status)
+                || !fetched.content_type.starts_with("text/html")
+                || final_url.origin() != start.origin()
+            {
+                continue;
+            }
+            if final_url.path() != url.path()
+                && !seen.insert(normalize::normalize_path(final_url.path(), norm))
+            {
+                continue;
+            }
+
+            html_total += 1;
+            let rel_path = rel_path_for(final_url.path());
+            let route = normalize::file_path_to_route(&rel_path, norm);
+            let mut page = extractor.extract(
+                rel_path.clone(),
+                dist_path.join(&rel_path),
+                route,
+                fetched.body,
+            );
+            page.http = Some(fetched.response);
+
+            if depth < cfg.max_depth {
+                for href in &page.anchor_hrefs {
+                    let Some(next) = final_url.join(href).ok().as_ref().and_then(to_crawl_url)
+                    else {
+                        continue;
+                    };
+                    if is_page_url(&next) {
+                        queue.push_back((next, depth + 1, Some(rel_path.clone())));
+                    }
+                }
+            }
+            if filter.matches(&rel_path) {
+                pages.push(page);
+            }
+        }
+
+        let html_matched = pages.len();
+        let mut route_to_index = HashMap::new();
+        for (i, page) in pages.iter().enumerate() {
+            route_to_index.insert(page.route.clone(), i);
+        }
+
+        Ok(Self {
+            pages,
+            route_to_index,
+            sitemap_urls,
+            sitemaps,
+            dist_path,
+            base_url,
+            html_total,
+            html_matched,
+            crawl: Some(report),
+        })
+    }
+}
+
+/// Agent for crawl requests: redirects are followed by hand so every hop is
+/// recorded, and error statuses are returned as responses.
+pub fn new_agent(timeout_ms: u64) -> ureq::Agent {
+    ureq::config::Config::builder()
+        .timeout_global(Some(Duration::from_millis(timeout_ms)))
+        .http_status_as_error(false)
+        .max_redirects(0)
+        .build()
+        .new_agent()
+}
+
+/// A single GET advertising gzip and brotli, without following redirects.
+/// Returns the response (headers as sent, Content-Encoding included) and the
+/// decompressed body.
+pub fn get(agent: &ureq::Agent, url: &str) -> Result<(HttpResponse, Vec<u8>)> {
+    let mut response = agent
+        .get(url)
+        .header("Accept-Encoding", "br, gzip")
+        .call()?;
+    let status = response.status().as_u16();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect();
+    let raw = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_BODY_BYTES)
+        .read_to_vec()?;
+    let http = HttpResponse {
+        url: url.to_string(),
+        status,
+        redirects: Vec::new(),
+        headers,
+    };
+    let body = decode_body(http.header("content-encoding"), raw)?;
+    Ok((http, body))
+}
+
+/// Undo a Content-Encoding (gzip, deflate, br), capped at `MAX_BODY_BYTES`.
+fn decode_body(encoding: Option<&str>, raw: Vec<u8>) -> Result<Vec<u8>> {
+    let reader: Box<dyn Read + '_> = match encoding.map(|e| e.trim().to_ascii_lowercase()) {
+        Some(e) if e == "gzip" || e == "x-gzip" => Box::new(GzDecoder::new(raw.as_slice())),
+        Some(e) if e == "deflate" => Box::new(ZlibDecoder::new(raw.as_slice())),
+        Some(e) if e == "br" => Box::new(brotli::Decompressor::new(raw.as_slice(), 4096)),
+        _ => return Ok(raw),
+    };
+    let mut body = Vec::new();
+    reader
+        .take(MAX_BODY_BYTES)
+        .read_to_end(&mut body)
+        .context("could not decompress response body")?;
+    Ok(body)
+}
+
+/// GET `url`, following redirects on the crawl server and recording each
+/// hop. A redirect leaving the server ends the chain with that 3xx response.
+fn fetch(
+    agent: &ureq::Agent,
+    url: &Url,
+    to_crawl_url: &dyn Fn(&Url) -> Option<Url>,
+) -> Result<Fetched> {
+    let mut current = url.clone();
+    let mut redirects = Vec::new();
+    loop {
+        let (mut response, body) = get(agent, current.as_str())?;
+        let status = response.status;
+        if let (300..=399, Some(location)) = (status, response.header("location")) {
+            if let Some(next) = current.join(location).ok().as_ref().and_then(to_crawl_url) {
+                redirects.push((status, location.to_string()));
+                if redirects.len() > MAX_REDIRECTS {
+                    anyhow::bail!("more than {MAX_REDIRECTS} redirects");
+                }
+                current = next;
+                continue;
+            }
+        }
+
+        let content_type = response
+            .header("content-type")
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let body = if (200..300).contains(&status) && content_type.starts_with("text/html") {
+            String::from_utf8_lossy(&body).into_owned()
+        } else {
+            String::new()
+        };
+        response.redirects = redirects;
+        return Ok(Fetched {
+            response,
+            content_type,
+            body,
+        });
+    }
+}
+
+/// Page URLs listed by the server's own sitemap, following indexes. Catches
+/// SSR routes that never made it into a sitemap file in dist.
+fn fetch_sitemap_urls(
+    agent: &ureq::Agent,
+    start: &Url,
+    to_crawl_url: &dyn Fn(&Url) -> Option<Url>,
+) -> Vec<String> {
+    let mut visited = HashSet::new();
+    let mut urls = Vec::new();
+    // The first root sitemap the server has is authoritative.
+    for entry_point in SITEMAP_ENTRY_POINTS {
+        let mut queue: VecDeque<Url> = start.join(entry_point).into_iter().collect();
+        while let Some(url) = queue.pop_front() {
+            if visited.len() >= MAX_SITEMAPS || !visited.insert(url.to_string()) {
+                continue;
+            }
+            let Some((kind, entries)) = fetch_sitemap(agent, &url) else {
+                continue;
+            };
+            for entry in entries {
+                match kind {
+                    SitemapKind::UrlSet => urls.push(entry.loc),
+                    SitemapKind::Index => {
+                        if let Some(child) =
+                            Url::parse(&entry.loc).ok().as_ref().and_then(to_crawl_url)
+                        {
+                            queue.push_back(child);
+                        }
+                    }
+                }
+            }
+        }
+        if !urls.is_empty() {
+            break;
+        }
+    }
+    urls
+}
+
+fn fetch_sitemap(
+    agent: &ureq::Agent,
+    url: &Url,
+) -> Option<(SitemapKind, Vec<sitemap::SitemapEntry>)> {
+    let (response, body) = get(agent, url.as_str()).ok()?;
+    if response.status != 200 {
+        return None;
+    }
+    sitemap::parse(&String::from_utf8_lossy(&body), url.path()).ok()
+}
+
+/// Whether a URL may be a page: no file extension, or .html/.htm. Assets are
+/// verified against dist by the link checks and need not be fetched.
+fn is_page_url(url: &Url) -> bool {
+    let last = url.path().rsplit('/').next().unwrap_or("");
+    match last.rsplit_once('.') {
+        Some((_, ext)) => ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"),
+        None => true,
+    }
+}
+
+/// Dist-style path for a crawled URL path, mirroring Astro's directory build
+/// format: "/" -> "index.html", "/blog/" -> "blog/index.html",
+/// "/about" -> "about.html".
+fn rel_path_for(url_path: &str) -> String {
+    let path = percent_decode_str(url_path).decode_utf8_lossy();
+    let path = path.trim_start_matches('/');
+    if path.is_empty() || path.ends_with('/') {
+        format!("{path}index.html")
+    } else if is_html_file(path) {
+        path.to_string()
+    } else {
+        format!("{path}.html")
+    }
+}
+
+fn is_html_file(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    lower.ends_with(".html") || lower.ends_with(".htm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rel_path_mirrors_directory_format() {
+        assert_eq!(rel_path_for("/"), "index.html");
+        assert_eq!(rel_path_for("/blog/"), "blog/index.html");
+        assert_eq!(rel_path_for("/about"), "about.html");
+        assert_eq!(rel_path_for("/legacy/page.html"), "legacy/page.html");
+        assert_eq!(rel_path_for("/caf%C3%A9/"), "café/index.html");
+    }
+
+    #[test]
+    fn assets_are_not_page_urls() {
+        let url = |p: &str| Url::parse(&format!("http://localhost:4321{p}")).unwrap();
+        assert!(is_page_url(&url("/blog/post/")));
+        assert!(is_page_url(&url("/about")));
+        assert!(is_page_url(&url("/old.html")));
+        assert!(!is_page_url(&url("/_astro/index.abc123.css")));
+        assert!(!is_page_url(&url("/images/logo.png")));
+    }
+}
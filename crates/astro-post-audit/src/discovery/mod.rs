@@ -1,14 +1,20 @@
 use anyhow::Result;
-use globset::{Glob, GlobSetBuilder};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use percent_encoding::percent_decode_str;
 use rayon::prelude::*;
 use scraper::{Html, Selector};
 use std::collections::{HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::config::Config;
 use crate::normalize;
 
+pub mod crawl;
+pub mod sitemap;
+
+use sitemap::SitemapSet;
+
 /// Metadata for a single HTML page (Send-safe: stores raw HTML, not parsed DOM).
 #[derive(Debug, Clone)]
 pub struct PageInfo {
@@ -47,6 +53,10 @@ pub struct PageInfo {
     pub heading_levels: Vec<u8>,
     /// Target URL of a `<meta http-equiv="refresh" content="0;url=...">`, if the page is a redirect.
     pub meta_refresh_target: Option<String>,
+    /// HTTP response details when the page was fetched in crawl mode.
+    pub http: Option<crawl::HttpResponse>,
+    /// Status code the page is served for, if it is an error page (404.html -> 404).
+    pub error_status: Option<u16>,
 }
 
 impl PageInfo {
@@ -54,6 +64,12 @@ impl PageInfo {
     pub fn parse_html(&self) -> Html {
         Html::parse_document(&self.html_content)
     }
+
+    /// Error pages (404.html, 500.html) are served at arbitrary URLs and are
+    /// exempt from indexing-related checks.
+    pub fn is_error_page(&self) -> bool {
+        self.error_status.is_some()
+    }
 }
 
 /// In-memory index of all HTML pages in the dist directory.
@@ -63,10 +79,10 @@ pub struct SiteIndex {
     pub pages: Vec<PageInfo>,
     /// Map from normalized route -> index into pages vec
     pub route_to_index: HashMap<String, usize>,
-    /// Sitemap entries (absolute URLs) if sitemap.xml exists
+    /// Sitemap entries (absolute URLs) from every discovered sitemap file
     pub sitemap_urls: HashSet<String>,
-    /// Parse error for sitemap.xml, if parsing failed.
-    pub sitemap_parse_error: Option<String>,
+    /// Discovered sitemap files (indexes followed), with per-URL source file.
+    pub sitemaps: SitemapSet,
     /// Path to the dist directory
     pub dist_path: PathBuf,
     /// Base URL (if provided)
@@ -75,6 +91,8 @@ pub struct SiteIndex {
     pub html_total: usize,
     /// HTML files remaining after include/exclude filtering (diagnostics).
     pub html_matched: usize,
+    /// Crawl results when pages were fetched from a server instead of dist.
+    pub crawl: Option<crawl::CrawlReport>,
 }
 
 impl SiteIndex {
@@ -85,27 +103,7 @@ impl SiteIndex {
         exclude: &[String],
     ) -> Result<Self> {
         let dist_path = dist_path.canonicalize()?;
-
-        // Build glob matchers for include/exclude
-        let include_set = if include.is_empty() {
-            None
-        } else {
-            let mut builder = GlobSetBuilder::new();
-            for pattern in include {
-                builder.add(Glob::new(pattern)?);
-            }
-            Some(builder.build()?)
-        };
-
-        let exclude_set = if exclude.is_empty() {
-            None
-        } else {
-            let mut builder = GlobSetBuilder::new();
-            for pattern in exclude {
-                builder.add(Glob::new(pattern)?);
-            }
-            Some(builder.build()?)
-        };
+        let filter = PathFilter::new(include, exclude)?;
 
         // Discover all HTML files first (for diagnostics), then apply include/exclude.
         let all_html: Vec<(String, PathBuf)> = WalkDir::new(&dist_path)
@@ -133,36 +131,12 @@ impl SiteIndex {
 
         let html_files: Vec<(String, PathBuf)> = all_html
             .into_iter()
-            .filter(|(rel, _)| {
-                // Apply include/exclude filters (patterns always use forward slashes)
-                if let Some(ref inc) = include_set {
-                    if !inc.is_match(rel) {
-                        return false;
-                    }
-                }
-                if let Some(ref exc) = exclude_set {
-                    if exc.is_match(rel) {
-                        return false;
-                    }
-                }
-                true
-            })
+            .filter(|(rel, _)| filter.matches(rel))
             .collect();
         let html_matched = html_files.len();
 
         let base_url = config.site.base_url.clone();
-        let norm_config = config.url_normalization.clone();
-        let canonical_sel = Selector::parse("link[rel='canonical']").ok();
-        let robots_sel = Selector::parse("meta[name='robots']").ok();
-        let anchor_sel = Selector::parse("a[href]").ok();
-        let id_sel = Selector::parse("[id]").ok();
-        let lang_sel = Selector::parse("html[lang]").ok();
-        let title_sel = Selector::parse("title").ok();
-        let desc_sel = Selector::parse("meta[name='description']").ok();
-        let viewport_sel = Selector::parse("meta[name='viewport']").ok();
-        let h1_sel = Selector::parse("h1").ok();
-        let headings_sel = Selector::parse("h1, h2, h3, h4, h5, h6").ok();
-        let meta_refresh_sel = Selector::parse("meta[http-equiv='refresh'][content]").ok();
+        let extractor = PageExtractor::new(config);
 
         // Read and pre-extract metadata in parallel
         let pages: Vec<PageInfo> = html_files
@@ -175,106 +149,8 @@ impl SiteIndex {
                         return None;
                     }
                 };
-
-                // Parse once and extract reusable metadata.
-                let html = Html::parse_document(&content);
-                let canonical = canonical_sel
-                    .as_ref()
-                    .and_then(|sel| extract_canonical(&html, sel));
-                let canonical_hrefs = canonical_sel
-                    .as_ref()
-                    .map(|sel| {
-                        html.select(sel)
-                            .filter_map(|el| el.value().attr("href"))
-                            .map(|s| s.to_string())
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                let noindex = robots_sel
-                    .as_ref()
-                    .is_some_and(|sel| has_noindex(&html, sel));
-                let anchor_hrefs = anchor_sel
-                    .as_ref()
-                    .map(|sel| {
-                        html.select(sel)
-                            .filter_map(|el| el.value().attr("href"))
-                            .map(|s| s.to_string())
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                let element_ids = id_sel
-                    .as_ref()
-                    .map(|sel| {
-                        html.select(sel)
-                            .filter_map(|el| el.value().attr("id"))
-                            .map(|s| s.to_string())
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                let html_lang = lang_sel
-                    .as_ref()
-                    .and_then(|sel| html.select(sel).next())
-                    .and_then(|el| el.value().attr("lang"))
-                    .map(|s| s.to_string());
-                let title_text = title_sel
-                    .as_ref()
-                    .and_then(|sel| html.select(sel).next())
-                    .map(|el| el.text().collect::<String>().trim().to_string());
-                let meta_description = desc_sel
-                    .as_ref()
-                    .and_then(|sel| html.select(sel).next())
-                    .and_then(|el| el.value().attr("content"))
-                    .map(|s| s.trim().to_string());
-                let has_viewport = viewport_sel
-                    .as_ref()
-                    .is_some_and(|sel| html.select(sel).next().is_some());
-                let h1_count = h1_sel
-                    .as_ref()
-                    .map(|sel| html.select(sel).count())
-                    .unwrap_or(0);
-                let heading_levels = headings_sel
-                    .as_ref()
-                    .map(|sel| {
-                        html.select(sel)
-                            .filter_map(|el| {
-                                el.value()
-                                    .name()
-                                    .strip_prefix('h')
-                                    .and_then(|n| n.parse::<u8>().ok())
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                let meta_refresh_target = meta_refresh_sel
-                    .as_ref()
-                    .and_then(|sel| html.select(sel).next())
-                    .and_then(|el| el.value().attr("content"))
-                    .and_then(parse_meta_refresh_target);
-
-                let route = normalize::file_path_to_route(rel, &norm_config);
-                let absolute_url = base_url
-                    .as_ref()
-                    .and_then(|base| normalize::to_absolute(&route, base));
-
-                Some(PageInfo {
-                    rel_path: rel.clone(),
-                    abs_path: abs.clone(),
-                    route,
-                    absolute_url,
-                    html_content: content,
-                    canonical,
-                    noindex,
-                    canonical_hrefs,
-                    anchor_hrefs,
-                    element_ids,
-                    html_lang,
-                    title_text,
-                    meta_description,
-                    has_viewport,
-                    h1_count,
-                    heading_levels,
-                    meta_refresh_target,
-                })
+                let route = normalize::file_path_to_route(rel, &config.url_normalization);
+                Some(extractor.extract(rel.clone(), abs.clone(), route, content))
             })
             .collect();
 
@@ -284,27 +160,20 @@ impl SiteIndex {
             route_to_index.insert(page.route.clone(), i);
         }
 
-        // Parse sitemap
-        let sitemap_path = dist_path.join("sitemap.xml");
-        let (sitemap_urls, sitemap_parse_error): (HashSet<String>, Option<String>) =
-            if sitemap_path.exists() {
-                match parse_sitemap(&sitemap_path) {
-                    Ok(urls) => (urls.into_iter().collect(), None),
-                    Err(e) => (HashSet::new(), Some(e.to_string())),
-                }
-            } else {
-                (HashSet::new(), None)
-            };
+        // Discover sitemaps (robots.txt, sitemap indexes, .xml.gz)
+        let sitemaps = sitemap::discover(&dist_path, base_url.as_deref());
+        let sitemap_urls = sitemaps.urls();
 
         Ok(Self {
             pages,
             route_to_index,
             sitemap_urls,
-            sitemap_parse_error,
+            sitemaps,
             dist_path,
             base_url,
             html_total,
             html_matched,
+            crawl: None,
         })
     }
 
@@ -313,10 +182,240 @@ impl SiteIndex {
         self.route_to_index.contains_key(route)
     }
 
+    /// The sitemap file (dist-relative) that lists `url`, for `Finding.file`.
+    pub fn sitemap_source(&self, url: &str) -> &str {
+        self.sitemaps
+            .url_sources
+            .get(url)
+            .map_or("sitemap.xml", String::as_str)
+    }
+
     /// Check if a file (relative path) exists in dist.
     pub fn file_exists(&self, rel_path: &str) -> bool {
         self.dist_path.join(rel_path).exists()
     }
+
+    /// Resolve a local asset reference (`src`, `href`, CSS `url()`) to a path
+    /// inside dist. `from_rel` is the dist-relative path of the referencing
+    /// file, used for relative references. Returns None for external,
+    /// protocol-relative and data URLs, and for `..` references that leave
+    /// dist.
+    pub fn resolve_local_asset(&self, reference: &str, from_rel: &str) -> Option<PathBuf> {
+        let reference = reference.trim();
+        if reference.starts_with("http://")
+            || reference.starts_with("https://")
+            || reference.starts_with("//")
+            || reference.starts_with("data:")
+            || reference.starts_with('#')
+        {
+            return None;
+        }
+        let clean = normalize::strip_fragment_and_query(reference);
+        if clean.is_empty() {
+            return None;
+        }
+        let decoded = percent_decode_str(clean).decode_utf8_lossy();
+        let joined = if let Some(abs) = decoded.strip_prefix('/') {
+            self.dist_path.join(abs)
+        } else {
+            let dir = Path::new(from_rel).parent().unwrap_or(Path::new(""));
+            self.dist_path.join(dir).join(decoded.as_ref())
+        };
+        let path = collapse_path(&joined);
+        path.starts_with(&self.dist_path).then_some(path)
+    }
+
+    /// Dist-relative path (forward slashes) of a file inside dist, as used in
+    /// `Finding.file`.
+    pub fn dist_rel_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.dist_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// Include/exclude globs matched against dist-relative paths.
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let build = |patterns: &[String]| -> Result<Option<GlobSet>> {
+            if patterns.is_empty() {
+                return Ok(None);
+            }
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                builder.add(Glob::new(pattern)?);
+            }
+            Ok(Some(builder.build()?))
+        };
+        Ok(Self {
+            include: build(include)?,
+            exclude: build(exclude)?,
+        })
+    }
+
+    /// Patterns always use forward slashes.
+    pub fn matches(&self, rel_path: &str) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|inc| inc.is_match(rel_path))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|exc| exc.is_match(rel_path))
+    }
+}
+
+/// Pre-parsed selectors that turn raw HTML into a `PageInfo`.
+pub struct PageExtractor {
+    base_url: Option<String>,
+    canonical_sel: Selector,
+    robots_sel: Selector,
+    anchor_sel: Selector,
+    id_sel: Selector,
+    lang_sel: Selector,
+    title_sel: Selector,
+    desc_sel: Selector,
+    viewport_sel: Selector,
+    h1_sel: Selector,
+    headings_sel: Selector,
+    meta_refresh_sel: Selector,
+}
+
+impl PageExtractor {
+    pub fn new(config: &Config) -> Self {
+        let sel = |css: &str| Selector::parse(css).unwrap();
+        Self {
+            base_url: config.site.base_url.clone(),
+            canonical_sel: sel("link[rel='canonical']"),
+            robots_sel: sel("meta[name='robots']"),
+            anchor_sel: sel("a[href]"),
+            id_sel: sel("[id]"),
+            lang_sel: sel("html[lang]"),
+            title_sel: sel("title"),
+            desc_sel: sel("meta[name='description']"),
+            viewport_sel: sel("meta[name='viewport']"),
+            h1_sel: sel("h1"),
+            headings_sel: sel("h1, h2, h3, h4, h5, h6"),
+            meta_refresh_sel: sel("meta[http-equiv='refresh'][content]"),
+        }
+    }
+
+    /// Parse once and extract reusable metadata.
+    pub fn extract(
+        &self,
+        rel_path: String,
+        abs_path: PathBuf,
+        route: String,
+        content: String,
+    ) -> PageInfo {
+        let html = Html::parse_document(&content);
+        let attrs = |sel: &Selector, attr: &str| -> Vec<String> {
+            html.select(sel)
+                .filter_map(|el| el.value().attr(attr))
+                .map(|s| s.to_string())
+                .collect()
+        };
+        let canonical = extract_canonical(&html, &self.canonical_sel);
+        let canonical_hrefs = attrs(&self.canonical_sel, "href");
+        let noindex = has_noindex(&html, &self.robots_sel);
+        let anchor_hrefs = attrs(&self.anchor_sel, "href");
+        let element_ids = attrs(&self.id_sel, "id").into_iter().collect();
+        let html_lang = html
+            .select(&self.lang_sel)
+            .next()
+            .and_then(|el| el.value().attr("lang"))
+            .map(|s| s.to_string());
+        let title_text = html
+            .select(&self.title_sel)
+            .next()
+            .map(|el| el.text().collect::<String>().trim().to_string());
+        let meta_description = html
+            .select(&self.desc_sel)
+            .next()
+            .and_then(|el| el.value().attr("content"))
+            .map(|s| s.trim().to_string());
+        let has_viewport = html.select(&self.viewport_sel).next().is_some();
+        let h1_count = html.select(&self.h1_sel).count();
+        let heading_levels = html
+            .select(&self.headings_sel)
+            .filter_map(|el| {
+                el.value()
+                    .name()
+                    .strip_prefix('h')
+                    .and_then(|n| n.parse::<u8>().ok())
+            })
+            .collect();
+        let meta_refresh_target = html
+            .select(&self.meta_refresh_sel)
+            .next()
+            .and_then(|el| el.value().attr("content"))
+            .and_then(parse_meta_refresh_target);
+
+        let absolute_url = self
+            .base_url
+            .as_ref()
+            .and_then(|base| normalize::to_absolute(&route, base));
+
+        PageInfo {
+            abs_path,
+            route,
+            absolute_url,
+            html_content: content,
+            canonical,
+            noindex,
+            canonical_hrefs,
+            anchor_hrefs,
+            element_ids,
+            html_lang,
+            title_text,
+            meta_description,
+            has_viewport,
+            h1_count,
+            heading_levels,
+            meta_refresh_target,
+            http: None,
+            error_status: error_status(&rel_path),
+            rel_path,
+        }
+    }
+}
+
+/// Status code for an error page file: "404.html" or "500.html" at the dist
+/// root or under a locale prefix such as "de/404.html". A route segment like
+/// "blog/404/" (page 404 of a `paginate()` series) is an ordinary page.
+pub fn error_status(rel_path: &str) -> Option<u16> {
+    let name = match rel_path.rsplit_once('/') {
+        Some((prefix, name)) if normalize::is_locale_token(prefix) => name,
+        Some(_) => return None,
+        None => rel_path,
+    };
+    match name {
+        "404.html" | "404.htm" => Some(404),
+        "500.html" | "500.htm" => Some(500),
+        _ => None,
+    }
+}
+
+/// Lexically collapse `.` and `..` components so the same file referenced from
+/// different directories yields the same path.
+fn collapse_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
 }
 
 /// Parse the redirect target from a `meta http-equiv="refresh"` content value
@@ -349,35 +448,37 @@ fn has_noindex(html: &Html, sel: &Selector) -> bool {
     })
 }
 
-fn parse_sitemap(path: &Path) -> Result<Vec<String>> {
-    use quick_xml::events::Event;
-    use quick_xml::reader::Reader;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let content = std::fs::read_to_string(path)?;
-    let mut reader = Reader::from_str(&content);
-
-    let mut urls = Vec::new();
-    let mut in_loc = false;
-
-    loop {
-        match reader.read_event() {
-            Ok(Event::Start(ref e)) if e.name().as_ref() == b"loc" => {
-                in_loc = true;
-            }
-            Ok(Event::Text(ref e)) if in_loc => {
-                if let Ok(text) = e.unescape() {
-                    urls.push(text.trim().to_string());
-                }
-                in_loc = false;
-            }
-            Ok(Event::End(ref e)) if e.name().as_ref() == b"loc" => {
-                in_loc = false;
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => anyhow::bail!("invalid sitemap.xml: {e}"),
-            _ => {}
-        }
+    #[test]
+    fn local_assets_stay_inside_dist() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let index = SiteIndex::build(dir.path(), &Config::default(), &[], &[]).unwrap();
+        assert_eq!(
+            index.resolve_local_asset("../img/a.png", "blog/post/index.html"),
+            Some(index.dist_path.join("blog/img/a.png"))
+        );
+        assert_eq!(
+            index.resolve_local_asset("/../../etc/passwd", "index.html"),
+            None
+        );
+        assert_eq!(
+            index.resolve_local_asset("../../../etc/passwd", "blog/index.html"),
+            None
+        );
     }
 
-    Ok(urls)
+    #[test]
+    fn error_pages_are_root_or_locale_files() {
+        assert_eq!(error_status("404.html"), Some(404));
+        assert_eq!(error_status("500.html"), Some(500));
+        assert_eq!(error_status("de/404.html"), Some(404));
+        assert_eq!(error_status("pt-br/404.html"), Some(404));
+        assert_eq!(error_status("404/index.html"), None);
+        assert_eq!(error_status("blog/404/index.html"), None);
+        assert_eq!(error_status("products/450/index.html"), None);
+        assert_eq!(error_status("docs/api/404.html"), None);
+    }
 }
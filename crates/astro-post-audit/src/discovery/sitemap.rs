@@ -0,0 +1,392 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use percent_encoding::percent_decode_str;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use url::Url;
+
+use crate::normalize;
+
+/// Sitemap protocol limit: URLs (or child sitemaps) per file.
+pub const MAX_ENTRIES_PER_FILE: usize = 50_000;
+/// Sitemap protocol limit: uncompressed size per file.
+pub const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Sitemaps looked for at the dist root when robots.txt lists none.
+/// `@astrojs/sitemap` writes `sitemap-index.xml` + `sitemap-N.xml`.
+const DEFAULT_ENTRY_POINTS: &[&str] = &[
+    "sitemap-index.xml",
+    "sitemap_index.xml",
+    "sitemap.xml",
+    "sitemap-index.xml.gz",
+    "sitemap.xml.gz",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SitemapKind {
+    /// `<sitemapindex>`: lists other sitemap files.
+    Index,
+    /// `<urlset>`: lists page URLs.
+    UrlSet,
+}
+
+/// One sitemap file reached during discovery.
+#[derive(Debug, Clone)]
+pub struct SitemapFile {
+    /// Path relative to dist (e.g., "sitemap-0.xml")
+    pub rel_path: String,
+    /// None if the file could not be parsed.
+    pub kind: Option<SitemapKind>,
+    /// The index (or "robots.txt") that referenced this file; None for
+    /// files found at the conventional root locations.
+    pub parent: Option<String>,
+    /// Number of `<url>` or `<sitemap>` entries.
+    pub entry_count: usize,
+    /// Uncompressed size in bytes.
+    pub size: u64,
+    pub parse_error: Option<String>,
+}
+
+/// A `<url>` entry of a urlset (or `<sitemap>` entry of an index).
+#[derive(Debug, Clone, Default)]
+pub struct SitemapEntry {
+    pub loc: String,
+    /// Dist-relative sitemap file the entry came from.
+    pub source: String,
+    pub lastmod: Option<String>,
+    pub changefreq: Option<String>,
+    pub priority: Option<String>,
+    /// `xhtml:link rel="alternate"` pairs: (hreflang, href).
+    pub alternates: Vec<(String, String)>,
+    /// `image:image/image:loc` values.
+    pub images: Vec<String>,
+    pub videos: Vec<SitemapVideo>,
+}
+
+impl SitemapEntry {
+    fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A `video:video` block of a sitemap entry.
+#[derive(Debug, Clone, Default)]
+pub struct SitemapVideo {
+    pub thumbnail_loc: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content_loc: Option<String>,
+    pub player_loc: Option<String>,
+}
+
+/// All sitemaps reachable from robots.txt and the conventional root files.
+#[derive(Debug, Clone, Default)]
+pub struct SitemapSet {
+    pub files: Vec<SitemapFile>,
+    /// Every `<url>` entry, in discovery order.
+    pub entries: Vec<SitemapEntry>,
+    /// Page URL -> dist-relative sitemap file that listed it first.
+    pub url_sources: HashMap<String, String>,
+    /// References to sitemap files that do not exist in dist: (referencing
+    /// file, referenced URL or path).
+    pub missing: Vec<(String, String)>,
+}
+
+impl SitemapSet {
+    /// Page URLs from every urlset, deduplicated.
+    pub fn urls(&self) -> HashSet<String> {
+        self.url_sources.keys().cloned().collect()
+    }
+}
+
+/// Find and parse every sitemap in dist: `Sitemap:` lines in robots.txt,
+/// then the conventional root files, following sitemap indexes recursively.
+/// Only references on the site's own host (per `base_url`, if set) are
+/// followed; gzipped files are decompressed.
+pub fn discover(dist_path: &Path, base_url: Option<&str>) -> SitemapSet {
+    let own_host = base_url
+        .and_then(|b| Url::parse(b).ok())
+        .and_then(|u| u.host_str().map(str::to_string));
+
+    let mut queue: VecDeque<(String, Option<String>)> = VecDeque::new();
+    let mut set = SitemapSet::default();
+
+    if let Ok(robots) = std::fs::read_to_string(dist_path.join("robots.txt")) {
+        for line in robots.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            if !key.trim().eq_ignore_ascii_case("sitemap") {
+                continue;
+            }
+            if let Some(rel) = local_rel_path(value.trim(), own_host.as_deref()) {
+                queue.push_back((rel, Some("robots.txt".to_string())));
+            }
+        }
+    }
+    if queue.is_empty() {
+        // Root files are only a fallback: a site whose robots.txt points at
+        // sitemap-index.xml may still ship a stale sitemap.xml.
+        if let Some(name) = DEFAULT_ENTRY_POINTS
+            .iter()
+            .find(|name| dist_path.join(name).is_file())
+        {
+            queue.push_back((name.to_string(), None));
+        }
+    }
+
+    let mut visited = HashSet::new();
+    while let Some((rel, parent)) = queue.pop_front() {
+        if !visited.insert(rel.clone()) {
+            continue;
+        }
+        let path = dist_path.join(&rel);
+        if !path.is_file() {
+            if let Some(parent) = parent {
+                set.missing.push((parent, rel));
+            }
+            continue;
+        }
+
+        let mut file = SitemapFile {
+            rel_path: rel.clone(),
+            kind: None,
+            parent,
+            entry_count: 0,
+            size: 0,
+            parse_error: None,
+        };
+        match read_limited(&path) {
+            Err(e) => file.parse_error = Some(e.to_string()),
+            Ok((_, size)) if size > MAX_FILE_BYTES => file.size = size,
+            Ok((content, size)) => {
+                file.size = size;
+                match parse(&content, &rel) {
+                    Ok((kind, entries)) => {
+                        file.kind = Some(kind);
+                        file.entry_count = entries.len();
+                        for entry in entries {
+                            match kind {
+                                SitemapKind::UrlSet => {
+                                    set.url_sources
+                                        .entry(entry.loc.clone())
+                                        .or_insert_with(|| rel.clone());
+                                    set.entries.push(entry);
+                                }
+                                SitemapKind::Index => {
+                                    if let Some(child) =
+                                        local_rel_path(&entry.loc, own_host.as_deref())
+                                    {
+                                        queue.push_back((child, Some(rel.clone())));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => file.parse_error = Some(e.to_string()),
+                }
+            }
+        }
+        set.files.push(file);
+    }
+
+    set
+}
+
+/// Map a sitemap reference (absolute URL or root-relative path) to a
+/// dist-relative file path. None for other hosts and for paths that climb
+/// out of dist with `..`.
+fn local_rel_path(reference: &str, own_host: Option<&str>) -> Option<String> {
+    let path = match Url::parse(reference) {
+        Ok(url) => {
+            if let (Some(own), Some(host)) = (own_host, url.host_str()) {
+                if !own.eq_ignore_ascii_case(host) {
+                    return None;
+                }
+            }
+            url.path().to_string()
+        }
+        Err(_) => normalize::strip_fragment_and_query(reference).to_string(),
+    };
+    let decoded = percent_decode_str(&path).decode_utf8_lossy();
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in decoded.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment),
+        }
+    }
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+/// Read a sitemap, decompressing `.gz`, stopping just past the size limit.
+/// Returns the content and its (possibly truncated) uncompressed size.
+fn read_limited(path: &Path) -> Result<(String, u64)> {
+    let file = std::fs::File::open(path)?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|e| e == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut bytes = Vec::new();
+    reader.take(MAX_FILE_BYTES + 1).read_to_end(&mut bytes)?;
+    let size = bytes.len() as u64;
+    Ok((String::from_utf8(bytes)?, size))
+}
+
+/// Parse a `<urlset>` or `<sitemapindex>` into its entries. For an index,
+/// only `loc` is set on each entry.
+pub fn parse(content: &str, source: &str) -> Result<(SitemapKind, Vec<SitemapEntry>)> {
+    let mut reader = Reader::from_str(content);
+    let mut kind = None;
+    let mut entries = Vec::new();
+    // Local names of the open elements: [urlset, url, image, loc].
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    let mut entry: Option<SitemapEntry> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => {
+                let name = e.local_name().as_ref().to_vec();
+                match (stack.len(), name.as_slice()) {
+                    (0, b"urlset") => kind = Some(SitemapKind::UrlSet),
+                    (0, b"sitemapindex") => kind = Some(SitemapKind::Index),
+                    (1, b"url" | b"sitemap") => entry = Some(SitemapEntry::new(source)),
+                    (2, b"video") => {
+                        if let Some(entry) = entry.as_mut() {
+                            entry.videos.push(SitemapVideo::default());
+                        }
+                    }
+                    (2, b"link") => {
+                        if let Some(entry) = entry.as_mut() {
+                            push_alternate(entry, e);
+                        }
+                    }
+                    _ => {}
+                }
+                stack.push(name);
+                text.clear();
+            }
+            Ok(Event::Empty(ref e)) if stack.len() == 2 && e.local_name().as_ref() == b"link" => {
+                if let Some(entry) = entry.as_mut() {
+                    push_alternate(entry, e);
+                }
+            }
+            Ok(Event::Text(ref e)) => {
+                if let Ok(t) = e.unescape() {
+                    text.push_str(&t);
+                }
+            }
+            Ok(Event::CData(ref e)) => text.push_str(&String::from_utf8_lossy(e.as_ref())),
+            Ok(Event::End(_)) => {
+                let value = text.trim().to_string();
+                text.clear();
+                if let Some(entry) = entry.as_mut() {
+                    assign_field(entry, &stack, value);
+                }
+                let closed = stack.pop();
+                if stack.len() == 1 && matches!(closed.as_deref(), Some(b"url" | b"sitemap")) {
+                    if let Some(entry) = entry.take().filter(|e| !e.loc.is_empty()) {
+                        entries.push(entry);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => anyhow::bail!("invalid XML: {e}"),
+            _ => {}
+        }
+    }
+
+    match kind {
+        Some(kind) => Ok((kind, entries)),
+        None => anyhow::bail!("root element is neither <urlset> nor <sitemapindex>"),
+    }
+}
+
+/// Store the text of the element that just closed (top of `stack`).
+fn assign_field(entry: &mut SitemapEntry, stack: &[Vec<u8>], value: String) {
+    if value.is_empty() {
+        return;
+    }
+    let path: Vec<&[u8]> = stack.iter().skip(2).map(Vec::as_slice).collect();
+    match path.as_slice() {
+        [b"loc"] => entry.loc = value,
+        [b"lastmod"] => entry.lastmod = Some(value),
+        [b"changefreq"] => entry.changefreq = Some(value),
+        [b"priority"] => entry.priority = Some(value),
+        [b"image", b"loc"] => entry.images.push(value),
+        [b"video", field] => {
+            if let Some(video) = entry.videos.last_mut() {
+                match *field {
+                    b"thumbnail_loc" => video.thumbnail_loc = Some(value),
+                    b"title" => video.title = Some(value),
+                    b"description" => video.description = Some(value),
+                    b"content_loc" => video.content_loc = Some(value),
+                    b"player_loc" => video.player_loc = Some(value),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_alternate(entry: &mut SitemapEntry, e: &BytesStart) {
+    let mut rel = None;
+    let mut hreflang = None;
+    let mut href = None;
+    for attr in e.attributes().flatten() {
+        let value = attr
+            .unescape_value()
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default();
+        match attr.key.local_name().as_ref() {
+            b"rel" => rel = Some(value),
+            b"hreflang" => hreflang = Some(value),
+            b"href" => href = Some(value),
+            _ => {}
+        }
+    }
+    if rel.as_deref() == Some("alternate") {
+        if let (Some(lang), Some(href)) = (hreflang, href) {
+            entry.alternates.push((lang, href));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_paths_stay_inside_dist() {
+        let host = Some("example.com");
+        assert_eq!(
+            local_rel_path("https://example.com/sitemap-0.xml", host).as_deref(),
+            Some("sitemap-0.xml")
+        );
+        assert_eq!(local_rel_path("https://other.com/sitemap.xml", host), None);
+        assert_eq!(
+            local_rel_path("/maps/sitemap%20a.xml?v=1", host).as_deref(),
+            Some("maps/sitemap a.xml")
+        );
+        assert_eq!(
+            local_rel_path("maps/../sitemap.xml", host).as_deref(),
+            Some("sitemap.xml")
+        );
+        assert_eq!(local_rel_path("../../x.xml", host), None);
+        assert_eq!(local_rel_path("/a/%2e%2e/%2e%2e/x.xml", host), None);
+    }
+}
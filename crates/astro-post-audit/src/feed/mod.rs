@@ -0,0 +1,391 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use scraper::Selector;
+use serde_json::Value;
+
+use crate::datetime;
+use crate::discovery::SiteIndex;
+use crate::normalize;
+
+/// Feed locations checked at the dist root when no page links a feed.
+/// `@astrojs/rss` endpoints are usually `src/pages/rss.xml.js`.
+const COMMON_FEED_PATHS: &[&str] = &[
+    "rss.xml",
+    "feed.xml",
+    "atom.xml",
+    "feed.json",
+    "index.xml",
+    "blog/rss.xml",
+];
+
+const FEED_LINK_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "RSS",
+            FeedFormat::Atom => "Atom",
+            FeedFormat::Json => "JSON Feed",
+        }
+    }
+}
+
+/// A parsed RSS 2.0, Atom or JSON Feed document.
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub format: FeedFormat,
+    pub title: Option<String>,
+    /// Channel `<link>`, Atom alternate link or JSON `home_page_url`.
+    pub home_url: Option<String>,
+    /// `atom:link rel="self"`, Atom self link or JSON `feed_url`.
+    pub self_url: Option<String>,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FeedItem {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    /// RSS `<guid>`, Atom `<id>` or JSON `id`.
+    pub id: Option<String>,
+    /// Raw publication date (RSS `pubDate`/`dc:date`, Atom `published` or
+    /// `updated`, JSON `date_published`).
+    pub date: Option<String>,
+    /// The date is an RSS `pubDate` (RFC 822) rather than a W3C datetime.
+    pub date_is_rfc822: bool,
+}
+
+impl FeedItem {
+    /// Publication date as Unix seconds, if present and valid for its element.
+    pub fn timestamp(&self) -> Option<i64> {
+        let date = self.date.as_deref()?;
+        if self.date_is_rfc822 {
+            datetime::parse_rfc2822(date)
+        } else {
+            datetime::parse_w3c(date)
+        }
+    }
+}
+
+/// A feed file found in dist.
+#[derive(Debug)]
+pub struct FeedFile {
+    /// Path relative to dist (e.g., "rss.xml")
+    pub rel_path: String,
+    /// First page that advertises the feed via `<link rel="alternate">`.
+    pub linked_from: Option<String>,
+    pub feed: Result<Feed, String>,
+}
+
+/// Find local feeds: `<link rel="alternate">` with a feed type on any page,
+/// plus the common root paths, each parsed once. Absolute hrefs on
+/// `site.base_url`'s origin are mapped into dist. Links to feeds missing from
+/// dist are returned with an error.
+pub fn discover(index: &SiteIndex) -> Vec<FeedFile> {
+    let sel = Selector::parse("link[rel~='alternate'][type][href]").unwrap();
+    let mut found: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut pages: Vec<_> = index.pages.iter().collect();
+    pages.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    for page in pages {
+        let html = page.parse_html();
+        for el in html.select(&sel) {
+            let attrs = el.value();
+            let ty = attrs.attr("type").unwrap_or("").trim().to_ascii_lowercase();
+            if !FEED_LINK_TYPES.contains(&ty.as_str()) {
+                continue;
+            }
+            let mut href = attrs.attr("href").unwrap_or("").trim().to_string();
+            // Absolute links on the site's own origin point into dist too.
+            if href.contains("://") || href.starts_with("//") {
+                let base = index.base_url.as_deref();
+                if !normalize::is_internal(&href, base) {
+                    continue;
+                }
+                match normalize::resolve_href(&href, &page.route, base) {
+                    Some(path) => href = path,
+                    None => continue,
+                }
+            }
+            let Some(path) = index.resolve_local_asset(&href, &page.rel_path) else {
+                continue;
+            };
+            found
+                .entry(index.dist_rel_path(&path))
+                .or_insert_with(|| Some(page.rel_path.clone()));
+        }
+    }
+    for rel in COMMON_FEED_PATHS {
+        if index.dist_path.join(rel).is_file() {
+            found.entry(rel.to_string()).or_insert(None);
+        }
+    }
+
+    found
+        .into_iter()
+        .map(|(rel_path, linked_from)| {
+            let feed = std::fs::read_to_string(index.dist_path.join(&rel_path))
+                .map_err(|e| e.to_string())
+                .and_then(|content| parse(&content).map_err(|e| e.to_string()));
+            FeedFile {
+                rel_path,
+                linked_from,
+                feed,
+            }
+        })
+        .collect()
+}
+
+/// Parse a feed, detecting the format from its content.
+pub fn parse(content: &str) -> Result<Feed> {
+    let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('{') {
+        parse_json(trimmed)
+    } else {
+        parse_xml(trimmed)
+    }
+}
+
+fn parse_json(content: &str) -> Result<Feed> {
+    let json: Value = serde_json::from_str(content)?;
+    let is_json_feed = json
+        .get("version")
+        .and_then(Value::as_str)
+        .is_some_and(|v| v.starts_with("https://jsonfeed.org/version/"));
+    if !is_json_feed {
+        anyhow::bail!("JSON document has no JSON Feed 'version'");
+    }
+    let text = |v: &Value, key: &str| {
+        v.get(key)
+            .and_then(Value::as_str)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let items = json
+        .get("items")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|item| FeedItem {
+            title: text(item, "title"),
+            link: text(item, "url"),
+            id: item.get("id").map(|id| match id {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }),
+            date: text(item, "date_published"),
+            date_is_rfc822: false,
+        })
+        .collect();
+    Ok(Feed {
+        format: FeedFormat::Json,
+        title: text(&json, "title"),
+        home_url: text(&json, "home_page_url"),
+        self_url: text(&json, "feed_url"),
+        items,
+    })
+}
+
+fn parse_xml(content: &str) -> Result<Feed> {
+    let mut reader = Reader::from_str(content);
+    let mut feed: Option<Feed> = None;
+    // Local names of the open elements.
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    let mut item: Option<FeedItem> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => {
+                let name = e.local_name().as_ref().to_vec();
+                if stack.is_empty() {
+                    let format = match name.as_slice() {
+                        b"rss" => FeedFormat::Rss,
+                        b"feed" => FeedFormat::Atom,
+                        _ => anyhow::bail!("root element is neither <rss> nor <feed>"),
+                    };
+                    feed = Some(Feed {
+                        format,
+                        title: None,
+                        home_url: None,
+                        self_url: None,
+                        items: Vec::new(),
+                    });
+                }
+                if let Some(feed) = feed.as_mut() {
+                    if is_item_start(feed.format, &stack, &name) {
+                        item = Some(FeedItem::default());
+                    }
+                    if name == b"link" {
+                        link_attributes(feed, item.as_mut(), e);
+                    }
+                }
+                stack.push(name);
+                text.clear();
+            }
+            Ok(Event::Empty(ref e)) if e.local_name().as_ref() == b"link" => {
+                if let Some(feed) = feed.as_mut() {
+                    link_attributes(feed, item.as_mut(), e);
+                }
+            }
+            Ok(Event::Text(ref e)) => {
+                if let Ok(t) = e.unescape() {
+                    text.push_str(&t);
+                }
+            }
+            Ok(Event::CData(ref e)) => text.push_str(&String::from_utf8_lossy(e.as_ref())),
+            Ok(Event::End(_)) => {
+                let value = text.trim().to_string();
+                text.clear();
+                let closed = stack.pop().unwrap_or_default();
+                let Some(feed) = feed.as_mut() else {
+                    continue;
+                };
+                if is_item_start(feed.format, &stack, &closed) {
+                    if let Some(item) = item.take() {
+                        feed.items.push(item);
+                    }
+                    continue;
+                }
+                if value.is_empty() {
+                    continue;
+                }
+                let depth = stack.len();
+                let channel_depth = if feed.format == FeedFormat::Rss { 2 } else { 1 };
+                match (&mut item, closed.as_slice()) {
+                    (Some(item), name) if depth == channel_depth + 1 => match name {
+                        b"title" => item.title = Some(value),
+                        b"link" if item.link.is_none() => item.link = Some(value),
+                        b"guid" | b"id" => item.id = Some(value),
+                        b"pubDate" => {
+                            item.date = Some(value);
+                            item.date_is_rfc822 = true;
+                        }
+                        b"published" => item.date = Some(value),
+                        b"date" | b"updated" if item.date.is_none() => item.date = Some(value),
+                        _ => {}
+                    },
+                    (None, name) if depth == channel_depth => match name {
+                        b"title" => feed.title = Some(value),
+                        b"link" if feed.home_url.is_none() => feed.home_url = Some(value),
+                        _ => {}
+                    },
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => anyhow::bail!("invalid XML: {e}"),
+            _ => {}
+        }
+    }
+
+    feed.ok_or_else(|| anyhow::anyhow!("empty document"))
+}
+
+/// Whether `name`, opened under `stack`, is an RSS `<item>` / Atom `<entry>`.
+fn is_item_start(format: FeedFormat, stack: &[Vec<u8>], name: &[u8]) -> bool {
+    match format {
+        FeedFormat::Rss => stack.len() == 2 && name == b"item",
+        FeedFormat::Atom => stack.len() == 1 && name == b"entry",
+        FeedFormat::Json => false,
+    }
+}
+
+/// Atom-style `<link rel=… href=…>` (also `atom:link` inside RSS).
+fn link_attributes(feed: &mut Feed, item: Option<&mut FeedItem>, e: &BytesStart) {
+    let mut rel = None;
+    let mut href = None;
+    for attr in e.attributes().flatten() {
+        let value = attr.unescape_value().map(|v| v.trim().to_string()).ok();
+        match attr.key.local_name().as_ref() {
+            b"rel" => rel = value,
+            b"href" => href = value,
+            _ => {}
+        }
+    }
+    let Some(href) = href.filter(|h| !h.is_empty()) else {
+        return;
+    };
+    match (rel.as_deref().unwrap_or("alternate"), item) {
+        ("alternate", Some(item)) => {
+            item.link.get_or_insert(href);
+        }
+        ("alternate", None) => {
+            feed.home_url.get_or_insert(href);
+        }
+        ("self", None) => feed.self_url = Some(href),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse(
+            r#"<?xml version="1.0"?><rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel>
+<title>Blog</title><link>https://example.com/</link>
+<atom:link href="https://example.com/rss.xml" rel="self" type="application/rss+xml"/>
+<item><title><![CDATA[Hello & welcome]]></title><link>https://example.com/blog/hello/</link>
+<guid isPermaLink="true">https://example.com/blog/hello/</guid><pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+        assert_eq!(feed.format, FeedFormat::Rss);
+        assert_eq!(feed.title.as_deref(), Some("Blog"));
+        assert_eq!(feed.home_url.as_deref(), Some("https://example.com/"));
+        assert_eq!(
+            feed.self_url.as_deref(),
+            Some("https://example.com/rss.xml")
+        );
+        assert_eq!(feed.items.len(), 1);
+        let item = &feed.items[0];
+        assert_eq!(item.title.as_deref(), Some("Hello & welcome"));
+        assert_eq!(
+            item.link.as_deref(),
+            Some("https://example.com/blog/hello/")
+        );
+        assert_eq!(item.timestamp(), Some(1_704_067_200));
+    }
+
+    #[test]
+    fn test_parse_atom_and_json() {
+        let atom = parse(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title><link href="https://example.com/"/><link rel="self" href="https://example.com/atom.xml"/>
+<entry><title>Post</title><link href="https://example.com/p/"/><id>urn:uuid:1</id><updated>2024-01-02T00:00:00Z</updated><published>2024-01-01T00:00:00Z</published></entry></feed>"#,
+        )
+        .unwrap();
+        assert_eq!(atom.format, FeedFormat::Atom);
+        assert_eq!(
+            atom.self_url.as_deref(),
+            Some("https://example.com/atom.xml")
+        );
+        assert_eq!(
+            atom.items[0].link.as_deref(),
+            Some("https://example.com/p/")
+        );
+        assert_eq!(atom.items[0].date.as_deref(), Some("2024-01-01T00:00:00Z"));
+
+        let json = parse(
+            r#"{"version":"https://jsonfeed.org/version/1.1","title":"Blog","items":[{"id":"1","url":"https://example.com/p/","title":"Post","date_published":"2024-01-01T00:00:00Z"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(json.format, FeedFormat::Json);
+        assert_eq!(json.items[0].id.as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,696 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::normalize;
+
+/// A redirect or rewrite rule from a static host's configuration.
+#[derive(Debug, Clone)]
+pub struct RedirectRule {
+    /// Config file the rule came from: dist-relative for `_redirects`,
+    /// project-relative for `netlify.toml` / `vercel.json`.
+    pub file: String,
+    /// Human-readable position in the file ("line 3", "redirects[2]").
+    pub location: String,
+    pub from: String,
+    pub to: String,
+    /// HTTP status; 200 is a rewrite, 3xx a redirect.
+    pub status: u16,
+    /// The rule applies even when a file exists at `from` (Netlify `301!` /
+    /// `force = true`, and every Vercel redirect).
+    pub force: bool,
+    /// The rule only applies to some requests (query, country, language,
+    /// role, or Vercel `has` / `missing` conditions).
+    pub conditional: bool,
+}
+
+impl RedirectRule {
+    pub fn is_redirect(&self) -> bool {
+        (300..400).contains(&self.status)
+    }
+
+    /// `from` has no splat or placeholders.
+    pub fn is_exact(&self) -> bool {
+        is_exact(&self.from)
+    }
+
+    /// The target for `path` if this rule matches it, with splats and
+    /// placeholders substituted.
+    pub fn target_for(&self, path: &str) -> Option<String> {
+        let mut captures = match_pattern(&self.from, path)?;
+        let mut target = self.to.clone();
+        // Longest names first so `:slug` does not eat into `:slugs`.
+        captures.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+        for (name, value) in &captures {
+            if name.starts_with('$') {
+                target = target.replace(name.as_str(), value);
+                continue;
+            }
+            for suffix in ["*", "+", ""] {
+                target = target.replace(&format!(":{name}{suffix}"), value);
+            }
+        }
+        Some(target)
+    }
+}
+
+/// Response headers configured for a path pattern.
+#[derive(Debug, Clone)]
+pub struct HeaderRule {
+    pub file: String,
+    pub pattern: String,
+    /// (name, value) pairs in file order.
+    pub headers: Vec<(String, String)>,
+}
+
+/// A line or entry that could not be understood.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub file: String,
+    pub location: String,
+    pub message: String,
+}
+
+/// Redirects and headers from `_redirects`, `_headers` (Netlify, Cloudflare
+/// Pages), `netlify.toml` and `vercel.json`, in the order the host applies
+/// them.
+#[derive(Debug, Clone, Default)]
+pub struct HostingConfig {
+    pub redirects: Vec<RedirectRule>,
+    pub headers: Vec<HeaderRule>,
+    pub errors: Vec<ParseError>,
+}
+
+impl HostingConfig {
+    /// The first redirect or rewrite rule that matches `path`, with its
+    /// expanded target. Conditional rules are skipped.
+    pub fn first_match(&self, path: &str) -> Option<(&RedirectRule, String)> {
+        self.redirects
+            .iter()
+            .filter(|r| !r.conditional)
+            .find_map(|r| r.target_for(path).map(|target| (r, target)))
+    }
+
+    /// Headers configured for `path`, from every matching block in order.
+    /// Like Netlify and Cloudflare, values of a header set by several
+    /// blocks are joined with ", ".
+    pub fn headers_for(&self, path: &str) -> Vec<(String, String)> {
+        let mut out: Vec<(String, String)> = Vec::new();
+        for rule in &self.headers {
+            if match_pattern(&rule.pattern, path).is_none() {
+                continue;
+            }
+            for (name, value) in &rule.headers {
+                match out.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+                    Some((_, existing)) => {
+                        existing.push_str(", ");
+                        existing.push_str(value);
+                    }
+                    None => out.push((name.clone(), value.clone())),
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Load every hosting config file: `_redirects` and `_headers` from dist
+/// (Astro copies them from `public/`), `netlify.toml` and `vercel.json` from
+/// the project root, falling back to dist.
+pub fn load(dist_path: &Path, project_root: Option<&Path>) -> HostingConfig {
+    let mut config = HostingConfig::default();
+
+    if let Ok(content) = std::fs::read_to_string(dist_path.join("_redirects")) {
+        parse_redirects_file(&content, "_redirects", &mut config);
+    }
+    if let Ok(content) = std::fs::read_to_string(dist_path.join("_headers")) {
+        parse_headers_file(&content, "_headers", &mut config);
+    }
+
+    let locate = |name: &str| {
+        project_root
+            .map(|root| root.join(name))
+            .filter(|p| p.is_file())
+            .or_else(|| Some(dist_path.join(name)).filter(|p| p.is_file()))
+    };
+    if let Some(path) = locate("netlify.toml") {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            parse_netlify_toml(&content, "netlify.toml", &mut config);
+        }
+    }
+    if let Some(path) = locate("vercel.json") {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            parse_vercel_json(&content, "vercel.json", &mut config);
+        }
+    }
+
+    config
+}
+
+/// Parse a Netlify / Cloudflare `_redirects` file:
+/// `from [conditions] to [status][!] [conditions]`, one rule per line.
+pub fn parse_redirects_file(content: &str, file: &str, config: &mut HostingConfig) {
+    for (i, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let location = format!("line {}", i + 1);
+        let error = |message: String| ParseError {
+            file: file.to_string(),
+            location: location.clone(),
+            message,
+        };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let from = tokens[0];
+        // Query parameter conditions sit between `from` and `to`.
+        let mut rest = tokens[1..].iter().copied().peekable();
+        let mut conditional = false;
+        while rest.next_if(|t| is_condition(t)).is_some() {
+            conditional = true;
+        }
+        let Some(to) = rest.next() else {
+            config
+                .errors
+                .push(error(format!("Rule '{line}' has no target")));
+            continue;
+        };
+        let mut status = 301;
+        let mut force = false;
+        if let Some(token) = rest.next_if(|t| !is_condition(t)) {
+            let (code, forced) = match token.strip_suffix('!') {
+                Some(code) => (code, true),
+                None => (token, false),
+            };
+            match code.parse::<u16>() {
+                Ok(code) if (100..600).contains(&code) => {
+                    status = code;
+                    force = forced;
+                }
+                _ => {
+                    config
+                        .errors
+                        .push(error(format!("Invalid status '{token}' in rule '{line}'")));
+                    continue;
+                }
+            }
+        }
+        // Country=, Language=, Role= conditions follow the status.
+        if rest.next().is_some() {
+            conditional = true;
+        }
+        config.redirects.push(RedirectRule {
+            file: file.to_string(),
+            location,
+            from: from.to_string(),
+            to: to.to_string(),
+            status,
+            force,
+            conditional,
+        });
+    }
+}
+
+/// `key=value` tokens are conditions; paths and URLs never start that way.
+fn is_condition(token: &str) -> bool {
+    token.contains('=') && !token.starts_with('/') && !token.contains("://")
+}
+
+/// Parse a Netlify / Cloudflare `_headers` file: an unindented path pattern
+/// followed by indented `Name: value` lines. Cloudflare's `! Name` detach
+/// lines are ignored.
+pub fn parse_headers_file(content: &str, file: &str, config: &mut HostingConfig) {
+    let mut current: Option<HeaderRule> = None;
+    for (i, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let location = format!("line {}", i + 1);
+        if !raw.starts_with([' ', '\t']) {
+            config.headers.extend(current.take());
+            current = Some(HeaderRule {
+                file: file.to_string(),
+                pattern: line.to_string(),
+                headers: Vec::new(),
+            });
+            continue;
+        }
+        if line.starts_with('!') {
+            continue;
+        }
+        match (current.as_mut(), line.split_once(':')) {
+            (Some(rule), Some((name, value))) if !name.trim().is_empty() => {
+                rule.headers
+                    .push((name.trim().to_string(), value.trim().to_string()));
+            }
+            (None, _) => config.errors.push(ParseError {
+                file: file.to_string(),
+                location,
+                message: format!("Header '{line}' is not below a path"),
+            }),
+            _ => config.errors.push(ParseError {
+                file: file.to_string(),
+                location,
+                message: format!("Expected 'Name: value', found '{line}'"),
+            }),
+        }
+    }
+    config.headers.extend(current);
+}
+
+/// Parse `redirects`, `rewrites` and `headers` from `vercel.json`. Vercel
+/// applies redirects before the filesystem and rewrites after it.
+pub fn parse_vercel_json(content: &str, file: &str, config: &mut HostingConfig) {
+    let json: Value = match serde_json::from_str(content) {
+        Ok(json) => json,
+        Err(e) => {
+            config.errors.push(ParseError {
+                file: file.to_string(),
+                location: format!("line {}", e.line()),
+                message: format!("Invalid JSON: {e}"),
+            });
+            return;
+        }
+    };
+
+    for (key, is_redirect) in [("redirects", true), ("rewrites", false)] {
+        let Some(entries) = json.get(key).and_then(Value::as_array) else {
+            continue;
+        };
+        for (i, entry) in entries.iter().enumerate() {
+            let location = format!("{key}[{i}]");
+            let field = |name: &str| entry.get(name).and_then(Value::as_str);
+            let (Some(from), Some(to)) = (field("source"), field("destination")) else {
+                config.errors.push(ParseError {
+                    file: file.to_string(),
+                    location,
+                    message: "Entry needs 'source' and 'destination'".into(),
+                });
+                continue;
+            };
+            let status = if is_redirect {
+                match entry.get("statusCode").and_then(Value::as_u64) {
+                    Some(code) => code as u16,
+                    None if entry.get("permanent").and_then(Value::as_bool) == Some(false) => 307,
+                    None => 308,
+                }
+            } else {
+                200
+            };
+            config.redirects.push(RedirectRule {
+                file: file.to_string(),
+                location,
+                from: from.to_string(),
+                to: to.to_string(),
+                status,
+                force: is_redirect,
+                conditional: entry.get("has").is_some() || entry.get("missing").is_some(),
+            });
+        }
+    }
+
+    if let Some(entries) = json.get("headers").and_then(Value::as_array) {
+        for entry in entries {
+            let Some(pattern) = entry.get("source").and_then(Value::as_str) else {
+                continue;
+            };
+            let headers = entry
+                .get("headers")
+                .and_then(Value::as_array)
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|h| {
+                            Some((
+                                h.get("key")?.as_str()?.to_string(),
+                                h.get("value")?.as_str()?.to_string(),
+                            ))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            config.headers.push(HeaderRule {
+                file: file.to_string(),
+                pattern: pattern.to_string(),
+                headers,
+            });
+        }
+    }
+}
+
+/// The parts of `netlify.toml` the audit reads.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NetlifyToml {
+    redirects: Vec<NetlifyRedirect>,
+    headers: Vec<NetlifyHeaders>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NetlifyRedirect {
+    from: Option<String>,
+    to: Option<String>,
+    status: Option<u16>,
+    force: bool,
+    conditions: Option<toml::Value>,
+    query: Option<toml::Value>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NetlifyHeaders {
+    #[serde(rename = "for")]
+    pattern: Option<String>,
+    values: toml::Table,
+}
+
+/// Parse the `[[redirects]]` and `[[headers]]` tables of `netlify.toml`.
+pub fn parse_netlify_toml(content: &str, file: &str, config: &mut HostingConfig) {
+    let parsed: NetlifyToml = match toml::from_str(content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let line = e
+                .span()
+                .map_or(1, |span| content[..span.start].matches('\n').count() + 1);
+            config.errors.push(ParseError {
+                file: file.to_string(),
+                location: format!("line {line}"),
+                message: format!("Invalid TOML: {}", e.message()),
+            });
+            return;
+        }
+    };
+
+    for (i, r) in parsed.redirects.into_iter().enumerate() {
+        let location = format!("redirects[{i}]");
+        match (r.from, r.to) {
+            (Some(from), Some(to)) => config.redirects.push(RedirectRule {
+                file: file.to_string(),
+                location,
+                from,
+                to,
+                status: r.status.unwrap_or(301),
+                force: r.force,
+                conditional: r.conditions.is_some() || r.query.is_some(),
+            }),
+            _ => config.errors.push(ParseError {
+                file: file.to_string(),
+                location,
+                message: "[[redirects]] needs 'from' and 'to'".into(),
+            }),
+        }
+    }
+    for (i, h) in parsed.headers.into_iter().enumerate() {
+        let Some(pattern) = h.pattern else {
+            config.errors.push(ParseError {
+                file: file.to_string(),
+                location: format!("headers[{i}]"),
+                message: "[[headers]] needs 'for'".into(),
+            });
+            continue;
+        };
+        // Multi-line values (long CSPs) are sent as one line.
+        let headers = h
+            .values
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let value = value.as_str()?.split_whitespace().collect::<Vec<_>>();
+                Some((name, value.join(" ")))
+            })
+            .collect();
+        config.headers.push(HeaderRule {
+            file: file.to_string(),
+            pattern,
+            headers,
+        });
+    }
+}
+
+/// `pattern` has no splat or placeholders in its path. The scheme, host and
+/// port of an absolute URL and any query string are not part of the match.
+pub fn is_exact(pattern: &str) -> bool {
+    let path = match pattern.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |i| &rest[i..]),
+        None if pattern.starts_with("//") => {
+            pattern[2..].find('/').map_or("", |i| &pattern[2 + i..])
+        }
+        None => pattern,
+    };
+    !normalize::strip_fragment_and_query(path).contains(['*', ':', '('])
+}
+
+/// Match a host path pattern against a URL path, ignoring trailing slashes
+/// as Netlify does. `*` (anywhere in the last segment), `:name*`, `:name+`
+/// and `(.*)` match the rest of the path as `splat` / `name` / `$1`;
+/// `:name` matches one segment. Other regex syntax never matches.
+/// Returns the captures on a match.
+pub fn match_pattern(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let pattern = pattern.split('?').next().unwrap_or(pattern);
+    let pattern_segs: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path_segs: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut captures = Vec::new();
+
+    for (i, seg) in pattern_segs.iter().enumerate() {
+        let rest = path_segs.get(i..).unwrap_or(&[]).join("/");
+        if *seg == "(.*)" {
+            captures.push(("$1".to_string(), rest));
+            return Some(captures);
+        }
+        if let Some(name) = seg
+            .strip_prefix(':')
+            .and_then(|s| s.strip_suffix('*').or_else(|| s.strip_suffix('+')))
+        {
+            if seg.ends_with('+') && rest.is_empty() {
+                return None;
+            }
+            captures.push((name.to_string(), rest));
+            return Some(captures);
+        }
+        if let Some(prefix) = seg.strip_suffix('*') {
+            if prefix.contains(['*', ':', '(']) {
+                return None;
+            }
+            let rest = rest.strip_prefix(prefix)?;
+            captures.push((
+                "splat".to_string(),
+                rest.trim_start_matches('/').to_string(),
+            ));
+            return Some(captures);
+        }
+        let actual = path_segs.get(i)?;
+        if let Some(name) = seg.strip_prefix(':') {
+            if name.contains('(') {
+                return None;
+            }
+            captures.push((name.to_string(), actual.to_string()));
+        } else if seg.contains(['*', '(']) || seg != actual {
+            return None;
+        }
+    }
+
+    (pattern_segs.len() == path_segs.len()).then_some(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_redirects_file() {
+        let mut config = HostingConfig::default();
+        parse_redirects_file(
+            "# comment\n/old /new\n/blog/* /posts/:splat 302!\n/search q=:q /find 301\n/de/* /de/404 404 Language=de\n/broken\n/x /y abc\n",
+            "_redirects",
+            &mut config,
+        );
+        let r = &config.redirects;
+        assert_eq!(r.len(), 4);
+        assert_eq!(
+            (r[0].status, r[0].force, r[0].conditional),
+            (301, false, false)
+        );
+        assert_eq!(r[0].location, "line 2");
+        assert_eq!((r[1].status, r[1].force), (302, true));
+        assert_eq!((r[2].to.as_str(), r[2].conditional), ("/find", true));
+        assert!(r[3].conditional);
+        assert_eq!(config.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_headers_file() {
+        let mut config = HostingConfig::default();
+        parse_headers_file(
+            "/*\n  X-Frame-Options: DENY\n  Content-Security-Policy: default-src 'self'\n/_astro/*\n  Cache-Control: public, max-age=31536000, immutable\n  ! X-Frame-Options\n",
+            "_headers",
+            &mut config,
+        );
+        assert_eq!(config.headers.len(), 2);
+        assert_eq!(config.headers[0].headers.len(), 2);
+        assert!(config.errors.is_empty());
+        assert_eq!(config.headers[1].pattern, "/_astro/*");
+        assert_eq!(config.headers_for("/_astro/app.js").len(), 3);
+        assert_eq!(config.headers_for("/about/").len(), 2);
+
+        parse_headers_file(
+            "/about/*\n  X-Frame-Options: SAMEORIGIN\n",
+            "_headers",
+            &mut config,
+        );
+        let headers = config.headers_for("/about/team/");
+        assert_eq!(
+            headers[0],
+            ("X-Frame-Options".into(), "DENY, SAMEORIGIN".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_netlify_toml() {
+        let mut config = HostingConfig::default();
+        parse_netlify_toml(
+            r#"
+[build]
+  publish = "dist" # comment
+
+[[redirects]]
+  from = "/old"
+  to = "/new#x"
+  status = 302
+  force = true
+
+[[redirects]]
+  from = "/lang"
+  to = "/en"
+  [redirects.conditions]
+    Language = ["en"]
+
+[[redirects]]
+  to = "/nowhere"
+
+[[headers]]
+  for = "/*"
+  [headers.values]
+    "X-Frame-Options" = "DENY"
+    Content-Security-Policy = '''
+      default-src 'self';
+      img-src *'''
+"#,
+            "netlify.toml",
+            &mut config,
+        );
+        assert_eq!(config.redirects.len(), 2);
+        assert_eq!(config.redirects[0].to, "/new#x");
+        assert_eq!(
+            (config.redirects[0].status, config.redirects[0].force),
+            (302, true)
+        );
+        assert!(config.redirects[1].conditional);
+        assert_eq!(config.errors.len(), 1);
+        assert_eq!(config.errors[0].location, "redirects[2]");
+        assert_eq!(
+            config.headers[0].headers,
+            vec![
+                ("X-Frame-Options".to_string(), "DENY".to_string()),
+                (
+                    "Content-Security-Policy".to_string(),
+                    "default-src 'self'; img-src *".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_netlify_toml_inline_tables() {
+        let mut config = HostingConfig::default();
+        parse_netlify_toml(
+            r#"redirects = [
+  { from = "/a", to = "/b", status = 302, query = { id = ":id" } },
+  { "from" = "/c#d", to = "/e" },
+]"#,
+            "netlify.toml",
+            &mut config,
+        );
+        assert_eq!(config.redirects.len(), 2);
+        assert!(config.redirects[0].conditional);
+        assert_eq!(config.redirects[1].from, "/c#d");
+        assert_eq!(config.redirects[1].status, 301);
+
+        let mut config = HostingConfig::default();
+        parse_netlify_toml(
+            "[[redirects]]\nfrom = \"/a\nto = 1",
+            "netlify.toml",
+            &mut config,
+        );
+        assert_eq!(config.errors.len(), 1);
+        assert_eq!(config.errors[0].location, "line 2");
+    }
+
+    #[test]
+    fn test_parse_vercel_json() {
+        let mut config = HostingConfig::default();
+        parse_vercel_json(
+            r#"{"redirects":[{"source":"/a","destination":"/b","permanent":false},{"source":"/c/:slug*","destination":"/d/:slug*","has":[{"type":"query","key":"x"}]}],
+               "rewrites":[{"source":"/app/(.*)","destination":"/app.html"}],
+               "headers":[{"source":"/(.*)","headers":[{"key":"X-Frame-Options","value":"DENY"}]}]}"#,
+            "vercel.json",
+            &mut config,
+        );
+        let r = &config.redirects;
+        assert_eq!((r[0].status, r[0].force), (307, true));
+        assert!(r[1].conditional);
+        assert_eq!((r[2].status, r[2].force), (200, false));
+        assert_eq!(config.headers[0].headers.len(), 1);
+        assert!(match_pattern(&config.headers[0].pattern, "/x/y").is_some());
+    }
+
+    #[test]
+    fn test_match_pattern() {
+        assert!(match_pattern("/old", "/old/").is_some());
+        assert!(match_pattern("/old", "/older").is_none());
+        assert!(match_pattern("/*", "/").is_some());
+        assert_eq!(
+            match_pattern("/blog/*", "/blog/a/b"),
+            Some(vec![("splat".to_string(), "a/b".to_string())])
+        );
+        assert_eq!(
+            match_pattern("/news/:year/:slug", "/news/2024/hello/"),
+            Some(vec![
+                ("year".to_string(), "2024".to_string()),
+                ("slug".to_string(), "hello".to_string())
+            ])
+        );
+        assert!(match_pattern("/news/:year", "/news").is_none());
+        assert!(match_pattern("/docs/:path+", "/docs").is_none());
+        assert!(match_pattern("/v/(\\d+)", "/v/1").is_none());
+    }
+
+    #[test]
+    fn test_is_exact() {
+        assert!(is_exact("/new/"));
+        assert!(is_exact("https://example.com/new/"));
+        assert!(is_exact("https://example.com:8080/new/"));
+        assert!(is_exact("//example.com/new/"));
+        assert!(is_exact("/find?q=:q"));
+        assert!(!is_exact("/blog/:slug"));
+        assert!(!is_exact("https://example.com/blog/*"));
+    }
+
+    #[test]
+    fn test_target_for() {
+        let rule = RedirectRule {
+            file: "_redirects".into(),
+            location: "line 1".into(),
+            from: "/news/:year/*".into(),
+            to: "/blog/:year/:splat".into(),
+            status: 301,
+            force: false,
+            conditional: false,
+        };
+        assert_eq!(
+            rule.target_for("/news/2024/a/b/").as_deref(),
+            Some("/blog/2024/a/b")
+        );
+        assert_eq!(rule.target_for("/other"), None);
+    }
+}
@@ -0,0 +1,254 @@
+/// A module specifier imported by a JavaScript file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleImport {
+    pub specifier: String,
+    /// `import("...")`, loaded on demand rather than with the importer.
+    pub dynamic: bool,
+}
+
+/// Extract static (`import … from "x"`, `import "x"`, `export … from "x"`)
+/// and dynamic (`import("x")`) imports with string-literal specifiers.
+///
+/// This is a token scan tuned for bundler output, not a full JS parser: it
+/// skips comments, string contents and regex literals, and ignores computed
+/// specifiers.
+pub fn module_imports(src: &str) -> Vec<ModuleImport> {
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    // Whether a `/` here starts a regex literal rather than a division: true
+    // after punctuation and keywords such as `return`, false after operands.
+    let mut regex_allowed = true;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'"' | b'\'' | b'`' => {
+                i = skip_string(bytes, i);
+                regex_allowed = false;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = memchr(bytes, i, b'\n');
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = src[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |n| i + 2 + n + 2);
+            }
+            b'/' if regex_allowed => {
+                i = skip_regex(bytes, i);
+                regex_allowed = false;
+            }
+            _ if is_ident_start(b) => {
+                let start = i;
+                while i < bytes.len() && is_ident_char(bytes[i]) {
+                    i += 1;
+                }
+                let word = &src[start..i];
+                let member =
+                    start > 0 && (bytes[start - 1] == b'.' || is_ident_char(bytes[start - 1]));
+                regex_allowed = !member && REGEX_KEYWORDS.contains(&word);
+                if member {
+                    continue;
+                }
+                let next = skip_ws(bytes, i);
+                match word {
+                    "import" if bytes.get(next) == Some(&b'(') => {
+                        let arg = skip_ws(bytes, next + 1);
+                        if let Some(spec) = string_at(src, arg) {
+                            out.push(ModuleImport {
+                                specifier: spec,
+                                dynamic: true,
+                            });
+                        }
+                    }
+                    "import" | "from" => {
+                        if let Some(spec) = string_at(src, next) {
+                            out.push(ModuleImport {
+                                specifier: spec,
+                                dynamic: false,
+                            });
+                            i = skip_string(bytes, next);
+                            regex_allowed = false;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {
+                if !b.is_ascii_whitespace() {
+                    regex_allowed = !(b.is_ascii_digit() || b == b')' || b == b']');
+                }
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Keywords after which a `/` starts a regex literal, not a division.
+const REGEX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+/// Whether a specifier points at a file the browser fetches from this site
+/// (relative or root-relative), as opposed to a bare or remote module.
+pub fn is_local_specifier(spec: &str) -> bool {
+    spec.starts_with("./")
+        || spec.starts_with("../")
+        || (spec.starts_with('/') && !spec.starts_with("//"))
+}
+
+fn string_at(src: &str, pos: usize) -> Option<String> {
+    let quote = *src.as_bytes().get(pos)?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    // An unterminated literal has no specifier.
+    let (end, closed) = scan_string(src.as_bytes(), pos);
+    closed.then(|| src[pos + 1..end - 1].to_string())
+}
+
+/// Index just past the string literal starting at `pos`.
+fn skip_string(bytes: &[u8], pos: usize) -> usize {
+    scan_string(bytes, pos).0
+}
+
+/// End of the string literal starting at `pos`, and whether its closing quote
+/// was found (the literal may run into a newline or the end of input).
+fn scan_string(bytes: &[u8], pos: usize) -> (usize, bool) {
+    let quote = bytes[pos];
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            c if c == quote => return (i + 1, true),
+            b'\n' if quote != b'`' => return (i, false),
+            _ => i += 1,
+        }
+    }
+    (bytes.len(), false)
+}
+
+/// Index just past the regex literal starting at `pos` (flags excluded).
+/// A `/` inside a character class does not end it.
+fn skip_regex(bytes: &[u8], pos: usize) -> usize {
+    let mut i = pos + 1;
+    let mut in_class = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'[' => {
+                in_class = true;
+                i += 1;
+            }
+            b']' => {
+                in_class = false;
+                i += 1;
+            }
+            b'/' if !in_class => return i + 1,
+            b'\n' => return i,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_ws(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+fn memchr(bytes: &[u8], from: usize, needle: u8) -> usize {
+    bytes[from..]
+        .iter()
+        .position(|&b| b == needle)
+        .map_or(bytes.len(), |n| from + n)
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$'
+}
+
+fn is_ident_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(src: &str) -> Vec<(String, bool)> {
+        module_imports(src)
+            .into_iter()
+            .map(|m| (m.specifier, m.dynamic))
+            .collect()
+    }
+
+    #[test]
+    fn test_minified_imports() {
+        let src = r#"import{j as e}from"./jsx-runtime.D1.js";import"./side.js";export{a}from'./re.js';const t=()=>import("./lazy.B2.js");"#;
+        assert_eq!(
+            specs(src),
+            vec![
+                ("./jsx-runtime.D1.js".into(), false),
+                ("./side.js".into(), false),
+                ("./re.js".into(), false),
+                ("./lazy.B2.js".into(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_strings_comments_and_members() {
+        let src = r#"// import "./a.js"
+/* import("./b.js") */ const s = "import './c.js'"; obj.import("./d.js"); x.from("./e.js");"#;
+        assert!(specs(src).is_empty());
+    }
+
+    #[test]
+    fn test_unterminated_string_is_not_a_specifier() {
+        assert!(specs("import \"./a.js").is_empty());
+        assert!(specs("import \"./é").is_empty());
+        assert_eq!(
+            specs("import './a.js\nimport \"./b.js\";"),
+            vec![("./b.js".into(), false)]
+        );
+    }
+
+    #[test]
+    fn test_skips_regex_literals() {
+        let src = r#"const q=/["']/g,r=s.replace(/'/,"");import"./a.js";x=a/b;import("./b.js");if(t)return/`/.test(u);import"./c.js";"#;
+        assert_eq!(
+            specs(src),
+            vec![
+                ("./a.js".into(), false),
+                ("./b.js".into(), true),
+                ("./c.js".into(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_local_specifier() {
+        assert!(is_local_specifier("./a.js"));
+        assert!(is_local_specifier("/_astro/a.js"));
+        assert!(!is_local_specifier("react"));
+        assert!(!is_local_specifier("https://esm.sh/react"));
+        assert!(!is_local_specifier("//cdn.example.com/a.js"));
+    }
+}
@@ -0,0 +1,399 @@
+//! `llms.txt` (https://llmstxt.org): parsing against the proposed format and
+//! generating a draft from the site index.
+//!
+//! ```text
+//! # Site name
+//! > One-paragraph summary
+//! Optional details: paragraphs and lists, no headings.
+//! ## Section
+//! - [Page title](https://example.com/page/): optional notes
+//! ```
+
+use std::collections::BTreeMap;
+
+use url::Url;
+
+use crate::config::Config;
+use crate::discovery::{PageInfo, SiteIndex};
+use crate::normalize;
+
+/// A parsed `llms.txt` or `llms-full.txt`.
+#[derive(Debug, Clone, Default)]
+pub struct LlmsTxt {
+    pub title: Option<String>,
+    /// The blockquote directly after the title.
+    pub summary: Option<String>,
+    pub sections: Vec<Section>,
+    /// Every markdown link in the file, in order.
+    pub links: Vec<Link>,
+    pub problems: Vec<Problem>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub name: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub line: usize,
+    pub text: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemKind {
+    /// The file does not start with an H1.
+    NoTitle,
+    /// A second H1, or an H3+ heading where only H2 sections are allowed.
+    Heading,
+    /// A list item in a section that is not `[name](url)` with optional notes.
+    ListItem,
+}
+
+/// A line that does not follow the format.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub line: usize,
+    pub kind: ProblemKind,
+    pub message: String,
+}
+
+pub fn parse(content: &str) -> LlmsTxt {
+    let mut doc = LlmsTxt::default();
+    let mut seen_content = false;
+    let mut expect_summary = false;
+    let mut in_code = false;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line_no = i + 1;
+        let line = raw.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.is_empty() {
+            continue;
+        }
+        doc.links
+            .extend(markdown_links(line).into_iter().map(|(text, url)| Link {
+                line: line_no,
+                text,
+                url,
+            }));
+
+        let first = !seen_content;
+        seen_content = true;
+        let level = line.bytes().take_while(|&b| b == b'#').count();
+        let heading =
+            (level > 0 && line[level..].starts_with(' ')).then(|| line[level..].trim().to_string());
+
+        if first {
+            match (level, &heading) {
+                (1, Some(title)) => {
+                    doc.title = Some(title.clone());
+                    expect_summary = true;
+                    continue;
+                }
+                _ => doc.problems.push(Problem {
+                    line: line_no,
+                    kind: ProblemKind::NoTitle,
+                    message: "llms.txt must start with an H1 title (`# Site name`)".into(),
+                }),
+            }
+        }
+
+        if let Some(name) = heading {
+            expect_summary = false;
+            match level {
+                2 => doc.sections.push(Section {
+                    name,
+                    line: line_no,
+                }),
+                // A late title was already reported as NoTitle.
+                1 if doc.title.is_none() => doc.title = Some(name),
+                1 => doc.problems.push(Problem {
+                    line: line_no,
+                    kind: ProblemKind::Heading,
+                    message: format!("Second H1 '{name}'; only the title may be an H1"),
+                }),
+                _ => doc.problems.push(Problem {
+                    line: line_no,
+                    kind: ProblemKind::Heading,
+                    message: format!("H{level} heading '{name}'; the format only has H2 sections"),
+                }),
+            }
+            continue;
+        }
+
+        if expect_summary {
+            expect_summary = false;
+            if let Some(quote) = line.strip_prefix('>') {
+                doc.summary = Some(quote.trim().to_string());
+                continue;
+            }
+        }
+
+        if doc.sections.is_empty() {
+            continue;
+        }
+        let item = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .map(str::trim);
+        if let Some(item) = item {
+            if !is_link_item(item) {
+                doc.problems.push(Problem {
+                    line: line_no,
+                    kind: ProblemKind::ListItem,
+                    message: format!("List item '{item}' is not a link (`- [name](url): notes`)"),
+                });
+            }
+        }
+    }
+    doc
+}
+
+/// `[name](url)` optionally followed by `: notes`.
+fn is_link_item(item: &str) -> bool {
+    let Some((text, url, rest)) = split_link(item) else {
+        return false;
+    };
+    !text.trim().is_empty()
+        && !url.trim().is_empty()
+        && (rest.trim().is_empty() || rest.trim_start().starts_with(':'))
+}
+
+/// Split a leading `[text](url)` off `s`, returning (text, url, remainder).
+fn split_link(s: &str) -> Option<(&str, &str, &str)> {
+    let rest = s.strip_prefix('[')?;
+    let close = rest.find("](")?;
+    let after = &rest[close + 2..];
+    let end = after.find(')')?;
+    Some((&rest[..close], &after[..end], &after[end + 1..]))
+}
+
+/// All `[text](url)` links on a line; images (`![alt](src)`) are skipped.
+fn markdown_links(line: &str) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let is_image = rest[..start].ends_with('!');
+        match split_link(&rest[start..]) {
+            Some((text, url, remainder)) => {
+                if !is_image {
+                    // `[text](url "title")`
+                    let url = url.split_whitespace().next().unwrap_or("");
+                    links.push((text.to_string(), url.to_string()));
+                }
+                rest = remainder;
+            }
+            None => rest = &rest[start + 1..],
+        }
+    }
+    links
+}
+
+/// Pages worth listing: indexable, self-canonical, not an error page or a
+/// numbered listing page (`/blog/2/`).
+pub fn is_listable(page: &PageInfo, config: &Config) -> bool {
+    if page.noindex || page.is_error_page() {
+        return false;
+    }
+    let last = page
+        .route
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or("");
+    if !last.is_empty() && last.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    !page
+        .canonical
+        .as_deref()
+        .and_then(|c| Url::parse(c).ok())
+        .is_some_and(|c| {
+            normalize::normalize_path(c.path(), &config.url_normalization) != page.route
+        })
+}
+
+/// Draft an `llms.txt`: the home page's title and description as title and
+/// summary, then one H2 section per top-level path segment, named after the
+/// section's index page. Top-level pages without children go under "Pages".
+pub fn generate(index: &SiteIndex, config: &Config) -> String {
+    let home = index.route_to_index.get("/").map(|&i| &index.pages[i]);
+    let title = home
+        .and_then(|p| p.title_text.as_deref())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| {
+            index
+                .base_url
+                .as_deref()
+                .and_then(|b| Url::parse(b).ok())
+                .and_then(|u| u.host_str().map(str::to_string))
+        })
+        .unwrap_or_else(|| "Site".to_string());
+
+    let mut pages: Vec<&PageInfo> = index
+        .pages
+        .iter()
+        .filter(|p| p.route != "/" && is_listable(p, config))
+        .collect();
+    pages.sort_by(|a, b| a.route.cmp(&b.route));
+
+    // segment -> (section index page, child pages)
+    let mut sections: BTreeMap<&str, (Option<&PageInfo>, Vec<&PageInfo>)> = BTreeMap::new();
+    for page in &pages {
+        let trimmed = page.route.trim_matches('/');
+        let (segment, is_index) = match trimmed.split_once('/') {
+            Some((segment, _)) => (segment, false),
+            None => (trimmed, true),
+        };
+        let entry = sections.entry(segment).or_default();
+        if is_index {
+            entry.0 = Some(page);
+        } else {
+            entry.1.push(page);
+        }
+    }
+
+    let mut out = format!("# {title}\n");
+    if let Some(description) = home.and_then(|p| p.meta_description.as_deref()) {
+        out.push_str(&format!("\n> {}\n", description.trim()));
+    }
+
+    let link = |page: &PageInfo| {
+        let url = index
+            .base_url
+            .as_deref()
+            .and_then(|b| normalize::to_absolute(&page.route, b))
+            .unwrap_or_else(|| page.route.clone());
+        let name = page
+            .title_text
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or(&page.route);
+        match page.meta_description.as_deref().map(str::trim) {
+            Some(d) if !d.is_empty() => format!("- [{name}]({url}): {d}\n"),
+            _ => format!("- [{name}]({url})\n"),
+        }
+    };
+
+    let mut standalone = Vec::new();
+    for (segment, (section_page, children)) in &sections {
+        if children.is_empty() {
+            standalone.extend(*section_page);
+            continue;
+        }
+        let name = section_page
+            .and_then(|p| p.title_text.as_deref())
+            .map(strip_site_name)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| capitalize(segment));
+        out.push_str(&format!("\n## {name}\n\n"));
+        for page in section_page.iter().chain(children) {
+            out.push_str(&link(page));
+        }
+    }
+    if !standalone.is_empty() {
+        out.push_str("\n## Pages\n\n");
+        for page in standalone {
+            out.push_str(&link(page));
+        }
+    }
+    out
+}
+
+/// "Blog | Example" → "Blog": drop a site name appended to a page title.
+fn strip_site_name(title: &str) -> &str {
+    [" | ", " – ", " — ", " - ", " · "]
+        .iter()
+        .filter_map(|sep| title.find(sep))
+        .min()
+        .map_or(title, |i| &title[..i])
+        .trim()
+}
+
+fn capitalize(segment: &str) -> String {
+    let words: Vec<String> = segment
+        .split(['-', '_'])
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_proposed_format() {
+        let doc = parse(
+            "# Example\n\n> Docs for Example.\n\nSome details with a [link](/about/).\n\n## Docs\n\n- [Start](https://example.com/start/): how to begin\n- [API](/api/)\n\n## Optional\n\n- [Changelog](/changelog/)\n",
+        );
+        assert_eq!(doc.title.as_deref(), Some("Example"));
+        assert_eq!(doc.summary.as_deref(), Some("Docs for Example."));
+        let sections: Vec<&str> = doc.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(sections, ["Docs", "Optional"]);
+        let urls: Vec<&str> = doc.links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "/about/",
+                "https://example.com/start/",
+                "/api/",
+                "/changelog/"
+            ]
+        );
+        assert!(doc.problems.is_empty(), "{:?}", doc.problems);
+    }
+
+    #[test]
+    fn reports_structure_problems() {
+        let doc = parse(
+            "Intro without title\n# Late title\n## Docs\n# Again\n### Sub\n- plain item\n- [Ok](/ok/): notes\n- [Bad](/bad/) trailing\n",
+        );
+        let kinds: Vec<(usize, ProblemKind)> =
+            doc.problems.iter().map(|p| (p.line, p.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (1, ProblemKind::NoTitle),
+                (4, ProblemKind::Heading),
+                (5, ProblemKind::Heading),
+                (6, ProblemKind::ListItem),
+                (8, ProblemKind::ListItem),
+            ]
+        );
+        assert_eq!(doc.title.as_deref(), Some("Late title"));
+        assert!(doc.summary.is_none());
+    }
+
+    #[test]
+    fn skips_images_and_code_blocks() {
+        let doc = parse(
+            "# T\n![logo](/logo.png) see [docs](/docs/ \"Docs\")\n```\n[x](/in-code/)\n```\n",
+        );
+        let urls: Vec<&str> = doc.links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(urls, ["/docs/"]);
+    }
+
+    #[test]
+    fn section_names() {
+        assert_eq!(capitalize("case-studies"), "Case Studies");
+        assert_eq!(strip_site_name("Case Studies | Example"), "Case Studies");
+        assert_eq!(strip_site_name("Blog – Example - Docs"), "Blog");
+    }
+}
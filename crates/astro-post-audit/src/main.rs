@@ -8,11 +8,19 @@ use std::time::Instant;
 mod baseline;
 mod checks;
 mod config;
+mod csp;
+mod css;
+mod datetime;
 mod discovery;
+mod feed;
 mod hints;
+mod hosting;
+mod js;
+mod llms;
 mod normalize;
 mod overview;
 mod report;
+mod robots;
 
 use config::Config;
 use discovery::SiteIndex;
@@ -25,6 +33,9 @@ use report::{Finding, Reporter, Summary};
 )]
 #[command(version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the dist/ directory to audit
     #[arg(default_value = "dist")]
     dist_path: PathBuf,
@@ -34,6 +45,33 @@ struct Cli {
     config_stdin: bool,
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Inspect dist/robots.txt
+    Robots {
+        #[command(subcommand)]
+        command: RobotsCommand,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum RobotsCommand {
+    /// Show whether a crawler may fetch a path, and which rule decides.
+    /// Exits 1 when the path is blocked.
+    Test {
+        /// Path or URL to test, e.g. /blog/ or https://example.com/blog/?page=2
+        path: String,
+
+        /// User-agent product token, e.g. Googlebot or GPTBot
+        #[arg(long, default_value = "*")]
+        ua: String,
+
+        /// Path to the dist/ directory containing robots.txt
+        #[arg(long, default_value = "dist")]
+        dist: PathBuf,
+    },
+}
+
 fn main() {
     // Install miette's fancy graphical handler for any unhandled errors
     miette::set_hook(Box::new(|_| {
@@ -90,6 +128,13 @@ fn draw_progress(done: usize, total: usize, label: &str) {
 fn run() -> Result<i32> {
     let cli = Cli::parse();
 
+    if let Some(Command::Robots {
+        command: RobotsCommand::Test { path, ua, dist },
+    }) = &cli.command
+    {
+        return robots_test(dist, path, ua);
+    }
+
     // Load config: --config-stdin (JSON) or defaults
     let config = if cli.config_stdin {
         let mut buf = String::new();
@@ -118,7 +163,11 @@ fn run() -> Result<i32> {
     let t_start = Instant::now();
     let include = &config.filters.include;
     let exclude = &config.filters.exclude;
-    let site_index = SiteIndex::build(&cli.dist_path, &config, include, exclude)?;
+    let site_index = if config.crawl.url.is_some() {
+        SiteIndex::crawl(&cli.dist_path, &config, include, exclude)?
+    } else {
+        SiteIndex::build(&cli.dist_path, &config, include, exclude)?
+    };
     let discovery_ms = t_start.elapsed().as_millis();
 
     if debug {
@@ -132,15 +181,32 @@ fn run() -> Result<i32> {
             site_index.pages.len(),
             discovery_ms
         );
-        match &site_index.sitemap_parse_error {
-            Some(err) => eprintln!("[debug] sitemap.xml: parse error: {err}"),
-            None if site_index.sitemap_urls.is_empty() => {
-                eprintln!("[debug] sitemap.xml: not found or empty")
+        if let Some(crawl) = &site_index.crawl {
+            eprintln!(
+                "[debug] crawl: {} URL(s) requested from {}, {} error(s){}",
+                crawl.fetched,
+                crawl.start_url,
+                crawl.errors.len(),
+                if crawl.limit_reached {
+                    ", page limit reached"
+                } else {
+                    ""
+                }
+            );
+        }
+        if site_index.sitemaps.files.is_empty() {
+            eprintln!("[debug] sitemap: not found");
+        }
+        for file in &site_index.sitemaps.files {
+            match &file.parse_error {
+                Some(err) => eprintln!("[debug] {}: parse error: {err}", file.rel_path),
+                None => eprintln!(
+                    "[debug] {}: {} entr{}",
+                    file.rel_path,
+                    file.entry_count,
+                    if file.entry_count == 1 { "y" } else { "ies" }
+                ),
             }
-            None => eprintln!(
-                "[debug] sitemap.xml: {} URL(s)",
-                site_index.sitemap_urls.len()
-            ),
         }
     }
 
@@ -166,7 +232,7 @@ fn run() -> Result<i32> {
 
     // Page properties overview mode (informational, exits before checks)
     if config.page_overview {
-        let ov = overview::collect(&site_index);
+        let ov = overview::collect(&site_index, &config);
         let reporter = Reporter::new(format);
         reporter.print_overview(&ov)?;
         return Ok(0);
@@ -210,6 +276,22 @@ fn run() -> Result<i32> {
         ("js_bloat", checks::js_bloat::check_all),
         ("content_sync", checks::content_sync::check_all),
         ("html_validation", checks::html_validation::check_all),
+        ("page_weight", checks::page_weight::check_all),
+        ("unused_css", checks::unused_css::check_all),
+        ("stylesheets", checks::stylesheets::check_all),
+        ("module_graph", checks::module_graph::check_all),
+        ("manifest", checks::manifest::check_all),
+        ("feeds", checks::feeds::check_all),
+        ("csp", checks::csp::check_all),
+        ("sri", checks::sri::check_all),
+        ("crawl", checks::crawl::check_all),
+        ("http_headers", checks::http_headers::check_all),
+        ("error_pages", checks::error_pages::check_all),
+        ("pagination", checks::pagination::check_all),
+        ("llms_txt", checks::llms_txt::check_all),
+        ("security_txt", checks::security_txt::check_all),
+        ("secrets", checks::secrets::check_all),
+        ("deploy_hygiene", checks::deploy_hygiene::check_all),
     ];
 
     let total_checks = registry.len();
@@ -355,6 +437,11 @@ fn run() -> Result<i32> {
     let reporter = Reporter::new(format);
     reporter.print(&findings, &summary, benchmark_data.as_ref())?;
 
+    if let Some(path) = &config.llms_txt.generate {
+        std::fs::write(path, llms::generate(&site_index, &config))
+            .map_err(|e| anyhow::anyhow!("llms_txt.generate: {path}: {e}"))?;
+    }
+
     // Write extra report files (all formats from a single audit run)
     for extra in &config.extra_reports {
         let fmt = extra
@@ -382,6 +469,60 @@ fn run() -> Result<i32> {
     }
 }
 
+/// `robots test`: print the verdict for one path and the rule behind it.
+fn robots_test(dist: &std::path::Path, path: &str, ua: &str) -> Result<i32> {
+    let path = match url::Url::parse(path) {
+        Ok(url) if url.has_host() => match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        },
+        _ if path.starts_with('/') => path.to_string(),
+        _ => format!("/{path}"),
+    };
+    let robots_path = dist.join("robots.txt");
+    let Some(robots) = robots::Robots::load(dist) else {
+        println!(
+            "{}: not found, so every path is allowed",
+            robots_path.display()
+        );
+        return Ok(0);
+    };
+
+    let verdict = robots.check(ua, &path);
+    println!("robots.txt: {}", robots_path.display());
+    let groups = verdict
+        .groups
+        .iter()
+        .map(|g| format!("line {}", g.line))
+        .collect::<Vec<_>>()
+        .join(", ");
+    match robots.specific_groups(ua) {
+        Some(_) => println!("User-agent: {ua} (own group at {groups})"),
+        None if verdict.groups.is_empty() => {
+            println!("User-agent: {ua} (no matching group, no User-agent: * group)")
+        }
+        None if ua == "*" => println!("User-agent: * (group at {groups})"),
+        None => println!("User-agent: {ua} (no own group, follows User-agent: * at {groups})"),
+    }
+    println!("Path: {path}");
+    let decision = if verdict.allowed {
+        "ALLOWED"
+    } else {
+        "BLOCKED"
+    };
+    match verdict.rule {
+        Some(rule) => println!("{decision} by line {}: {}", rule.line, rule.directive()),
+        None => println!("{decision} (no rule matches)"),
+    }
+    if robots.truncated {
+        println!(
+            "Note: robots.txt exceeds {} KiB; rules past the limit were ignored",
+            robots::MAX_SIZE / 1024
+        );
+    }
+    Ok(if verdict.allowed { 0 } else { 1 })
+}
+
 #[cfg(test)]
 mod tests {
     use super::render_progress_line;
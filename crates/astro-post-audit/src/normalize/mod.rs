@@ -179,6 +179,19 @@ pub fn resolve_href(href: &str, page_route: &str, base_url: Option<&str>) -> Opt
     }
 }
 
+/// A locale path segment such as `de`, `en-us` or `pt-BR`.
+pub fn is_locale_token(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('-').collect();
+    match parts.as_slice() {
+        [lang] if lang.len() == 2 => lang.chars().all(|c| c.is_ascii_alphabetic()),
+        [lang, region] if lang.len() == 2 && (region.len() == 2 || region.len() == 3) => {
+            lang.chars().all(|c| c.is_ascii_alphabetic())
+                && region.chars().all(|c| c.is_ascii_alphabetic())
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,9 @@ use scraper::Selector;
 use serde::Serialize;
 use std::collections::HashMap;
 
+use crate::checks::page_weight::{self, PageWeight};
+use crate::config::Config;
+use crate::discovery::crawl::HttpResponse;
 use crate::discovery::SiteIndex;
 
 /// Properties collected for a single page.
@@ -25,6 +28,12 @@ pub struct PageProperties {
     pub json_ld_types: Vec<String>,
     pub has_skip_link: bool,
     pub noindex: bool,
+    /// Full page weight, when `page_weight.enabled` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_weight: Option<PageWeight>,
+    /// Status, redirect hops and response headers, in crawl mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpResponse>,
 }
 
 /// Aggregate statistics across all pages.
@@ -53,7 +62,12 @@ pub struct PageOverview {
 }
 
 /// Collect page properties from all pages in the site index.
-pub fn collect(index: &SiteIndex) -> PageOverview {
+pub fn collect(index: &SiteIndex, config: &Config) -> PageOverview {
+    let weights = if config.page_weight.enabled {
+        page_weight::measure(index)
+    } else {
+        HashMap::new()
+    };
     let mut pages: Vec<PageProperties> = index
         .pages
         .par_iter()
@@ -162,6 +176,8 @@ pub fn collect(index: &SiteIndex) -> PageOverview {
                 json_ld_types,
                 has_skip_link,
                 noindex: page.noindex,
+                page_weight: weights.get(&page.rel_path).cloned(),
+                http: page.http.clone(),
             }
         })
         .collect();
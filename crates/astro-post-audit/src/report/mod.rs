@@ -570,6 +570,48 @@ impl Reporter {
             );
         }
 
+        // Page weight (only when page_weight.enabled)
+        if overview.pages.iter().any(|p| p.page_weight.is_some()) {
+            println!("\n{}", "Page Weight (brotli)".bold());
+            let header = format!(
+                "  {:<width$}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  Reqs",
+                "File",
+                "Total",
+                "HTML",
+                "JS",
+                "CSS",
+                "Images",
+                "Fonts",
+                "Iframes",
+                width = max_file_len
+            );
+            println!("{}", header.dimmed());
+            let kb = |b: u64| format!("{:.1}KB", b as f64 / 1024.0);
+            for p in &overview.pages {
+                let Some(w) = &p.page_weight else {
+                    continue;
+                };
+                let file_display = if p.file.len() > max_file_len {
+                    format!("…{}", &p.file[p.file.len() - max_file_len + 1..])
+                } else {
+                    p.file.clone()
+                };
+                println!(
+                    "  {:<width$}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  {:>4}",
+                    file_display,
+                    kb(w.total.brotli),
+                    kb(w.document.brotli),
+                    kb(w.scripts.brotli),
+                    kb(w.styles.brotli),
+                    kb(w.images.brotli),
+                    kb(w.fonts.brotli),
+                    kb(w.iframes.brotli),
+                    w.requests,
+                    width = max_file_len
+                );
+            }
+        }
+
         // Summary
         println!();
         let stat = |label: &str, count: usize, total: usize| {
@@ -0,0 +1,386 @@
+//! robots.txt parsing and matching per RFC 9309.
+//!
+//! Groups start with one or more `User-agent` lines and run until the next
+//! `User-agent` line that follows a rule; blank lines and comments do not end
+//! a group. A crawler obeys every group naming its product token (merged), or
+//! else the merged `*` groups. Among the rules of that group the longest
+//! matching pattern wins, and `Allow` wins a tie.
+
+use std::path::Path;
+
+/// Bytes of robots.txt a crawler is required to parse (RFC 9309 §2.5).
+/// Google ignores everything past this limit.
+pub const MAX_SIZE: usize = 500 * 1024;
+
+/// Search engine crawlers checked for coverage alongside the AI bots.
+pub const SEARCH_BOTS: &[&str] = &[
+    "Googlebot",
+    "Bingbot",
+    "DuckDuckBot",
+    "Applebot",
+    "YandexBot",
+    "Baiduspider",
+];
+
+/// A parsed robots.txt.
+#[derive(Debug, Clone, Default)]
+pub struct Robots {
+    pub groups: Vec<Group>,
+    pub sitemaps: Vec<String>,
+    /// The file exceeded `MAX_SIZE`; lines past the limit were not parsed.
+    pub truncated: bool,
+}
+
+/// One group: the user-agents it names and the rules that follow them.
+#[derive(Debug, Clone, Default)]
+pub struct Group {
+    /// Lower-cased product tokens, `*` for the wildcard group.
+    pub agents: Vec<String>,
+    pub rules: Vec<Rule>,
+    pub crawl_delay: Option<f64>,
+    /// 1-based line of the first `User-agent` line.
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub allow: bool,
+    /// Pattern with percent-encoding normalized; may contain `*` and a
+    /// trailing `$`. Never empty (`Disallow:` with no value is dropped).
+    pub pattern: String,
+    pub line: usize,
+}
+
+impl Rule {
+    /// `Allow: /x` / `Disallow: /x`, as written in robots.txt.
+    pub fn directive(&self) -> String {
+        let key = if self.allow { "Allow" } else { "Disallow" };
+        format!("{key}: {}", self.pattern)
+    }
+}
+
+/// The outcome of matching a path for a user-agent.
+#[derive(Debug, Clone)]
+pub struct Verdict<'a> {
+    pub allowed: bool,
+    /// The rule that decided; None when no rule matched (allowed by default).
+    pub rule: Option<&'a Rule>,
+    /// The groups whose rules applied; empty when no group matched at all.
+    pub groups: Vec<&'a Group>,
+}
+
+impl Robots {
+    /// Read and parse `robots.txt` at the dist root, if present.
+    pub fn load(dist_path: &Path) -> Option<Robots> {
+        let bytes = std::fs::read(dist_path.join("robots.txt")).ok()?;
+        Some(Robots::parse(&String::from_utf8_lossy(&bytes)))
+    }
+
+    pub fn parse(content: &str) -> Robots {
+        let mut robots = Robots::default();
+        let mut body = content.strip_prefix('\u{feff}').unwrap_or(content);
+        if body.len() > MAX_SIZE {
+            let mut end = MAX_SIZE;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            // A cut-off last line could turn `Disallow: /private/` into
+            // `Disallow: /p`; drop it.
+            body = body[..end].rsplit_once('\n').map_or("", |(head, _)| head);
+            robots.truncated = true;
+        }
+
+        // Whether the current group has seen a rule yet: a User-agent line
+        // after a rule starts a new group, consecutive ones share a group.
+        let mut in_rules = true;
+        for (i, raw) in body.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        robots.groups.push(Group {
+                            line: i + 1,
+                            ..Group::default()
+                        });
+                        in_rules = false;
+                    }
+                    let agent = if value == "*" {
+                        "*".to_string()
+                    } else {
+                        product_token(value)
+                    };
+                    if let Some(group) = robots.groups.last_mut() {
+                        if !agent.is_empty() {
+                            group.agents.push(agent);
+                        }
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // Rules before the first User-agent belong to no group.
+                    let Some(group) = robots.groups.last_mut() else {
+                        continue;
+                    };
+                    if !value.is_empty() {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: normalize_octets(value),
+                            line: i + 1,
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    if let (Some(group), Ok(delay)) =
+                        (robots.groups.last_mut(), value.parse::<f64>())
+                    {
+                        group.crawl_delay = Some(delay);
+                    }
+                }
+                "sitemap" if !value.is_empty() => robots.sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+        robots
+    }
+
+    /// Groups that name `user_agent`'s product token; None when the crawler
+    /// falls back to the `*` groups.
+    pub fn specific_groups(&self, user_agent: &str) -> Option<Vec<&Group>> {
+        let token = product_token(user_agent);
+        let groups: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|g| !token.is_empty() && g.agents.contains(&token))
+            .collect();
+        (!groups.is_empty()).then_some(groups)
+    }
+
+    /// The groups `user_agent` obeys: its own, merged, or else the `*` groups.
+    /// Pass `*` for a crawler without a group of its own.
+    pub fn groups_for(&self, user_agent: &str) -> Vec<&Group> {
+        if user_agent != "*" {
+            if let Some(groups) = self.specific_groups(user_agent) {
+                return groups;
+            }
+        }
+        self.groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == "*"))
+            .collect()
+    }
+
+    /// Decide whether `user_agent` may fetch `path` (path plus optional query).
+    pub fn check(&self, user_agent: &str, path: &str) -> Verdict<'_> {
+        let groups = self.groups_for(user_agent);
+        // robots.txt itself is always allowed (RFC 9309 §2.2.2).
+        if path == "/robots.txt" {
+            return Verdict {
+                allowed: true,
+                rule: None,
+                groups,
+            };
+        }
+        let path = normalize_octets(path);
+        let mut best: Option<&Rule> = None;
+        for rule in groups.iter().flat_map(|g| &g.rules) {
+            if !pattern_matches(&rule.pattern, &path) {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some(b) => {
+                    rule.pattern.len() > b.pattern.len()
+                        || (rule.pattern.len() == b.pattern.len() && rule.allow && !b.allow)
+                }
+            };
+            if better {
+                best = Some(rule);
+            }
+        }
+        Verdict {
+            allowed: best.is_none_or(|r| r.allow),
+            rule: best,
+            groups,
+        }
+    }
+
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        self.check(user_agent, path).allowed
+    }
+}
+
+/// The product token of a user-agent: its leading run of letters, `_` and `-`,
+/// lower-cased (`Googlebot/2.1` → `googlebot`).
+pub fn product_token(user_agent: &str) -> String {
+    user_agent
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic() || *c == '_' || *c == '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Percent-encode non-ASCII, space and control octets and upper-case existing
+/// escapes, so `/café`, `/caf%c3%a9` and `/caf%C3%A9` compare equal.
+fn normalize_octets(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            out.push('%');
+            out.push(bytes[i + 1].to_ascii_uppercase() as char);
+            out.push(bytes[i + 2].to_ascii_uppercase() as char);
+            i += 3;
+            continue;
+        }
+        if b.is_ascii_graphic() {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Match a robots pattern against a path from its start. `*` matches any run
+/// of octets; a trailing `$` anchors the end, otherwise any suffix matches.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pat, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p.as_bytes(), true),
+        None => (pattern.as_bytes(), false),
+    };
+    let text = path.as_bytes();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+    loop {
+        if p == pat.len() && (!anchored || t == text.len()) {
+            return true;
+        }
+        if p < pat.len() && pat[p] == b'*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if p < pat.len() && t < text.len() && pat[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((sp, st)) = star.filter(|&(_, st)| st < text.len()) {
+            star = Some((sp, st + 1));
+            p = sp;
+            t = st + 1;
+        } else {
+            return false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_merge_and_fall_back_to_wildcard() {
+        let robots = Robots::parse(
+            "User-agent: *\nDisallow: /private/\n\n\
+             User-agent: Googlebot\nUser-agent: Bingbot/2.0\nDisallow: /a/\n\n\
+             # comment between\nUser-agent: googlebot\nDisallow: /b/\n",
+        );
+        assert_eq!(robots.groups.len(), 3);
+        assert_eq!(robots.groups[1].agents, ["googlebot", "bingbot"]);
+
+        assert!(!robots.is_allowed("Googlebot", "/a/x"));
+        assert!(!robots.is_allowed("Googlebot/2.1", "/b/"));
+        // Googlebot's own groups replace the * group entirely.
+        assert!(robots.is_allowed("Googlebot", "/private/"));
+        assert!(robots.is_allowed("Bingbot", "/b/"));
+        assert!(!robots.is_allowed("GPTBot", "/private/"));
+        assert!(!robots.is_allowed("*", "/private/x"));
+        // Googlebot-Image is a different product token.
+        assert!(robots.is_allowed("Googlebot-Image", "/a/"));
+    }
+
+    #[test]
+    fn blank_lines_do_not_end_a_group() {
+        let robots = Robots::parse("User-agent: *\n\nDisallow: /x/\n");
+        assert_eq!(robots.groups.len(), 1);
+        assert!(!robots.is_allowed("*", "/x/"));
+    }
+
+    #[test]
+    fn longest_match_wins_and_allow_wins_ties() {
+        let robots = Robots::parse(
+            "User-agent: *\nDisallow: /blog/\nAllow: /blog/public/\nDisallow: /tie\nAllow: /tie\n",
+        );
+        assert!(!robots.is_allowed("*", "/blog/post/"));
+        assert!(robots.is_allowed("*", "/blog/public/post/"));
+        assert!(robots.is_allowed("*", "/tie"));
+        let verdict = robots.check("*", "/blog/x/");
+        assert_eq!(verdict.rule.unwrap().line, 2);
+    }
+
+    #[test]
+    fn wildcards_and_end_anchor() {
+        assert!(pattern_matches("/*.pdf$", "/docs/a.pdf"));
+        assert!(!pattern_matches("/*.pdf$", "/docs/a.pdf?x=1"));
+        assert!(pattern_matches("/a*b$", "/axbxb"));
+        assert!(pattern_matches("/fish*", "/fish.html"));
+        assert!(pattern_matches("/fish", "/fishheads/yummy.html"));
+        assert!(!pattern_matches("/fish", "/Fish.asp"));
+        assert!(pattern_matches("/*?", "/page?id=1"));
+        assert!(pattern_matches("/$", "/"));
+        assert!(!pattern_matches("/$", "/a"));
+    }
+
+    #[test]
+    fn empty_disallow_allows_everything() {
+        let robots = Robots::parse("User-agent: *\nDisallow:\n");
+        assert!(robots.groups[0].rules.is_empty());
+        assert!(robots.is_allowed("*", "/"));
+    }
+
+    #[test]
+    fn percent_encoding_is_normalized() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /café/\n");
+        assert!(!robots.is_allowed("*", "/caf%c3%a9/menu"));
+        assert!(!robots.is_allowed("*", "/café/menu"));
+    }
+
+    #[test]
+    fn robots_txt_is_always_allowed() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /\n");
+        assert!(robots.is_allowed("*", "/robots.txt"));
+    }
+
+    #[test]
+    fn content_past_the_size_limit_is_ignored() {
+        let mut content = String::from("User-agent: *\n");
+        while content.len() < MAX_SIZE {
+            content.push_str("Disallow: /padding/\n");
+        }
+        content.push_str("Disallow: /late/\nSitemap: https://example.com/sitemap.xml\n");
+        let robots = Robots::parse(&content);
+        assert!(robots.truncated);
+        assert!(robots.is_allowed("*", "/late/"));
+        assert!(robots.sitemaps.is_empty());
+    }
+
+    #[test]
+    fn sitemaps_and_crawl_delay() {
+        let robots = Robots::parse(
+            "Sitemap: https://example.com/a.xml\nUser-agent: *\nCrawl-delay: 20\nsitemap: https://example.com/b.xml\n",
+        );
+        assert_eq!(robots.sitemaps.len(), 2);
+        assert_eq!(robots.groups[0].crawl_delay, Some(20.0));
+    }
+}
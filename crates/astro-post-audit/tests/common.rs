@@ -48,6 +48,19 @@ pub fn run_audit_json(dist_path: &Path, config_json: &str) -> (serde_json::Value
     (json, code)
 }
 
+/// Run the audit and keep only findings whose rule ID starts with `prefix`
+/// (e.g. "secrets/").
+pub fn findings_with_prefix(dir: &Path, config_json: &str, prefix: &str) -> Vec<serde_json::Value> {
+    let (json, _) = run_audit_json(dir, config_json);
+    json["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["rule_id"].as_str().unwrap().starts_with(prefix))
+        .cloned()
+        .collect()
+}
+
 /// Create a minimal valid page in a temp dir.
 pub fn write_valid_page(dir: &Path, rel_path: &str, title: &str, h1: &str, canonical_path: &str) {
     let full = dir.join(rel_path);
@@ -75,3 +88,84 @@ pub fn write_valid_page(dir: &Path, rel_path: &str, title: &str, h1: &str, canon
     )
     .unwrap();
 }
+
+/// A canned response for `serve`: status, extra headers and body.
+#[derive(Clone)]
+pub struct Route {
+    pub path: &'static str,
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Route {
+    pub fn status(path: &'static str, status: u16) -> Self {
+        Self {
+            path,
+            status,
+            headers: Vec::new(),
+            body: if status == 404 {
+                b"Not found".to_vec()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    pub fn html(path: &'static str, body: &str) -> Self {
+        Self {
+            path,
+            status: 200,
+            headers: vec![("Content-Type", "text/html; charset=utf-8".into())],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn redirect(path: &'static str, status: u16, location: &str) -> Self {
+        Self {
+            path,
+            status,
+            headers: vec![("Location", location.to_string())],
+            body: Vec::new(),
+        }
+    }
+}
+
+/// Serve fixed routes on a local port (a stand-in for `astro preview`) and
+/// return the base URL. Unknown paths answer 404. The server lives until the
+/// test process exits.
+pub fn serve(routes: Vec<Route>) -> String {
+    serve_with(move |_, path| {
+        routes
+            .iter()
+            .find(|r| r.path == path)
+            .cloned()
+            .unwrap_or_else(|| Route::status("", 404))
+    })
+}
+
+/// Like `serve`, but every response is built by `respond` from the request
+/// method and path, so a test can count requests or answer differently over
+/// time (rate limits, flaky hosts).
+pub fn serve_with<F>(respond: F) -> String
+where
+    F: Fn(&str, &str) -> Route + Send + 'static,
+{
+    let server = tiny_http::Server::http("127.0.0.1:0").expect("failed to bind");
+    let port = server.server_addr().to_ip().unwrap().port();
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or("/").to_string();
+            let route = respond(request.method().as_str(), &path);
+            let mut response =
+                tiny_http::Response::from_data(route.body).with_status_code(route.status);
+            for (name, value) in &route.headers {
+                response.add_header(
+                    tiny_http::Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap(),
+                );
+            }
+            let _ = request.respond(response);
+        }
+    });
+    format!("http://127.0.0.1:{port}")
+}
@@ -67,7 +67,7 @@ export interface RulesConfig {
   };
   /** Sitemap cross-reference checks. */
   sitemap?: {
-    /** `sitemap.xml` must exist in `dist/`. @default false */
+    /** A sitemap must exist in `dist/` (`sitemap.xml`, `sitemap-index.xml`, or one listed in robots.txt). @default false */
     require?: boolean;
     /** Canonical URLs should appear in the sitemap. @default true */
     canonical_must_be_in_sitemap?: boolean;
@@ -75,6 +75,12 @@ export interface RulesConfig {
     forbid_noncanonical_in_sitemap?: boolean;
     /** Every sitemap URL must correspond to a page in `dist/`. @default true */
     entries_must_exist_in_dist?: boolean;
+    /** Validate `lastmod` (W3C datetime, not in the future), `changefreq` and `priority` values. @default true */
+    check_entry_metadata?: boolean;
+    /** Compare `xhtml:link` hreflang alternates with each page's HTML hreflang links. @default true */
+    check_alternates?: boolean;
+    /** Check `image:`/`video:` extension URLs against files in `dist/` and required video members. @default true */
+    check_media?: boolean;
   };
   /** `robots.txt` file checks. */
   robots_txt?: {
@@ -92,6 +98,8 @@ export interface RulesConfig {
     check_noindex_contradiction?: boolean;
     /** Warn when a URL listed in the sitemap is blocked by robots.txt. @default false */
     check_sitemap_blocked?: boolean;
+    /** Report, per search and AI crawler, which indexable pages robots.txt blocks. @default false */
+    bot_coverage?: boolean;
   };
   /** Image HTML attribute checks for CLS prevention and responsive image best practices. */
   images?: {
@@ -103,6 +111,12 @@ export interface RulesConfig {
     info_missing_srcset?: boolean;
     /** Info if `<img>` uses a legacy format (`.jpg`, `.png`, `.gif`) — suggests WebP/AVIF. @default false */
     format_hints?: boolean;
+    /** Read the real pixel size of local images: oversized files, `width`/`height` with the wrong aspect ratio, wrong `srcset` `w` descriptors, large images in `public/`. @default false */
+    check_intrinsic_size?: boolean;
+    /** Warn when an image is wider than its rendered slot (largest px `sizes` entry or `width` attribute) by more than this factor. @default 3 */
+    max_oversize_ratio?: number;
+    /** Warn on raster images over this size (KB) served from `public/` instead of `_astro/`. @default 200 */
+    public_max_kb?: number;
   };
   /** AI visibility scoring — checks static signals that influence AI search citation probability. @default false */
   ai_visibility?: {
@@ -243,6 +257,8 @@ export interface RulesConfig {
     check_mixed_content?: boolean;
     /** Warn on inline `<script>` tags. @default false */
     warn_inline_scripts?: boolean;
+    /** Check HSTS, X-Content-Type-Options and CSP response headers of crawled pages. @default false */
+    check_headers?: boolean;
   };
   /** Duplicate content detection. */
   content_quality?: {
@@ -274,6 +290,24 @@ export interface RulesConfig {
     allow_domains?: string[];
     /** Skip links to these domains. */
     block_domains?: string[];
+    /** JSON file that keeps results between runs (e.g. `node_modules/.cache/astro-post-audit/links.json`). Without it every URL is requested on every run. */
+    cache_path?: string;
+    /** Reuse a cached OK result for this many seconds. @default 604800 */
+    cache_ttl_ok_secs?: number;
+    /** Reuse a cached broken result for this many seconds. @default 3600 */
+    cache_ttl_broken_secs?: number;
+    /** Maximum concurrent requests to one host. @default 2 */
+    max_per_host?: number;
+    /** Minimum delay between request starts to the same host, in milliseconds. @default 0 */
+    host_delay_ms?: number;
+    /** Retries after a timeout, connection error, 408, 429 or 5xx. @default 2 */
+    retries?: number;
+    /** Wait before the first retry in milliseconds, doubled for each further retry. A `Retry-After` header (up to 60s) takes precedence. @default 1000 */
+    backoff_ms?: number;
+    /** Start no requests after this many seconds; unchecked links are reported as info. 0 = no limit. @default 0 */
+    time_budget_secs?: number;
+    /** Links broken in only one of this many recent runs are reported as flaky (info) instead of broken. Needs `cache_path`; 0 = off. @default 3 */
+    quarantine_runs?: number;
   };
   /** I18n consistency audit across route locale, lang, hreflang, and canonical signals. */
   i18n_audit?: {
@@ -296,16 +330,24 @@ export interface RulesConfig {
     enabled?: boolean;
     /** Enable GDPR/DSGVO third-party transfer checks (Google Fonts, YouTube, Maps, public CDNs, external images). @default false */
     gdpr?: boolean;
+    /** Check Referrer-Policy and Permissions-Policy response headers of crawled pages. @default false */
+    check_headers?: boolean;
+    /** Report plain-text email addresses, phone numbers and IBANs (text, attributes, JSON-LD, comments) and HTML comments with internal notes. @default false */
+    pii?: boolean;
+    /** Values the PII scan accepts, e.g. the contact address in the imprint. Glob patterns (`*@example.com`); phone numbers are compared by digits. @default [] */
+    pii_allow?: string[];
   };
   /** Cross-page structured-data graph consistency checks. */
   structured_data_graph?: {
     /** Enable cross-page JSON-LD consistency checks in dist output. @default false */
     enabled?: boolean;
   };
-  /** Static meta-refresh redirect analysis (links to redirects, chains, loops). */
+  /** Redirect analysis: meta-refresh pages and hosting config (links to redirects, chains, loops, missing targets, shadowed rules). */
   redirects?: {
     /** Enable redirect analysis in dist output. @default false */
     enabled?: boolean;
+    /** Read `_redirects` / `_headers` from dist and `netlify.toml` / `vercel.json` from the project root. @default true */
+    hosting_config?: boolean;
   };
   /** Client-side JavaScript bloat detection per route. */
   js_bloat?: {
@@ -314,6 +356,163 @@ export interface RulesConfig {
     /** Warn when a route's total local JS exceeds this size in KB. @default 100 */
     max_kb?: number;
   };
+  /** Full page weight per route: HTML, JS, CSS, images, fonts, iframes (raw/gzip/brotli). */
+  page_weight?: {
+    /** Report page weight per route and in the page overview. @default false */
+    enabled?: boolean;
+    /** Warn when a route's brotli transfer size exceeds this size in KB. No budget when unset. */
+    max_kb?: number;
+  };
+  /** Unused CSS detection: selectors in local stylesheets that match no element on the pages loading them. */
+  unused_css?: {
+    /** Enable unused CSS detection. @default false */
+    enabled?: boolean;
+    /** Class names (glob patterns, e.g. "is-open", "swiper-*") added at runtime by JavaScript; selectors using them are never reported. @default [] */
+    safelist?: string[];
+  };
+  /** Stylesheet content audit for local .css files and inline <style> blocks. */
+  stylesheets?: {
+    /** Enable the stylesheet content audit. @default false */
+    enabled?: boolean;
+    /** Error on url() references to files missing from dist. @default true */
+    check_urls?: boolean;
+    /** Warn on @import, which serializes stylesheet downloads. @default true */
+    check_imports?: boolean;
+    /** Warn on @font-face rules without font-display. @default true */
+    require_font_display?: boolean;
+    /** Warn on http:// URLs inside url(). @default true */
+    check_mixed_content?: boolean;
+  };
+  /** JavaScript module graph: entry scripts, island component/renderer URLs, modulepreload and imports. */
+  module_graph?: {
+    /** Report total JS per route and per island, and duplicated chunks. @default false */
+    enabled?: boolean;
+    /** Warn when a route's eagerly loaded JS exceeds this size in KB. No budget when unset. */
+    max_route_kb?: number;
+    /** Warn when a single island's JS exceeds this size in KB. No budget when unset. */
+    max_island_kb?: number;
+  };
+  /** Web app manifest, favicon set and theme-color. */
+  manifest?: {
+    /** Audit the web app manifest, favicon set and theme-color. @default false */
+    enabled?: boolean;
+    /** Warn on pages without `<link rel="icon">` when there is no `/favicon.ico`. @default true */
+    require_favicon?: boolean;
+    /** Warn on pages without `<link rel="apple-touch-icon">` when there is no `/apple-touch-icon.png`. @default true */
+    require_apple_touch_icon?: boolean;
+    /** Info on pages without `<meta name="theme-color">`. @default true */
+    require_theme_color?: boolean;
+    /** Warn on pages linking a different manifest or icon set than most pages. @default true */
+    check_consistency?: boolean;
+  };
+  /** RSS, Atom and JSON feeds (e.g. `@astrojs/rss`). */
+  feeds?: {
+    /** Discover and audit RSS, Atom and JSON feeds. @default false */
+    enabled?: boolean;
+    /** Route globs of posts that must appear in a feed (`*` matches one path segment). Empty disables the check. @default ["/blog/*", "/posts/*"] */
+    post_patterns?: string[];
+    /** Warn when a feed item's title does not match the linked page's `<title>`. @default true */
+    check_titles?: boolean;
+  };
+  /** Content-Security-Policy evaluation against every page. */
+  csp?: {
+    /** Check every page against its Content-Security-Policy (from `_headers`, host config or `<meta http-equiv>`). @default false */
+    enabled?: boolean;
+    /** Policy to evaluate instead of the deployed one, e.g. a draft before shipping it. */
+    policy?: string;
+    /** Warn about pages served without a policy. @default true */
+    require_policy?: boolean;
+    /** Emit a suggested policy with `sha256-` hashes for every inline script and style. @default false */
+    suggest_policy?: boolean;
+  };
+  /** Subresource Integrity verification for scripts and stylesheets. */
+  sri?: {
+    /** Verify `integrity` attributes on scripts and stylesheets against the files in dist. @default false */
+    enabled?: boolean;
+    /** Report the correct integrity value for every local script and stylesheet. @default false */
+    report_hashes?: boolean;
+    /** Algorithm for reported values. @default "sha384" */
+    report_algorithm?: 'sha256' | 'sha384' | 'sha512';
+  };
+  /**
+   * Crawl a running server instead of reading HTML from dist, so SSR and
+   * hybrid routes are audited. The server must already be running.
+   */
+  crawl?: {
+    /** Base URL to crawl, e.g. `http://localhost:4321` for `astro preview`. Unset = read dist. */
+    url?: string;
+    /** Stop after requesting this many URLs. @default 500 */
+    max_pages?: number;
+    /** Follow links at most this many clicks away from `/` and the sitemap. @default 10 */
+    max_depth?: number;
+    /** Timeout per request in milliseconds. @default 10000 */
+    timeout_ms?: number;
+    /** Also start from every URL in the sitemap (dist and server). @default true */
+    follow_sitemap?: boolean;
+  };
+  /** Response header checks for crawled pages and their assets (requires `crawl.url`). */
+  http_headers?: {
+    /** Check caching, compression, Content-Type and X-Robots-Tag headers. @default false */
+    enabled?: boolean;
+    /** Also request local scripts, stylesheets, images and fonts referenced by crawled pages. @default true */
+    check_assets?: boolean;
+    /** Maximum number of assets requested. @default 200 */
+    max_assets?: number;
+    /** Warn when HTML may be cached longer than this many seconds. @default 3600 */
+    html_max_age?: number;
+    /** Minimum max-age in seconds for hashed `_astro/` assets. @default 31536000 */
+    asset_min_max_age?: number;
+    /** Text responses larger than this many bytes should be compressed. @default 1024 */
+    compression_min_bytes?: number;
+  };
+  /** Dedicated audit of 404 and other error pages. */
+  error_pages?: {
+    /** Check error pages for sitemap entries, self-canonicals, a way home and relative asset URLs; probe for soft 404s in crawl mode. @default false */
+    enabled?: boolean;
+  };
+  /** Validation of paginate() series such as /blog/, /blog/2/, /blog/3/. */
+  pagination?: {
+    /** Check series for gaps, broken or one-sided rel=prev/next, non-self canonicals, duplicate titles and orphaned pages. @default false */
+    enabled?: boolean;
+  };
+  /** `llms.txt` validation and draft generation. */
+  llms_txt?: {
+    /** Validate dist/llms.txt: format, internal links (also in llms-full.txt), coverage. @default false */
+    enabled?: boolean;
+    /** Warn when llms.txt is missing. @default false */
+    require?: boolean;
+    /** Warn about indexable pages linked from the home page that llms.txt does not list. @default true */
+    check_coverage?: boolean;
+    /** Write a draft llms.txt built from page titles, descriptions and URL sections to this path. Works without `enabled`. */
+    generate?: string;
+  };
+  /** `/.well-known/security.txt` validation (RFC 9116). */
+  security_txt?: {
+    /** Require /.well-known/security.txt and validate it against RFC 9116. @default false */
+    enabled?: boolean;
+    /** Warn when Expires is less than this many days away. @default 30 */
+    expires_warn_days?: number;
+  };
+  /** Credential scanning of every text file in dist (HTML, JS, CSS, JSON, source maps). */
+  secrets?: {
+    /** Scan built output for AWS keys, Stripe secret keys, GitHub tokens, private keys, JWTs and high-entropy secret assignments. @default false */
+    enabled?: boolean;
+    /** Extra patterns scanned for in addition to the bundled ruleset; findings are reported as `secrets/<id>`. With a capture group, only the group is reported. @default [] */
+    patterns?: Array<{ id: string; regex: string }>;
+    /** Regexes for matched values to ignore, e.g. publishable keys that are meant to be public. @default [] */
+    allow?: string[];
+  };
+  /** Deploy hygiene: development leftovers and source maps anywhere in dist, not just HTML. */
+  deploy_hygiene?: {
+    /** Walk all of dist for files that should not be deployed: dotfiles, backups, VCS and editor leftovers, source maps. @default false */
+    enabled?: boolean;
+    /** Extra glob patterns to flag, on top of `.env*`, `.git`, `node_modules`, `.DS_Store`, `*.bak`, swap files and similar. Patterns without `/` match a file or directory name anywhere in dist. @default [] */
+    deny?: string[];
+    /** Glob patterns exempt from the deny-list, e.g. an `.env.example` published on purpose. @default [] */
+    allow?: string[];
+    /** Report source maps that embed original sources, and `sourceMappingURL` comments pointing at missing or deployed maps. @default true */
+    check_source_maps?: boolean;
+  };
   /**
    * Cross-check `src/content/` collection items against generated pages.
    * Requires the project root, which the integration passes automatically.
@@ -321,6 +520,8 @@ export interface RulesConfig {
   content_sync?: {
     /** Warn about content items with no corresponding build page. @default false */
     enabled?: boolean;
+    /** Report items marked `draft: true` or dated in the future (`pubDate`, `publishDate`, else `date` at lower confidence) that still produced a page, sitemap entry or feed item. @default true */
+    check_drafts?: boolean;
   };
   /** Native HTML5 syntax validation using the html5ever tokenizer (offline). */
   html_validation?: {
@@ -363,7 +564,7 @@ export interface GoLiveConfig {
    * Only set this when the go-live target intentionally differs from Astro `site`.
    */
   expectedSite?: string;
-  /** Domains that must not appear in canonical URLs, sitemaps, OG tags, or absolute links. */
+  /** Domains that must not appear in canonical URLs, sitemaps, OG tags, absolute links, or local JS, CSS, JSON and XML files. */
   forbiddenDomains?: string[];
 }
 
@@ -757,7 +958,7 @@ export default function postAudit(
 
         const args: string[] = [distPath, "--config-stdin"];
 
-        // Info: sitemap checks active but no sitemap.xml in dist
+        // Info: sitemap checks active but no sitemap in dist
         const sitemapChecksActive =
           resolvedRules.sitemap?.canonical_must_be_in_sitemap !== false ||
           resolvedRules.sitemap?.entries_must_exist_in_dist !== false;
@@ -767,7 +968,7 @@ export default function postAudit(
           !deps.existsSync(join(distPath, "sitemap-index.xml"))
         ) {
           logger.info(
-            "No sitemap.xml found in dist/. Sitemap checks are limited. Add @astrojs/sitemap or @casoon/astro-sitemap to generate a sitemap.",
+            "No sitemap found in dist/. Sitemap checks are limited. Add @astrojs/sitemap or @casoon/astro-sitemap to generate a sitemap.",
           );
         }
 
@@ -812,8 +1013,15 @@ export default function postAudit(
           stdinConfig.hints = { source_files: true };
           stdinConfig.project_root = rootDir;
         }
-        // content_sync needs the project root to locate src/content/.
-        if (resolvedRules.content_sync?.enabled && rootDir && !stdinConfig.project_root) {
+        // content_sync needs the project root to locate src/content/;
+        // redirects and csp read netlify.toml / vercel.json from it.
+        if (
+          (resolvedRules.content_sync?.enabled ||
+            resolvedRules.redirects?.enabled ||
+            resolvedRules.csp?.enabled) &&
+          rootDir &&
+          !stdinConfig.project_root
+        ) {
           stdinConfig.project_root = rootDir;
         }
         if (options.maxErrors != null)